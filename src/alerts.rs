@@ -0,0 +1,212 @@
+use std::collections::HashSet;
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+
+use crate::config::{AlertRule, BellConfig};
+use crate::display::{elapsed_since, format_cwd, format_duration};
+use crate::quiet::should_notify;
+use crate::session::Session;
+use crate::status::SessionStatus;
+use crate::transitions::Transition;
+
+/// 値をシェル用にクォートする（Windowsでは cmd 用、それ以外はシングルクォート）
+pub fn shell_quote(value: &str) -> String {
+    if cfg!(windows) {
+        cmd_quote(value)
+    } else {
+        format!("'{}'", value.replace('\'', r"'\''"))
+    }
+}
+
+/// 値を cmd /C 用にダブルクォートで囲む
+///
+/// & や | はクォートの中なら効かない。" はパスに使えないので除き、改行は空白にする。
+/// % は環境変数として展開されないよう "%" でクォートの外に出す（未定義の "名前" は展開されない）。
+fn cmd_quote(value: &str) -> String {
+    let value = value.replace('"', "").replace(['\r', '\n'], " ").replace('%', "\"%\"");
+    format!("\"{}\"", value)
+}
+
+/// コマンドテンプレートのプレースホルダをセッションの値で置換する（値はシェルクォート済み）
+pub fn expand_placeholders(template: &str, session: &Session) -> String {
+    template
+        .replace("{session_id}", &shell_quote(&session.session_id))
+        .replace("{cwd}", &shell_quote(&session.cwd))
+        .replace("{pane_id}", &shell_quote(&session.pane_id))
+        .replace(
+            "{reason}",
+            &shell_quote(session.waiting_reason.as_ref().map_or("", |r| r.name())),
+        )
+        .replace("{host}", &shell_quote(session.host.as_deref().unwrap_or("")))
+        .replace("{status}", &shell_quote(session.status.as_str()))
+        .replace("{elapsed}", &elapsed_since(session.updated).to_string())
+}
+
+/// デスクトップ通知を送る（macOSは通知センター、それ以外はnotify-send。無ければ何もしない）
+pub fn send_desktop_notification(title: &str, body: &str) {
+    let mut command = if cfg!(target_os = "macos") {
+        let mut command = Command::new("osascript");
+        command.args([
+            "-e",
+            "on run argv\n    display notification (item 2 of argv) with title (item 1 of argv)\nend run",
+            title,
+            body,
+        ]);
+        command
+    } else {
+        let mut command = Command::new("notify-send");
+        command.args(["--app-name", "claude-watch", title, body]);
+        command
+    };
+    let _ = command
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+}
+
+/// ターミナルのベルを鳴らす
+pub fn ring_bell() {
+    let mut stdout = io::stdout();
+    let _ = stdout.write_all(b"\x07");
+    let _ = stdout.flush();
+}
+
+/// 新しく承認待ちになったセッションがあればベル（と設定されたコマンド）で知らせる
+///
+/// 同時に複数のセッションが承認待ちになっても鳴らすのは1回だけ。
+pub fn ring_on_new_waiting(bell: &BellConfig, transitions: &[Transition]) {
+    if !bell.enabled {
+        return;
+    }
+    let Some(transition) = transitions
+        .iter()
+        .find(|t| t.to == Some(SessionStatus::Waiting) && should_notify(&t.session))
+    else {
+        return;
+    };
+    if bell.terminal {
+        ring_bell();
+    }
+    if let Some(ref command) = bell.command {
+        spawn_shell_command(&expand_placeholders(command, &transition.session));
+    }
+}
+
+/// シェルでコマンドを実行する引数（Windowsでは cmd /C、それ以外は sh -c）
+pub fn shell_args(command: &str) -> [&str; 3] {
+    if cfg!(windows) {
+        ["cmd", "/C", command]
+    } else {
+        ["sh", "-c", command]
+    }
+}
+
+/// シェルでコマンドを実行するCommand
+pub fn shell_command(command: &str) -> Command {
+    let [program, flag, command] = shell_args(command);
+    let mut cmd = Command::new(program);
+    cmd.args([flag, command]);
+    cmd
+}
+
+/// コマンドをバックグラウンドで実行する（完了は待たない）
+pub fn spawn_shell_command(command: &str) {
+    let _ = shell_command(command)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+}
+
+/// 一定時間同じステータスが続いたセッションに通知するルールエンジン
+pub struct AlertEngine {
+    rules: Vec<AlertRule>,
+    // 通知済みの (session_id, updated, ルール番号)。ステータスが更新されると updated が変わり再通知の対象になる
+    fired: HashSet<(String, u64, usize)>,
+}
+
+impl AlertEngine {
+    pub fn new(rules: Vec<AlertRule>) -> Self {
+        Self {
+            rules,
+            fired: HashSet::new(),
+        }
+    }
+
+    /// 現在のステータスが続く間、このセッションの通知を止める（確認済みにする）
+    pub fn acknowledge(&mut self, session: &Session) {
+        for idx in 0..self.rules.len() {
+            self.fired.insert((session.session_id.clone(), session.updated, idx));
+        }
+    }
+
+    /// セッション一覧を評価して通知を実行し、発火した通知の説明を返す
+    pub fn check(&mut self, sessions: &[Session]) -> Vec<String> {
+        // 状態が変わった（または消えた）セッションの通知済み記録を捨てる
+        self.fired.retain(|(session_id, updated, _)| {
+            sessions
+                .iter()
+                .any(|s| &s.session_id == session_id && s.updated == *updated)
+        });
+
+        let mut fired = Vec::new();
+        // スヌーズ中のセッションは通知しない
+        // 通知しない時間帯は記録もしないので、時間帯が明けてまだ続いていれば通知する
+        for session in sessions.iter().filter(|s| !s.snoozed && should_notify(s)) {
+            let elapsed = elapsed_since(session.updated);
+
+            for (idx, rule) in self.rules.iter().enumerate() {
+                if session.status != rule.status || elapsed < rule.after {
+                    continue;
+                }
+                let key = (session.session_id.clone(), session.updated, idx);
+                if !self.fired.insert(key) {
+                    continue;
+                }
+
+                // 承認待ちは理由（"Bash許可待ち" など）で知らせる
+                let label = match session.waiting_reason {
+                    Some(ref reason) => reason.label(),
+                    None => session.status.label().to_string(),
+                };
+                let message = format!(
+                    "{} {}が{}続いています",
+                    format_cwd(&session.cwd),
+                    label,
+                    format_duration(elapsed)
+                );
+
+                if rule.bell {
+                    ring_bell();
+                }
+                if rule.desktop {
+                    send_desktop_notification("claude-watch", &message);
+                }
+                if let Some(ref command) = rule.command {
+                    spawn_shell_command(&expand_placeholders(command, session));
+                }
+
+                fired.push(message);
+            }
+        }
+
+        fired
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cmd_quote_keeps_metacharacters_inside_quotes() {
+        assert_eq!(cmd_quote(r"C:\work\a&b|c"), r#""C:\work\a&b|c""#);
+        assert_eq!(cmd_quote("a\"&calc&\"b"), r#""a&calc&b""#);
+    }
+
+    #[test]
+    fn cmd_quote_moves_percent_out_of_quotes() {
+        assert_eq!(cmd_quote("%PATH%"), r#"""%"PATH"%"""#);
+        assert_eq!(cmd_quote("a\r\nb"), r#""a  b""#);
+    }
+}
@@ -0,0 +1,205 @@
+use anyhow::{anyhow, Context, Result};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+
+use crate::config::home_dir;
+use crate::display::glyph;
+use crate::hooks::register_spawned_pane;
+use crate::{iterm2, wezterm, window, windows_terminal};
+
+/// ペインの列挙・ジャンプ・起動を担うターミナル
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    WezTerm,
+    WindowsTerminal,
+    /// macOSのiTerm2（osascriptで操作）
+    ITerm2,
+    /// CLIの無い端末（ウィンドウのタイトルで探して前面に出すだけ）
+    Window,
+    /// マルチプレクサなし（ペインでの絞り込みとジャンプを行わない）
+    None,
+}
+
+static BACKEND: OnceLock<Backend> = OnceLock::new();
+
+// バックエンドが応答しなかった理由（応答すればクリアする）
+static UNAVAILABLE: Mutex<Option<String>> = Mutex::new(None);
+
+/// 起動時に使用するバックエンドを決める（以降は変更しない）
+pub fn init_backend(backend: Backend) {
+    let _ = BACKEND.set(backend);
+}
+
+pub fn current_backend() -> Backend {
+    BACKEND.get().copied().unwrap_or(Backend::WezTerm)
+}
+
+/// バックエンドが使えない場合はその理由
+pub fn backend_unavailable() -> Option<String> {
+    UNAVAILABLE.lock().ok()?.clone()
+}
+
+/// ジャンプなどペイン操作ができる状態か
+pub fn can_control_panes() -> bool {
+    current_backend() != Backend::None && backend_unavailable().is_none()
+}
+
+/// ペイン一覧を取得し、失敗した場合はバックエンドなしとして扱う
+///
+/// CLIが見つからない・応答しない環境（SSH先や素のターミナル）でも一覧表示を続けられるようにする。
+pub fn list_panes_or_degrade() -> Option<HashMap<String, Option<u32>>> {
+    let result = current_backend().list_panes();
+    let mut unavailable = UNAVAILABLE.lock().unwrap_or_else(|e| e.into_inner());
+    match result {
+        Ok(panes) => {
+            *unavailable = None;
+            panes
+        }
+        Err(e) => {
+            tracing::warn!("ペイン一覧を取得できません: {:#}", e);
+            *unavailable = Some(e.to_string());
+            None
+        }
+    }
+}
+
+impl Backend {
+    pub fn parse(name: &str) -> Result<Backend> {
+        match name {
+            "wezterm" => Ok(Backend::WezTerm),
+            "windows-terminal" | "wt" => Ok(Backend::WindowsTerminal),
+            "iterm2" | "iterm" => Ok(Backend::ITerm2),
+            "window" => Ok(Backend::Window),
+            "none" => Ok(Backend::None),
+            _ => Err(anyhow!(
+                "不明なバックエンド: {} (wezterm, windows-terminal, iterm2, window, none のいずれかを指定)",
+                name
+            )),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Backend::WezTerm => "wezterm",
+            Backend::WindowsTerminal => "windows-terminal",
+            Backend::ITerm2 => "iterm2",
+            Backend::Window => "window",
+            Backend::None => "none",
+        }
+    }
+
+    /// pane_id → フォアグラウンドプロセスのPID（ペインを列挙できないバックエンドはNone）
+    pub fn list_panes(self) -> Result<Option<HashMap<String, Option<u32>>>> {
+        match self {
+            Backend::WezTerm => wezterm::list_panes().map(Some),
+            Backend::ITerm2 => iterm2::list_panes().map(Some),
+            Backend::WindowsTerminal | Backend::Window | Backend::None => Ok(None),
+        }
+    }
+
+    pub fn activate_pane(self, pane_id: &str) -> Result<()> {
+        match self {
+            Backend::WezTerm => wezterm::activate_pane(pane_id),
+            Backend::WindowsTerminal => windows_terminal::focus_window(),
+            Backend::ITerm2 => iterm2::activate_pane(pane_id),
+            Backend::Window => window::focus_window(pane_id),
+            Backend::None => Err(no_backend_error()),
+        }
+    }
+
+    pub fn spawn_in_cwd(self, cwd: &str, command: &[&str]) -> Result<String> {
+        match self {
+            Backend::WezTerm => wezterm::spawn_in_cwd(cwd, command),
+            Backend::WindowsTerminal => windows_terminal::spawn_in_cwd(cwd, command),
+            Backend::ITerm2 => iterm2::spawn_in_cwd(cwd, command),
+            Backend::Window => Err(anyhow!("windowバックエンドは新しいペインの起動に対応していません")),
+            Backend::None => Err(no_backend_error()),
+        }
+    }
+
+    /// ペインに表示されている内容
+    pub fn get_text(self, pane_id: &str) -> Result<String> {
+        match self {
+            Backend::WezTerm => wezterm::get_text(pane_id),
+            Backend::WindowsTerminal => Err(anyhow!(
+                "Windows Terminalバックエンドはペインの内容の取得に対応していません"
+            )),
+            Backend::ITerm2 => iterm2::get_text(pane_id),
+            Backend::Window => Err(anyhow!("windowバックエンドはペインの内容の取得に対応していません")),
+            Backend::None => Err(no_backend_error()),
+        }
+    }
+
+    pub fn send_text(self, pane_id: &str, text: &str, no_paste: bool) -> Result<()> {
+        match self {
+            Backend::WezTerm => wezterm::send_text(pane_id, text, no_paste),
+            Backend::WindowsTerminal => Err(anyhow!(
+                "Windows Terminalバックエンドはテキスト送信に対応していません"
+            )),
+            Backend::ITerm2 => iterm2::send_text(pane_id, text),
+            Backend::Window => Err(anyhow!("windowバックエンドはテキスト送信に対応していません")),
+            Backend::None => Err(no_backend_error()),
+        }
+    }
+}
+
+fn no_backend_error() -> anyhow::Error {
+    anyhow!("バックエンドなしモードではペインを操作できません（--backend で指定してください）")
+}
+
+pub fn jump_to_pane(pane_id: &str) -> Result<()> {
+    current_backend().activate_pane(pane_id)?;
+    println!("{}Pane {} にジャンプしました", glyph("✅ ", ""), pane_id);
+    Ok(())
+}
+
+/// `claude --resume <session_id>` を新しいペインで起動する
+pub fn resume_session(cwd: &str, session_id: &str) -> Result<String> {
+    current_backend().spawn_in_cwd(cwd, &["claude", "--resume", session_id])
+}
+
+/// `new` に渡されたディレクトリを絶対パスにする（~ を展開し、ディレクトリでなければエラー）
+pub fn resolve_project_dir(path: &str) -> Result<String> {
+    let path = match path.strip_prefix('~') {
+        Some(rest) => home_dir()?.join(rest.trim_start_matches(['/', '\\'])),
+        None => PathBuf::from(path),
+    };
+    // canonicalize はWindowsで \\?\ が付くので使わない
+    let dir = std::path::absolute(&path).with_context(|| format!("パスを解決できません: {}", path.display()))?;
+    if !dir.is_dir() {
+        return Err(anyhow!("ディレクトリが見つかりません: {}", dir.display()));
+    }
+    Ok(dir.to_string_lossy().to_string())
+}
+
+/// 新しいペインで `claude` を起動し、フックより前に一覧に出るよう登録する
+pub fn new_session(cwd: &str) -> Result<String> {
+    let pane_id = current_backend().spawn_in_cwd(cwd, &["claude"])?;
+    // Windows Terminalは新しいタブの識別子を返さないので登録できない
+    if !pane_id.is_empty() {
+        register_spawned_pane(&pane_id, cwd)?;
+    }
+    Ok(pane_id)
+}
+
+/// ペインにCtrl-Cを送ってClaude Codeの処理を中断する
+pub fn interrupt_pane(pane_id: &str) -> Result<()> {
+    current_backend().send_text(pane_id, "\x03", true)
+}
+
+/// ペインにテキストを貼り付ける（submitならEnterも送ってプロンプトを送信する）
+pub fn send_prompt(pane_id: &str, text: &str, submit: bool) -> Result<()> {
+    // 改行を含んでも1つの入力になるようブラケットペーストで送る
+    current_backend().send_text(pane_id, text, false)?;
+    if submit {
+        current_backend().send_text(pane_id, "\r", true)?;
+    }
+    Ok(())
+}
+
+/// 承認待ちのペインに応答する（Enterで許可、Escで拒否）
+pub fn answer_permission(pane_id: &str, approve: bool) -> Result<()> {
+    let key = if approve { "\r" } else { "\x1b" };
+    current_backend().send_text(pane_id, key, true)
+}
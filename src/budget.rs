@@ -0,0 +1,191 @@
+use anyhow::{anyhow, Result};
+use std::collections::HashSet;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::alerts::{expand_placeholders, send_desktop_notification, spawn_shell_command};
+use crate::config::BudgetConfig;
+use crate::display::{format_cwd, glyph};
+use crate::quiet::{is_quiet_time, should_notify};
+use crate::session::Session;
+use crate::transcript::cost_today;
+
+// [budget]: トークン使用量から推定したコストを予算と比べ、注意・超過を一覧のバッジと通知で知らせる。
+// 1日の合計は今日更新されたトランスクリプトをすべて読むので、間隔をあけて集計し直す。
+
+/// 1日の合計を集計し直す間隔
+const DAILY_REFRESH: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BudgetLevel {
+    /// warn_ratio を超えた
+    Warn,
+    /// 予算を超えた
+    Over,
+}
+
+impl BudgetLevel {
+    pub fn icon(self) -> &'static str {
+        match self {
+            BudgetLevel::Warn => glyph("💸", "$"),
+            BudgetLevel::Over => glyph("🚨", "$!"),
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            BudgetLevel::Warn => "予算に近づいています",
+            BudgetLevel::Over => "予算超過",
+        }
+    }
+}
+
+/// 推定コストと予算
+#[derive(Debug, Clone, Copy)]
+pub struct BudgetStatus {
+    pub cost: f64,
+    pub budget: f64,
+    pub level: Option<BudgetLevel>,
+}
+
+impl BudgetStatus {
+    fn new(cost: f64, budget: f64, warn_ratio: f64) -> BudgetStatus {
+        let level = if cost >= budget {
+            Some(BudgetLevel::Over)
+        } else if cost >= budget * warn_ratio {
+            Some(BudgetLevel::Warn)
+        } else {
+            None
+        };
+        BudgetStatus { cost, budget, level }
+    }
+
+    /// "$4.12 / $5.00"
+    pub fn amounts(&self) -> String {
+        format!("${:.2} / ${:.2}", self.cost, self.budget)
+    }
+}
+
+static BUDGET: OnceLock<BudgetConfig> = OnceLock::new();
+
+/// 起動時に [budget] の設定を反映する（以降は変更しない）
+pub fn init_budget(config: &BudgetConfig) -> Result<()> {
+    if !(config.warn_ratio > 0.0 && config.warn_ratio <= 1.0) {
+        return Err(anyhow!(
+            "[budget] warn_ratio は0より大きく1以下で指定してください: {}",
+            config.warn_ratio
+        ));
+    }
+    for (name, value) in [("session_usd", config.session_usd), ("daily_usd", config.daily_usd)] {
+        if let Some(value) = value.filter(|v| *v <= 0.0) {
+            return Err(anyhow!("[budget] {} は正の値で指定してください: {}", name, value));
+        }
+    }
+    let _ = BUDGET.set(config.clone());
+    Ok(())
+}
+
+/// セッションの推定コストと予算（session_usd がなければNone）
+pub fn session_budget(session: &Session) -> Option<BudgetStatus> {
+    let config = BUDGET.get()?;
+    Some(BudgetStatus::new(
+        session.token_usage?.cost_usd,
+        config.session_usd?,
+        config.warn_ratio,
+    ))
+}
+
+/// セッションが注意・超過か
+pub fn session_budget_level(session: &Session) -> Option<BudgetLevel> {
+    session_budget(session)?.level
+}
+
+/// 最後に集計した今日の合計とその時刻
+static DAILY: Mutex<Option<(f64, Instant)>> = Mutex::new(None);
+
+/// 今日の合計を集計し直す（前回から DAILY_REFRESH たっていなければ何もしない）
+pub fn refresh_daily_cost() {
+    if BUDGET.get().and_then(|c| c.daily_usd).is_none() {
+        return;
+    }
+    let mut daily = DAILY.lock().unwrap_or_else(|e| e.into_inner());
+    if daily.is_some_and(|(_, at)| at.elapsed() < DAILY_REFRESH) {
+        return;
+    }
+    match cost_today() {
+        Ok(cost) => *daily = Some((cost, Instant::now())),
+        Err(e) => tracing::warn!("今日のコストを集計できません: {:#}", e),
+    }
+}
+
+/// 最後に集計した今日の合計と予算（daily_usd がないか未集計ならNone）
+pub fn daily_budget() -> Option<BudgetStatus> {
+    let config = BUDGET.get()?;
+    let (cost, _) = (*DAILY.lock().unwrap_or_else(|e| e.into_inner()))?;
+    Some(BudgetStatus::new(cost, config.daily_usd?, config.warn_ratio))
+}
+
+/// 通知済みの (セッションID、または "daily:<日付>", 段階)
+static NOTIFIED: Mutex<Option<HashSet<(String, BudgetLevel)>>> = Mutex::new(None);
+
+fn budget_message(subject: &str, status: &BudgetStatus, level: BudgetLevel) -> String {
+    match level {
+        BudgetLevel::Warn => format!(
+            "{}のコストが予算の{:.0}%に達しました（{}）",
+            subject,
+            status.cost / status.budget * 100.0,
+            status.amounts()
+        ),
+        BudgetLevel::Over => format!("{}のコストが予算を超えました（{}）", subject, status.amounts()),
+    }
+}
+
+/// 予算の注意・超過に新しく達したものを通知し、その説明を返す（同じ段階の通知は1回だけ）
+pub fn check_budgets(sessions: &[Session]) -> Vec<String> {
+    let Some(config) = BUDGET.get() else {
+        return Vec::new();
+    };
+    refresh_daily_cost();
+
+    let mut notified = NOTIFIED.lock().unwrap_or_else(|e| e.into_inner());
+    let notified = notified.get_or_insert_with(HashSet::new);
+    let mut messages = Vec::new();
+    // 通知しない時間帯は記録もしないので、時間帯が明けてまだ超えていれば通知する
+    for session in sessions.iter().filter(|s| should_notify(s)) {
+        let Some(status) = session_budget(session) else {
+            continue;
+        };
+        let Some(level) = status.level else {
+            continue;
+        };
+        if !notified.insert((session.session_id.clone(), level)) {
+            continue;
+        }
+        let message = budget_message(&format!("{} ", format_cwd(&session.cwd)), &status, level);
+        if config.desktop {
+            send_desktop_notification("claude-watch", &message);
+        }
+        if let Some(ref command) = config.command {
+            let command = command
+                .replace("{cost}", &format!("{:.2}", status.cost))
+                .replace("{budget}", &format!("{:.2}", status.budget));
+            spawn_shell_command(&expand_placeholders(&command, session));
+        }
+        messages.push(message);
+    }
+
+    if let Some(status) = daily_budget()
+        && let Some(level) = status.level
+        && !is_quiet_time()
+    {
+        let key = format!("daily:{}", chrono::Local::now().date_naive());
+        if notified.insert((key, level)) {
+            let message = budget_message("今日の合計", &status, level);
+            if config.desktop {
+                send_desktop_notification("claude-watch", &message);
+            }
+            messages.push(message);
+        }
+    }
+    messages
+}
@@ -0,0 +1,125 @@
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::display::{confirm, format_cwd, glyph, tree};
+use crate::session::{get_active_pane_ids, get_sessions_dir, load_sessions, Session};
+
+pub struct CleanOptions {
+    pub days: u64,
+    pub dry_run: bool,
+    pub archive: bool,
+    pub yes: bool,
+}
+
+/// 削除対象になった理由
+enum StaleReason {
+    PaneGone,
+    Superseded,
+    TooOld(u64),
+}
+
+impl StaleReason {
+    fn label(&self) -> String {
+        match self {
+            StaleReason::PaneGone => "ペインが存在しない".to_string(),
+            StaleReason::Superseded => "同じペインに新しいセッションがある".to_string(),
+            StaleReason::TooOld(days) => format!("{}日以上更新なし", days),
+        }
+    }
+}
+
+fn find_stale_sessions(sessions: Vec<Session>, days: u64) -> Result<Vec<(Session, StaleReason)>> {
+    let active_pane_ids = get_active_pane_ids();
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let threshold = days.saturating_mul(86400);
+
+    // pane_idごとの最新のupdated
+    let mut latest: HashMap<String, u64> = HashMap::new();
+    for session in &sessions {
+        let entry = latest.entry(session.pane_id.clone()).or_insert(0);
+        *entry = (*entry).max(session.updated);
+    }
+
+    let mut stale = Vec::new();
+    for session in sessions {
+        let pane_gone = active_pane_ids
+            .as_ref()
+            .is_some_and(|ids| !ids.contains(&session.pane_id));
+        let reason = if pane_gone {
+            Some(StaleReason::PaneGone)
+        } else if latest.get(&session.pane_id).is_some_and(|&u| session.updated < u) {
+            Some(StaleReason::Superseded)
+        } else if now.saturating_sub(session.updated) > threshold {
+            Some(StaleReason::TooOld(days))
+        } else {
+            None
+        };
+
+        if let Some(reason) = reason {
+            stale.push((session, reason));
+        }
+    }
+
+    stale.sort_by_key(|(s, _)| s.updated);
+    Ok(stale)
+}
+
+pub fn run_clean(opts: &CleanOptions) -> Result<()> {
+    let sessions = load_sessions()?;
+    let stale = find_stale_sessions(sessions, opts.days)?;
+
+    if stale.is_empty() {
+        println!("{}削除対象のセッションファイルはありません", glyph("✨ ", ""));
+        return Ok(());
+    }
+
+    println!("\n{}古いセッションファイル\n", glyph("🧹 ", ""));
+    for (session, reason) in &stale {
+        let file_name = session
+            .path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        println!("  {}  {}  (pane:{})", file_name, format_cwd(&session.cwd), session.pane_id);
+        println!("     {} {}", tree(), reason.label());
+    }
+    println!("\n合計: {}ファイル\n", stale.len());
+
+    if opts.dry_run {
+        println!("--dry-run のため何も変更しませんでした");
+        return Ok(());
+    }
+
+    let action = if opts.archive { "アーカイブ" } else { "削除" };
+    if !opts.yes && !confirm(&format!("{}ファイルを{}しますか？", stale.len(), action))? {
+        println!("中止しました");
+        return Ok(());
+    }
+
+    let archive_dir = get_sessions_dir()?.join("archive");
+    if opts.archive {
+        fs::create_dir_all(&archive_dir)
+            .with_context(|| format!("アーカイブディレクトリの作成に失敗: {:?}", archive_dir))?;
+    }
+
+    for (session, _) in &stale {
+        if opts.archive {
+            let Some(file_name) = session.path.file_name() else {
+                continue;
+            };
+            fs::rename(&session.path, archive_dir.join(file_name))
+                .with_context(|| format!("アーカイブに失敗: {:?}", session.path))?;
+        } else {
+            fs::remove_file(&session.path)
+                .with_context(|| format!("削除に失敗: {:?}", session.path))?;
+        }
+    }
+
+    println!("{}{}ファイルを{}しました", glyph("✅ ", ""), stale.len(), action);
+    Ok(())
+}
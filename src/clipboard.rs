@@ -0,0 +1,111 @@
+use anyhow::{anyhow, Result};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// 利用可能なクリップボードコマンド（上から順に試す）
+const CLIPBOARD_COMMANDS: &[(&str, &[&str])] = &[
+    ("clip.exe", &[]),
+    ("wl-copy", &[]),
+    ("xclip", &["-selection", "clipboard"]),
+    ("xsel", &["--clipboard", "--input"]),
+    ("pbcopy", &[]),
+];
+
+fn pipe_to_command(program: &str, args: &[&str], text: &str) -> Result<()> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+
+    if !child.wait()?.success() {
+        return Err(anyhow!("{} が失敗しました", program));
+    }
+    Ok(())
+}
+
+/// クリップボードにコピーする
+///
+/// コマンドが無ければ（SSH先など）OSC 52 でターミナル経由で手元のクリップボードに送る。
+pub fn copy_to_clipboard(text: &str) -> Result<()> {
+    for (program, args) in CLIPBOARD_COMMANDS {
+        if pipe_to_command(program, args, text).is_ok() {
+            return Ok(());
+        }
+    }
+    tracing::debug!("クリップボードコマンドが見つからないためOSC 52で送信");
+    copy_with_osc52(text)
+}
+
+/// OSC 52 のエスケープシーケンスを端末に書き込む（tmux内ではパススルーで包む）
+fn copy_with_osc52(text: &str) -> Result<()> {
+    let mut sequence = format!("\x1b]52;c;{}\x07", base64_encode(text.as_bytes()));
+    if std::env::var_os("TMUX").is_some() {
+        sequence = format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"));
+    }
+    // 標準出力がパイプでも端末に届くよう /dev/tty を優先する
+    let result = match OpenOptions::new().write(true).open("/dev/tty") {
+        Ok(mut tty) => tty.write_all(sequence.as_bytes()).and_then(|()| tty.flush()),
+        Err(_) => {
+            let mut stdout = std::io::stdout();
+            stdout.write_all(sequence.as_bytes()).and_then(|()| stdout.flush())
+        }
+    };
+    result.map_err(|e| anyhow!("クリップボードにコピーできません（OSC 52の送信に失敗）: {}", e))
+}
+
+const BASE64_CHARS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn base64_encode(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64_CHARS[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// クリップボードにコピーできるセッションの値
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyField {
+    Id,
+    Cwd,
+    /// `claude --resume <session_id>`
+    ResumeCommand,
+    PaneId,
+}
+
+impl CopyField {
+    pub fn parse(name: &str) -> Result<CopyField> {
+        match name {
+            "id" => Ok(CopyField::Id),
+            "cwd" => Ok(CopyField::Cwd),
+            "resume" => Ok(CopyField::ResumeCommand),
+            "pane" | "pane_id" => Ok(CopyField::PaneId),
+            _ => Err(anyhow!("不明な項目: {} (id, cwd, resume, pane のいずれかを指定)", name)),
+        }
+    }
+
+    /// コピーする値（履歴のセッションなどpane_idが無ければNone）
+    pub fn value(self, session_id: &str, cwd: &str, pane_id: Option<&str>) -> Option<String> {
+        match self {
+            CopyField::Id => Some(session_id.to_string()),
+            CopyField::Cwd => Some(cwd.to_string()),
+            CopyField::ResumeCommand => Some(format!("claude --resume {}", session_id)),
+            CopyField::PaneId => pane_id.map(str::to_string),
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use anyhow::{anyhow, Result};
+use ratatui::layout::Constraint;
+
+use crate::config::TableConfig;
+
+/// TUIのセッション一覧の列（[table] columns）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Column {
+    Status,
+    Project,
+    Summary,
+    Branch,
+    Messages,
+    Context,
+    Age,
+}
+
+impl Column {
+    const ALL: &'static [Column] = &[
+        Column::Status,
+        Column::Project,
+        Column::Summary,
+        Column::Branch,
+        Column::Messages,
+        Column::Context,
+        Column::Age,
+    ];
+
+    /// 設定ファイルでの列名
+    pub fn name(self) -> &'static str {
+        match self {
+            Column::Status => "status",
+            Column::Project => "project",
+            Column::Summary => "summary",
+            Column::Branch => "branch",
+            Column::Messages => "messages",
+            Column::Context => "context",
+            Column::Age => "age",
+        }
+    }
+
+    fn from_name(name: &str) -> Result<Column> {
+        Column::ALL.iter().copied().find(|c| c.name() == name).ok_or_else(|| {
+            let names: Vec<&str> = Column::ALL.iter().map(|c| c.name()).collect();
+            anyhow!("不明な列: {} ([table] セクション、{} のいずれか)", name, names.join(", "))
+        })
+    }
+
+    /// 見出し
+    pub fn header(self) -> &'static str {
+        match self {
+            Column::Status => "状態",
+            Column::Project => "プロジェクト",
+            Column::Summary => "要約",
+            Column::Branch => "ブランチ",
+            Column::Messages => "msg",
+            Column::Context => "ctx",
+            Column::Age => "経過",
+        }
+    }
+
+    /// 幅を指定しなかったときの幅（summaryは残りすべて）
+    fn default_width(self) -> Constraint {
+        match self {
+            // マーク + アイコン + "承認待ち"
+            Column::Status => Constraint::Length(13),
+            Column::Project => Constraint::Length(14),
+            Column::Summary => Constraint::Fill(1),
+            Column::Branch => Constraint::Length(12),
+            Column::Messages => Constraint::Length(5),
+            // ゲージ + " 100%"
+            Column::Context => Constraint::Length(10),
+            Column::Age => Constraint::Length(9),
+        }
+    }
+}
+
+/// [table] の設定から表示する列と幅を決める（不明な列名はエラー）
+pub fn table_columns(config: &TableConfig) -> Result<Vec<(Column, Constraint)>> {
+    for name in config.widths.keys() {
+        Column::from_name(name)?;
+    }
+    if config.columns.is_empty() {
+        return Err(anyhow!("[table] columns に列が1つもありません"));
+    }
+    config
+        .columns
+        .iter()
+        .map(|name| {
+            let column = Column::from_name(name)?;
+            let width = config
+                .widths
+                .get(name)
+                .map_or(column.default_width(), |&w| Constraint::Length(w));
+            Ok((column, width))
+        })
+        .collect()
+}
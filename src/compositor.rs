@@ -0,0 +1,155 @@
+use anyhow::{anyhow, Context, Result};
+use serde_json::Value;
+use std::io::{self, Read, Write};
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+
+// Hyprland / sway のIPCソケットに直接つないでウィンドウを探し、前面に出す。
+
+#[cfg(unix)]
+fn connect(path: &Path) -> io::Result<UnixStream> {
+    UnixStream::connect(path)
+}
+
+#[cfg(not(unix))]
+fn connect(_path: &Path) -> io::Result<std::net::TcpStream> {
+    Err(io::Error::other("このOSではUnixソケットに対応していません"))
+}
+
+/// Hyprlandのコマンドソケット（$XDG_RUNTIME_DIR/hypr、古い版は /tmp/hypr）
+fn hyprland_socket() -> Result<PathBuf> {
+    let signature =
+        std::env::var("HYPRLAND_INSTANCE_SIGNATURE").context("Hyprlandが動いていません（HYPRLAND_INSTANCE_SIGNATUREがありません）")?;
+    let runtime_dir = std::env::var("XDG_RUNTIME_DIR").unwrap_or_default();
+    [PathBuf::from(runtime_dir).join("hypr"), PathBuf::from("/tmp/hypr")]
+        .into_iter()
+        .map(|dir| dir.join(&signature).join(".socket.sock"))
+        .find(|path| path.exists())
+        .ok_or_else(|| anyhow!("Hyprlandのソケットが見つかりません"))
+}
+
+/// Hyprlandにコマンドを送り、応答を返す（1接続で1コマンド）
+fn hyprland_request(command: &str) -> Result<String> {
+    let path = hyprland_socket()?;
+    let mut stream = connect(&path).with_context(|| format!("Hyprlandに接続できません: {:?}", path))?;
+    stream.write_all(command.as_bytes())?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+    Ok(response)
+}
+
+fn hyprland_json(command: &str) -> Result<Value> {
+    serde_json::from_str(&hyprland_request(command)?).with_context(|| format!("Hyprlandの応答を解析できません: {}", command))
+}
+
+fn hyprland_dispatch(args: &str) -> Result<()> {
+    let response = hyprland_request(&format!("dispatch {}", args))?;
+    if response.trim() != "ok" {
+        return Err(anyhow!("Hyprland: dispatch {} が失敗しました: {}", args, response.trim()));
+    }
+    Ok(())
+}
+
+/// タイトルに title を含むHyprlandのウィンドウを前面に出す（見つからなければfalse）
+///
+/// move_to_current なら今のワークスペースへ移してからフォーカスする。
+pub fn hyprland_focus(title: &str, move_to_current: bool) -> Result<bool> {
+    let clients = hyprland_json("j/clients")?;
+    let Some(address) = clients
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|client| client["title"].as_str().is_some_and(|t| t.contains(title)))
+        .find_map(|client| client["address"].as_str())
+    else {
+        return Ok(false);
+    };
+
+    if move_to_current {
+        let workspace = hyprland_json("j/activeworkspace")?;
+        let id = workspace["id"]
+            .as_i64()
+            .ok_or_else(|| anyhow!("Hyprlandの現在のワークスペースが分かりません"))?;
+        hyprland_dispatch(&format!("movetoworkspacesilent {},address:{}", id, address))?;
+    }
+    hyprland_dispatch(&format!("focuswindow address:{}", address))?;
+    Ok(true)
+}
+
+/// i3/swayのIPCのメッセージ種別
+const SWAY_RUN_COMMAND: u32 = 0;
+const SWAY_GET_WORKSPACES: u32 = 1;
+const SWAY_GET_TREE: u32 = 4;
+const SWAY_MAGIC: &[u8] = b"i3-ipc";
+
+/// swayにメッセージを送り、応答のJSONを返す（"i3-ipc" + 長さ + 種別 + 本文、数値はネイティブエンディアン）
+fn sway_request(message_type: u32, payload: &str) -> Result<Value> {
+    let path = std::env::var("SWAYSOCK").context("swayが動いていません（SWAYSOCKがありません）")?;
+    let mut stream = connect(Path::new(&path)).with_context(|| format!("swayに接続できません: {}", path))?;
+
+    let mut message = SWAY_MAGIC.to_vec();
+    message.extend_from_slice(&u32::try_from(payload.len())?.to_ne_bytes());
+    message.extend_from_slice(&message_type.to_ne_bytes());
+    message.extend_from_slice(payload.as_bytes());
+    stream.write_all(&message)?;
+
+    let mut header = [0u8; 14];
+    stream.read_exact(&mut header)?;
+    if &header[..6] != SWAY_MAGIC {
+        return Err(anyhow!("swayの応答の形式が不正です"));
+    }
+    let len = u32::from_ne_bytes(header[6..10].try_into()?) as usize;
+    let mut body = vec![0u8; len];
+    stream.read_exact(&mut body)?;
+    serde_json::from_slice(&body).context("swayの応答を解析できません")
+}
+
+/// swayのツリーからタイトルが一致するウィンドウのcon_id
+fn find_sway_window(node: &Value, title: &str) -> Option<u64> {
+    if node["name"].as_str().is_some_and(|name| name.contains(title))
+        && (node["pid"].is_u64() || node["window"].is_u64())
+    {
+        return node["id"].as_u64();
+    }
+    ["nodes", "floating_nodes"]
+        .iter()
+        .filter_map(|key| node[*key].as_array())
+        .flatten()
+        .find_map(|child| find_sway_window(child, title))
+}
+
+/// タイトルに title を含むswayのウィンドウを前面に出す（見つからなければfalse）
+///
+/// move_to_current なら今のワークスペースへ移してからフォーカスする。
+pub fn sway_focus(title: &str, move_to_current: bool) -> Result<bool> {
+    let tree = sway_request(SWAY_GET_TREE, "")?;
+    let Some(id) = find_sway_window(&tree, title) else {
+        return Ok(false);
+    };
+
+    let mut command = String::new();
+    if move_to_current {
+        let workspaces = sway_request(SWAY_GET_WORKSPACES, "")?;
+        let name = workspaces
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|ws| ws["focused"].as_bool() == Some(true))
+            .and_then(|ws| ws["name"].as_str())
+            .ok_or_else(|| anyhow!("swayの現在のワークスペースが分かりません"))?;
+        command.push_str(&format!("[con_id={}] move container to workspace \"{}\"; ", id, name.replace('"', "\\\"")));
+    }
+    command.push_str(&format!("[con_id={}] focus", id));
+
+    let results = sway_request(SWAY_RUN_COMMAND, &command)?;
+    if let Some(failed) = results
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|result| result["success"].as_bool() != Some(true))
+    {
+        return Err(anyhow!("sway: {} が失敗しました: {}", command, failed["error"].as_str().unwrap_or_default()));
+    }
+    Ok(true)
+}
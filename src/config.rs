@@ -0,0 +1,693 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::status::SessionStatus;
+
+/// 単一指定と配列指定の両方を受け付ける値
+/// （`quit = "q"` と `quit = ["q", "Esc"]`、`[webhook]` と `[[webhook]]` など）
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum OneOrMany<T> {
+    One(T),
+    Many(Vec<T>),
+}
+
+impl<T> OneOrMany<T> {
+    pub fn items(&self) -> &[T] {
+        match self {
+            OneOrMany::One(item) => std::slice::from_ref(item),
+            OneOrMany::Many(items) => items,
+        }
+    }
+}
+
+impl<T> Default for OneOrMany<T> {
+    fn default() -> Self {
+        OneOrMany::Many(Vec::new())
+    }
+}
+
+/// ~/.config/claude-watch/config.toml の内容
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// 使用するターミナル（"wezterm" / "windows-terminal" / "iterm2" / "window"）
+    pub backend: Option<String>,
+    /// セッションファイルの置き場所（未指定なら CLAUDE_CONFIG_DIR または ~/.claude の sessions）
+    pub sessions_dir: Option<PathBuf>,
+    /// Claude Codeのプロセスが終了したセッションを完了扱いにする（/procを参照）
+    pub process_liveness: bool,
+    /// フックを取りこぼしたセッションのステータスを会話ログの末尾から補正する
+    pub reconcile_status: bool,
+    /// cwdのシンボリックリンクを解決してプロジェクトやプロセスと照合する
+    pub canonicalize_paths: bool,
+    /// 絵文字と罫線を使わずASCIIで表示する
+    pub ascii: bool,
+    /// 配色（"default" / "light" / "solarized" / "monochrome"）
+    pub theme: Option<String>,
+    /// 相対時刻の表記（"ja" / "en"、未指定なら環境変数LANGから判定）
+    pub locale: Option<String>,
+    /// 中断・削除・拒否の前に確認ダイアログを出す（falseなら確認せずに実行。許可はツール呼び出しの内容を見せて常に確認する）
+    pub confirm: bool,
+    /// `list` の1行ごとの書式（`list --template` と同じ、例: "{icon} {cwd} {status} {age}"）
+    pub list_template: Option<String>,
+    /// 承認待ちのセッションをスヌーズする時間（分）
+    pub snooze_minutes: u64,
+    /// アクション名 → キー（例: `quit = ["q", "Esc"]`）
+    pub keys: BTreeMap<String, OneOrMany<String>>,
+    pub timer: TimerConfig,
+    /// 完了したセッションを薄く表示する閾値（[escalation]）
+    pub escalation: EscalationConfig,
+    /// 長時間同じステータスのままのセッションに対する通知ルール（[[alerts]]）
+    pub alerts: Vec<AlertRule>,
+    /// ステータス変化を通知するWebhook（[webhook] または [[webhook]]）
+    pub webhook: OneOrMany<WebhookConfig>,
+    /// スマートフォンへのプッシュ通知（[push] または [[push]]、ntfy / Pushover）
+    pub push: OneOrMany<PushConfig>,
+    /// ステータス変化のまとめをメールで送る（[email]）
+    pub email: Option<EmailConfig>,
+    /// ステータス変化で実行するコマンド（[triggers]）
+    pub triggers: TriggersConfig,
+    /// セッションごと・1日ごとのコストの予算（[budget]）
+    pub budget: BudgetConfig,
+    /// モデル名に含まれる文字列 → 推定コストに使う単価（[pricing.<model>]、組み込みの単価より優先）
+    pub pricing: BTreeMap<String, ModelPricing>,
+    /// 会話ログの全文検索（[search]）
+    pub search: SearchConfig,
+    /// TUI表示中に新しく承認待ちになったセッションを音で知らせる（[bell]）
+    pub bell: BellConfig,
+    /// 通知しない時間帯とプロジェクト（[quiet]）
+    pub quiet: QuietConfig,
+    /// 一覧にも通知にも出さないセッションのcwd（globで指定、例: `ignore = ["/tmp/**", "~/experiments/**"]`）
+    pub ignore: Vec<String>,
+    /// プロジェクトごとの表示の設定（[[projects]]）
+    pub projects: Vec<ProjectConfig>,
+    /// フックが書き込む独自ステータスの表示（[statuses.<name>]）
+    pub statuses: BTreeMap<String, StatusConfig>,
+    /// SSHで監視する別のマシン（[[remote]]）
+    pub remote: Vec<RemoteConfig>,
+    /// セッションのディレクトリを開くエディタ（[editor]）
+    pub editor: EditorConfig,
+    /// TUIのセッション一覧に表示する列（[table]）
+    pub table: TableConfig,
+    /// backend = "window" でウィンドウを探す方法（[window]）
+    pub window: WindowConfig,
+    /// `serve` の設定（[serve]）
+    pub serve: ServeConfig,
+}
+
+/// 例:
+/// ```toml
+/// [table]
+/// columns = ["status", "project", "summary", "branch", "messages", "context", "age"]
+/// widths = { project = 20, summary = 40 }
+/// ```
+/// 幅を指定しない summary 列は残りの幅をすべて使う。
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct TableConfig {
+    /// 表示する列（左から順に）
+    pub columns: Vec<String>,
+    /// 列名 → 幅（文字数）
+    pub widths: BTreeMap<String, u16>,
+}
+
+impl Default for TableConfig {
+    fn default() -> Self {
+        Self {
+            columns: ["status", "project", "summary", "age"].map(String::from).to_vec(),
+            widths: BTreeMap::new(),
+        }
+    }
+}
+
+/// 例:
+/// ```toml
+/// [editor]
+/// command = "code {cwd}"
+/// ```
+/// 未指定なら $VISUAL / $EDITOR を新しいペインで開く。
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct EditorConfig {
+    /// 実行するコマンド（{cwd} を置換、セッションのディレクトリで実行）
+    pub command: Option<String>,
+    /// 端末で動くエディタ（nvimなど）を新しいペインで開く
+    pub pane: bool,
+}
+
+/// 例:
+/// ```toml
+/// [pricing."claude-opus-4-5"]
+/// input = 5.0
+/// output = 25.0
+/// cache_write = 6.25
+/// cache_read = 0.5
+/// ```
+/// モデル名にキーを含むものに使う（複数が当てはまれば長いキーを優先）。
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct ModelPricing {
+    /// 入力（USD / 100万トークン）
+    pub input: f64,
+    /// 出力（USD / 100万トークン）
+    pub output: f64,
+    /// キャッシュ書き込み（USD / 100万トークン）
+    pub cache_write: f64,
+    /// キャッシュ読み込み（USD / 100万トークン）
+    pub cache_read: f64,
+}
+
+/// 例:
+/// ```toml
+/// [serve]
+/// token = "my-secret"
+/// ```
+/// 未指定なら `serve --web` を起動するたびにトークンを生成して表示する。
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ServeConfig {
+    /// ダッシュボードからジャンプするのに必要なトークン
+    pub token: Option<String>,
+}
+
+/// 例:
+/// ```toml
+/// [window]
+/// tool = "hyprland"
+/// title = "{dir} - Alacritty"
+/// move_to_current = true
+/// ```
+/// backend = "window" のとき、タイトルに title を含むウィンドウを前面に出す。
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct WindowConfig {
+    /// ウィンドウを操作するツール（"wmctrl" / "xdotool" / "hyprland" / "sway"、未指定なら環境から判定）
+    pub tool: Option<String>,
+    /// ウィンドウタイトルに含まれる文字列（{cwd} と {dir} を置換）
+    pub title: String,
+    /// ウィンドウを今のワークスペースに移してからフォーカスする（hyprland / sway のみ）
+    pub move_to_current: bool,
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        Self {
+            tool: None,
+            title: "{dir}".to_string(),
+            move_to_current: false,
+        }
+    }
+}
+
+/// 例:
+/// ```toml
+/// [[remote]]
+/// name = "devbox"
+/// host = "me@devbox.example.com"
+/// jump = "ssh me@devbox.example.com tmux -L work select-pane -t {pane_id}"
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct RemoteConfig {
+    /// 一覧に表示するホスト名
+    pub name: String,
+    /// sshの接続先（~/.ssh/config のHost名も可）
+    pub host: String,
+    /// リモートで実行するコマンド（セッションのJSON配列を出力するもの）
+    #[serde(default = "default_remote_command")]
+    pub command: String,
+    /// 取得する間隔（秒）
+    #[serde(default = "default_remote_interval")]
+    pub interval: u64,
+    /// ペインへジャンプするローカルのコマンド（{session_id} {cwd} {pane_id} {host} を置換）
+    pub jump: Option<String>,
+}
+
+fn default_remote_command() -> String {
+    "claude-watch list --json".to_string()
+}
+
+fn default_remote_interval() -> u64 {
+    5
+}
+
+/// 例:
+/// ```toml
+/// [statuses.thinking]
+/// icon = "🧠"
+/// label = "思考中"
+/// color = "magenta"
+/// priority = 2
+/// running = true
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct StatusConfig {
+    pub icon: Option<String>,
+    /// --ascii のときのアイコン（未指定なら "[T]" のように頭文字から作る）
+    pub ascii_icon: Option<String>,
+    pub label: Option<String>,
+    /// 色名・"#rrggbb"・256色の番号
+    pub color: Option<String>,
+    /// ステータスバーで優先する度合い（承認待ち 3 / 実行中 2 / 完了 1 / 不明 0）
+    pub priority: Option<u8>,
+    /// 実行中・承認待ちと同じく経過時間を表示する
+    pub running: Option<bool>,
+}
+
+/// 例:
+/// ```toml
+/// [bell]
+/// enabled = true
+/// command = "paplay /usr/share/sounds/freedesktop/stereo/message.oga"
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct BellConfig {
+    pub enabled: bool,
+    /// ターミナルのベルを鳴らす（commandだけを使うならfalse）
+    pub terminal: bool,
+    /// 音を鳴らすコマンド（{session_id} {cwd} {pane_id} {status} {reason} {elapsed} を置換）
+    pub command: Option<String>,
+}
+
+impl Default for BellConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            terminal: true,
+            command: None,
+        }
+    }
+}
+
+/// 例:
+/// ```toml
+/// [webhook]
+/// url = "https://hooks.slack.com/..."
+/// on = ["active->waiting", "active->stopped"]
+/// format = "slack"
+/// link = "https://example.com/sessions/{session_id}"
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct WebhookConfig {
+    pub url: String,
+    /// 送信する遷移（"from->to"、"*" は任意、"new" / "gone" は出現・消滅）
+    #[serde(default = "default_webhook_on")]
+    pub on: Vec<String>,
+    /// 送信するJSONの形式
+    #[serde(default)]
+    pub format: WebhookFormat,
+    /// メッセージに付けるリンク（{session_id} {pane_id} {host} を置換、slack / discord のみ）
+    pub link: Option<String>,
+}
+
+/// Webhookに送るJSONの形式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookFormat {
+    /// claude-watchの汎用JSON
+    #[default]
+    Json,
+    /// Slackのincoming webhook（Block Kit）
+    Slack,
+    /// DiscordのWebhook（embed）
+    Discord,
+}
+
+fn default_webhook_on() -> Vec<String> {
+    vec!["active->waiting".to_string(), "active->stopped".to_string()]
+}
+
+/// 例:
+/// ```toml
+/// [[push]]
+/// service = "ntfy"
+/// topic = "my-claude-watch"
+///
+/// [[push]]
+/// service = "pushover"
+/// token = "アプリのAPIトークン"
+/// user = "ユーザーキー"
+/// statuses = ["waiting", "stopped"]
+/// min_interval = 600
+/// ```
+/// スヌーズ中のセッションと [quiet] の時間帯・プロジェクトには送らない。
+#[derive(Debug, Clone, Deserialize)]
+pub struct PushConfig {
+    #[serde(flatten)]
+    pub service: PushService,
+    /// 送信するステータス（このステータスになったときに送る）
+    #[serde(default = "default_push_statuses")]
+    pub statuses: Vec<SessionStatus>,
+    /// 同じセッションについて次に送るまでの最短の秒数
+    #[serde(default = "default_push_min_interval")]
+    pub min_interval: u64,
+    /// 1時間に送る最大の件数（0なら無制限）
+    #[serde(default = "default_push_max_per_hour")]
+    pub max_per_hour: usize,
+    /// 通知を開いたときのリンク（{session_id} {pane_id} {host} を置換）
+    pub link: Option<String>,
+}
+
+/// プッシュ通知のサービス
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "service", rename_all = "lowercase")]
+pub enum PushService {
+    Ntfy {
+        topic: String,
+        #[serde(default = "default_ntfy_server")]
+        server: String,
+        /// アクセス制限のあるトピックのトークン
+        token: Option<String>,
+    },
+    Pushover {
+        token: String,
+        user: String,
+        /// 送り先の端末名（未指定なら全端末）
+        device: Option<String>,
+    },
+}
+
+fn default_push_statuses() -> Vec<SessionStatus> {
+    vec![SessionStatus::Waiting]
+}
+
+fn default_push_min_interval() -> u64 {
+    300
+}
+
+fn default_push_max_per_hour() -> usize {
+    20
+}
+
+fn default_ntfy_server() -> String {
+    "https://ntfy.sh".to_string()
+}
+
+/// 例:
+/// ```toml
+/// [email]
+/// smtp = "smtps://smtp.example.com:465"
+/// username = "me@example.com"
+/// password_env = "CLAUDE_WATCH_SMTP_PASSWORD"
+/// from = "claude-watch <me@example.com>"
+/// to = ["me@example.com"]
+/// digest_minutes = 60
+/// ```
+/// 送信にはcurlのSMTP機能を使う。
+#[derive(Debug, Clone, Deserialize)]
+pub struct EmailConfig {
+    /// SMTPサーバーのURL（smtps:// はTLS、smtp:// はSTARTTLS）
+    pub smtp: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// パスワードを読む環境変数（password より優先）
+    pub password_env: Option<String>,
+    pub from: String,
+    pub to: OneOrMany<String>,
+    /// 実行中・承認待ちのセッションがすべて完了したらまとめを送る
+    #[serde(default = "default_true")]
+    pub on_all_stopped: bool,
+    /// この分数ごとにそれまでのステータス変化をまとめて送る（0で無効）
+    #[serde(default)]
+    pub digest_minutes: u64,
+    /// smtp:// でもTLSを必須にする（falseならローカルの中継サーバーに平文で送れる）
+    #[serde(default = "default_true")]
+    pub tls: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// 例:
+/// ```toml
+/// [triggers]
+/// on_waiting = "scripts/ping.sh {session_id} {cwd}"
+/// on_stopped = "say 終わりました"
+/// on_gone = "logger claude-watch {session_id} {from}"
+/// timeout = 30
+/// ```
+/// on_<ステータス>（独自ステータスも可）、on_new（出現）、on_gone（消滅）を指定する。
+/// プレースホルダは [[alerts]] の command と同じものに加えて {from} {to}。
+/// コマンドは設定ファイルのディレクトリで実行し、[quiet] やスヌーズの影響を受けない。
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct TriggersConfig {
+    /// コマンドを打ち切るまでの秒数
+    pub timeout: u64,
+    /// on_<イベント> → コマンド
+    #[serde(flatten)]
+    pub commands: BTreeMap<String, String>,
+}
+
+impl Default for TriggersConfig {
+    fn default() -> Self {
+        Self {
+            timeout: 30,
+            commands: BTreeMap::new(),
+        }
+    }
+}
+
+/// 例:
+/// ```toml
+/// [budget]
+/// session_usd = 5.0
+/// daily_usd = 30.0
+/// warn_ratio = 0.8
+/// desktop = true
+/// command = "notify.sh {session_id} {cwd} {cost}"
+/// ```
+/// コストはトークン使用量からの推定値。指定しなかった予算は見ない。
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct BudgetConfig {
+    /// 1セッションの予算（USD）
+    pub session_usd: Option<f64>,
+    /// 1日（ローカル時刻）の全セッション合計の予算（USD）
+    pub daily_usd: Option<f64>,
+    /// 予算のこの割合を超えたら注意を出す（0より大きく1以下）
+    pub warn_ratio: f64,
+    /// 注意・超過になったらデスクトップ通知を送る
+    pub desktop: bool,
+    /// セッションが注意・超過になったら実行するコマンド（[[alerts]] と同じプレースホルダと {cost} {budget}）
+    pub command: Option<String>,
+}
+
+impl Default for BudgetConfig {
+    fn default() -> Self {
+        Self {
+            session_usd: None,
+            daily_usd: None,
+            warn_ratio: 0.8,
+            desktop: false,
+            command: None,
+        }
+    }
+}
+
+/// 例:
+/// ```toml
+/// [search]
+/// index = true
+/// ```
+/// 索引は $XDG_STATE_HOME/claude-watch/search.db（SQLiteのFTS5）。デーモンと `search` が追記分だけを取り込む。
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SearchConfig {
+    /// 毎回トランスクリプトを読み直す代わりに索引を使う（2文字以下の語は索引を使わない）
+    pub index: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            backend: None,
+            sessions_dir: None,
+            process_liveness: true,
+            reconcile_status: true,
+            canonicalize_paths: true,
+            ascii: false,
+            theme: None,
+            locale: None,
+            confirm: true,
+            list_template: None,
+            snooze_minutes: 15,
+            keys: BTreeMap::new(),
+            timer: TimerConfig::default(),
+            escalation: EscalationConfig::default(),
+            alerts: Vec::new(),
+            webhook: OneOrMany::default(),
+            push: OneOrMany::default(),
+            email: None,
+            triggers: TriggersConfig::default(),
+            budget: BudgetConfig::default(),
+            pricing: BTreeMap::new(),
+            search: SearchConfig::default(),
+            bell: BellConfig::default(),
+            quiet: QuietConfig::default(),
+            ignore: Vec::new(),
+            projects: Vec::new(),
+            statuses: BTreeMap::new(),
+            remote: Vec::new(),
+            editor: EditorConfig::default(),
+            table: TableConfig::default(),
+            window: WindowConfig::default(),
+            serve: ServeConfig::default(),
+        }
+    }
+}
+
+/// 例:
+/// ```toml
+/// [[alerts]]
+/// status = "waiting"
+/// after = 120
+/// bell = true
+/// desktop = true
+/// command = "notify.sh {session_id} {cwd}"
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct AlertRule {
+    #[serde(default = "default_alert_status")]
+    pub status: SessionStatus,
+    /// このステータスが続いた秒数
+    pub after: u64,
+    #[serde(default)]
+    pub bell: bool,
+    #[serde(default)]
+    pub desktop: bool,
+    /// 実行するコマンド（{session_id} {cwd} {pane_id} {status} {reason} {elapsed} を置換）
+    pub command: Option<String>,
+}
+
+fn default_alert_status() -> SessionStatus {
+    SessionStatus::Waiting
+}
+
+/// 実行中・承認待ちの経過時間の色を変える閾値（秒）
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct TimerConfig {
+    /// この秒数を超えたら黄色
+    pub warn_after: u64,
+    /// この秒数を超えたら赤
+    pub alert_after: u64,
+    /// 実行中のままセッションファイルも会話ログもこの秒数更新されなければ「idle?」と表示する（0で無効）
+    pub idle_after: u64,
+}
+
+impl Default for TimerConfig {
+    fn default() -> Self {
+        Self {
+            warn_after: 120,
+            alert_after: 600,
+            idle_after: 900,
+        }
+    }
+}
+
+/// 例:
+/// ```toml
+/// [quiet]
+/// hours = "22:00-07:00"
+/// muted_projects = ["sandbox", "~/scratch"]
+/// ```
+/// ベル・デスクトップ通知・通知ルールのコマンド・Webhookを止める（TUIの表示は変わらない）。
+/// muted_projects はディレクトリ名、またはパス（その下も含む）で指定する。
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct QuietConfig {
+    /// 通知しない時間帯（"HH:MM-HH:MM"、日をまたいでもよい）
+    pub hours: Option<String>,
+    /// 通知しないプロジェクト
+    pub muted_projects: Vec<String>,
+}
+
+/// 例:
+/// ```toml
+/// [[projects]]
+/// path = "~/scratch"
+/// hide_stopped = true
+///
+/// [[projects]]
+/// path = "~/work/api"
+/// pin = true
+/// ```
+/// path はディレクトリ名、またはパス（その下も含む）で指定する。TUIと list の一覧に反映する。
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProjectConfig {
+    pub path: String,
+    /// 一覧に出さない
+    #[serde(default)]
+    pub hide: bool,
+    /// 完了したセッションを一覧に出さない
+    #[serde(default)]
+    pub hide_stopped: bool,
+    /// 常にピン留めする
+    #[serde(default)]
+    pub pin: bool,
+}
+
+/// 例:
+/// ```toml
+/// [escalation]
+/// stopped_dim_after = 3600
+/// ```
+/// 秒数で指定し、0なら無効。承認待ちの色と並びは [timer] の warn_after / alert_after に従う。
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct EscalationConfig {
+    /// 完了してからこの秒数たったセッションを薄く表示する
+    pub stopped_dim_after: u64,
+}
+
+impl Default for EscalationConfig {
+    fn default() -> Self {
+        Self { stopped_dim_after: 3600 }
+    }
+}
+
+/// ホームディレクトリ（HOME、WindowsではUSERPROFILE）
+pub fn home_dir() -> Result<PathBuf> {
+    ["HOME", "USERPROFILE"]
+        .iter()
+        .find_map(|var| std::env::var(var).ok().filter(|v| !v.is_empty()))
+        .map(PathBuf::from)
+        .context("HOME環境変数が見つかりません")
+}
+
+/// 環境変数が示すディレクトリ（未設定や空ならNone）
+pub fn env_dir(var: &str) -> Option<PathBuf> {
+    std::env::var(var).ok().filter(|v| !v.is_empty()).map(PathBuf::from)
+}
+
+pub fn get_config_path() -> Result<PathBuf> {
+    if let Some(dir) = env_dir("XDG_CONFIG_HOME") {
+        return Ok(dir.join("claude-watch/config.toml"));
+    }
+    // Windowsでは %APPDATA%\claude-watch\config.toml
+    if cfg!(windows)
+        && let Some(dir) = env_dir("APPDATA")
+    {
+        return Ok(dir.join("claude-watch").join("config.toml"));
+    }
+    Ok(home_dir()?.join(".config/claude-watch/config.toml"))
+}
+
+/// 設定ファイルを読み込む（存在しなければデフォルト）
+pub fn load_config() -> Result<Config> {
+    let path = get_config_path()?;
+    if !path.exists() {
+        tracing::debug!(path = ?path, "設定ファイルがないためデフォルト設定を使用");
+        return Ok(Config::default());
+    }
+
+    tracing::debug!(path = ?path, "設定ファイルを読み込み");
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("設定ファイル読み込みエラー: {:?}", path))?;
+    toml::from_str(&content).with_context(|| format!("設定ファイル解析エラー: {:?}", path))
+}
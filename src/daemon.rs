@@ -0,0 +1,91 @@
+use anyhow::Result;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::alerts::AlertEngine;
+use crate::budget::check_budgets;
+use crate::config::Config;
+use crate::display::glyph;
+use crate::email::dispatch_email;
+use crate::ipc::{socket_path, DaemonServer};
+use crate::push::dispatch_push;
+use crate::remote::RemotePoller;
+use crate::search::refresh_search_index;
+use crate::store::EventStore;
+use crate::session::load_active_sessions;
+use crate::state::load_state;
+use crate::transitions::StatusTracker;
+use crate::triggers::run_triggers;
+use crate::watcher::SessionWatcher;
+use crate::webhook::dispatch_webhooks;
+
+/// 画面を持たずにセッションを監視し続け、通知ルールを評価する
+pub fn run_daemon(config: &Config, interval: Duration) -> Result<()> {
+    let mut alerts = AlertEngine::new(config.alerts.clone());
+    let mut tracker = StatusTracker::new();
+    let mut store = EventStore::open()?;
+    let remotes = RemotePoller::start(&config.remote);
+    let server = DaemonServer::start(&config.remote)?;
+
+    eprintln!(
+        "{}claude-watch daemon 起動（{}秒間隔, 通知ルール{}件, {}）",
+        glyph("👀 ", ""),
+        interval.as_secs(),
+        config.alerts.len(),
+        socket_path()?.display()
+    );
+
+    // 監視できなければ interval ごとに読み直すだけにする
+    let watcher = SessionWatcher::start()
+        .inspect_err(|e| eprintln!("{}ファイルの監視を開始できません: {:#}", glyph("⚠ ", "! "), e))
+        .ok();
+
+    let mut last_load: Option<Instant> = None;
+    loop {
+        // ファイルが変わったらすぐに、変わらなくてもペインの終了などを拾うため interval ごとに読み直す
+        if let Some(at) = last_load {
+            let remaining = interval.saturating_sub(at.elapsed());
+            match watcher {
+                Some(ref watcher) => watcher.wait(remaining),
+                None => thread::sleep(remaining),
+            }
+        }
+        last_load = Some(Instant::now());
+
+        match load_active_sessions() {
+            Ok(sessions) => {
+                let mut sessions = remotes.merge(sessions);
+                // TUIでスヌーズしたセッションは状態ファイルから反映する
+                match load_state() {
+                    Ok(state) => state.apply_snoozes(&mut sessions),
+                    Err(e) => eprintln!("{}状態ファイルの読み込みに失敗: {}", glyph("⚠ ", "! "), e),
+                }
+                for session_id in server.take_acks() {
+                    if let Some(session) = sessions.iter().find(|s| s.session_id == session_id) {
+                        alerts.acknowledge(session);
+                    }
+                }
+                for message in alerts.check(&sessions) {
+                    eprintln!("{}{}", glyph("🔔 ", "* "), message);
+                }
+                for message in check_budgets(&sessions) {
+                    eprintln!("{}{}", glyph("💸 ", "$ "), message);
+                }
+                let transitions = tracker.update(&sessions);
+                if let Err(e) = store.record_transitions(&transitions) {
+                    eprintln!("{}イベントの記録に失敗: {}", glyph("⚠ ", "! "), e);
+                }
+                dispatch_webhooks(config.webhook.items(), &transitions);
+                dispatch_push(config.push.items(), &transitions);
+                dispatch_email(config.email.as_ref(), &sessions, &transitions);
+                run_triggers(&transitions);
+                server.publish(&sessions);
+            }
+            Err(e) => {
+                // 一時的な失敗は次回リトライ
+                eprintln!("{}セッションの取得に失敗: {}", glyph("⚠ ", "! "), e);
+            }
+        }
+        refresh_search_index();
+    }
+}
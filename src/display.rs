@@ -1,53 +1,101 @@
-use crate::session::Session;
+use std::io::{self, BufRead, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
 
-pub fn simplify_notification_message(msg: &str) -> String {
-    // "Claude needs your permission to use Bash" -> "Bash許可待ち"
-    // "Claude Code needs your approval for the plan" -> "プラン承認待ち"
-    if msg.contains("permission to use") {
-        if let Some(tool_name) = msg.split("use ").nth(1) {
-            return format!("{}許可待ち", tool_name);
+use crate::budget::session_budget_level;
+use crate::config::home_dir;
+use crate::git::GitStatus;
+use crate::search::SearchResult;
+use crate::session::{HistoryEntry, Session};
+use crate::state::State;
+use crate::stats::Stats;
+use crate::store::StoredTransition;
+use crate::status::SessionStatus;
+use crate::transcript::{ProjectUsage, TokenUsage};
+
+static ASCII: AtomicBool = AtomicBool::new(false);
+
+/// 絵文字と罫線をASCIIに置き換える（`--ascii` / 設定ファイルの ascii）
+pub fn set_ascii_mode(enabled: bool) {
+    ASCII.store(enabled, Ordering::Relaxed);
+}
+
+pub fn ascii_mode() -> bool {
+    ASCII.load(Ordering::Relaxed)
+}
+
+/// ASCIIモードなら代替表記を返す
+pub fn glyph(emoji: &'static str, ascii: &'static str) -> &'static str {
+    if ascii_mode() { ascii } else { emoji }
+}
+
+/// 相対時刻などの表記言語
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    Ja,
+    En,
+}
+
+static LOCALE: OnceLock<Locale> = OnceLock::new();
+
+impl Locale {
+    pub fn parse(name: &str) -> anyhow::Result<Locale> {
+        match name {
+            "ja" => Ok(Locale::Ja),
+            "en" => Ok(Locale::En),
+            _ => Err(anyhow::anyhow!("不明なロケール: {} (ja, en のいずれかを指定)", name)),
         }
-    } else if msg.contains("approval for the plan") {
-        return "プラン承認待ち".to_string();
     }
 
-    // デフォルトは元のメッセージをそのまま返す
-    truncate_text(msg, 40)
+    /// LC_ALL / LC_TIME / LANG が en で始まれば英語、それ以外は日本語
+    fn from_env() -> Locale {
+        let lang = ["LC_ALL", "LC_TIME", "LANG"]
+            .iter()
+            .find_map(|var| std::env::var(var).ok().filter(|v| !v.is_empty()))
+            .unwrap_or_default();
+        if lang.starts_with("en") { Locale::En } else { Locale::Ja }
+    }
 }
 
-pub fn get_status_icon(status: &str) -> &str {
-    match status {
-        "active" => "🟢",
-        "waiting" => "🟡",
-        "stopped" => "⚪",
-        _ => "❓",
-    }
+/// 起動時に表記言語を決める（設定ファイルの locale。未指定なら環境変数から判定）
+pub fn init_locale(locale: Option<Locale>) {
+    let _ = LOCALE.set(locale.unwrap_or_else(Locale::from_env));
+}
+
+pub fn locale() -> Locale {
+    *LOCALE.get_or_init(Locale::from_env)
+}
+
+/// 詳細行の頭につける枝
+pub fn tree() -> &'static str {
+    glyph("└─", "`-")
+}
+
+fn separator() -> &'static str {
+    glyph("──", "--")
+}
+
+/// [y/N] の確認プロンプトを表示して回答を返す
+pub fn confirm(prompt: &str) -> anyhow::Result<bool> {
+    print!("{} [y/N]: ", prompt);
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
 }
 
 pub fn format_cwd(cwd: &str) -> String {
-    if let Some(home) = std::env::var("HOME").ok() {
-        cwd.replace(&home, "~")
+    if let Ok(home) = home_dir() {
+        cwd.replace(&*home.to_string_lossy(), "~")
     } else {
         cwd.to_string()
     }
 }
 
-pub fn get_status_label(status: &str) -> &str {
-    match status {
-        "active" => "実行中",
-        "waiting" => "承認待ち",
-        "stopped" => "完了",
-        _ => "不明",
-    }
-}
-
-pub fn get_status_color(status: &str) -> ratatui::style::Color {
-    match status {
-        "active" => ratatui::style::Color::Green,
-        "waiting" => ratatui::style::Color::Yellow,
-        "stopped" => ratatui::style::Color::Gray,
-        _ => ratatui::style::Color::White,
-    }
+/// cwdの末尾のディレクトリ名（Windowsの区切り文字 `\` にも対応）
+pub fn format_dir_name(cwd: &str) -> &str {
+    cwd.rsplit(['/', '\\']).next().unwrap_or(cwd)
 }
 
 pub fn truncate_text(text: &str, max_chars: usize) -> String {
@@ -60,54 +108,239 @@ pub fn truncate_text(text: &str, max_chars: usize) -> String {
     }
 }
 
-pub fn format_relative_time(timestamp_str: &str) -> String {
+/// メモリ使用量（KB）を "512MB" / "1.5GB" 形式にする
+pub fn format_memory(kb: u64) -> String {
+    let mb = kb / 1024;
+    if mb >= 1024 {
+        format!("{:.1}GB", mb as f64 / 1024.0)
+    } else {
+        format!("{}MB", mb)
+    }
+}
+
+/// トークン数を "12.3k" / "1.2M" 形式にする
+pub fn format_tokens(tokens: u64) -> String {
+    if tokens >= 1_000_000 {
+        format!("{:.1}M", tokens as f64 / 1_000_000.0)
+    } else if tokens >= 1_000 {
+        format!("{:.1}k", tokens as f64 / 1_000.0)
+    } else {
+        tokens.to_string()
+    }
+}
+
+/// "入力/出力 tok · $コスト" 形式の使用量表示
+pub fn format_token_usage(usage: &TokenUsage) -> String {
+    format!(
+        "{}/{} tok · {}",
+        format_tokens(usage.total_input()),
+        format_tokens(usage.output_tokens),
+        format_cost(usage)
+    )
+}
+
+/// 推定コスト（"~$1.23"、単価の分からないモデルを含めば "~$1.23+?"、それだけなら "?"）
+pub fn format_cost(usage: &TokenUsage) -> String {
+    match (usage.unpriced, usage.cost_usd > 0.0) {
+        (true, false) => "?".to_string(),
+        (true, true) => format!("~${:.2}+?", usage.cost_usd),
+        (false, _) => format!("~${:.2}", usage.cost_usd),
+    }
+}
+
+/// コンテキストの使用率のゲージ（"▰▰▰▱▱ 78%"）
+pub fn format_context_gauge(percent: u64) -> String {
+    let filled = (percent.min(100) as usize + 10) / 20;
+    let (on, off) = if ascii_mode() { ("#", "-") } else { ("▰", "▱") };
+    format!("{}{} {}%", on.repeat(filled), off.repeat(5 - filled), percent)
+}
+
+/// 秒数を "42s" / "3m42s" / "1h05m" 形式にする
+pub fn format_duration(secs: u64) -> String {
+    if secs < 60 {
+        format!("{}s", secs)
+    } else if secs < 3600 {
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{}h{:02}m", secs / 3600, (secs % 3600) / 60)
+    }
+}
+
+/// 作業ツリーの状態を "±3 ↑1 ↓2" 形式にする（変更もupstreamとの差もなければNone）
+pub fn format_git_status(status: &GitStatus) -> Option<String> {
+    if status.is_clean() {
+        return None;
+    }
+    let mut parts = vec![];
+    if status.dirty > 0 {
+        parts.push(format!("{}{}", glyph("±", "~"), status.dirty));
+    }
+    if status.ahead > 0 {
+        parts.push(format!("{}{}", glyph("↑", "+"), status.ahead));
+    }
+    if status.behind > 0 {
+        parts.push(format!("{}{}", glyph("↓", "-"), status.behind));
+    }
+    Some(parts.join(" "))
+}
+
+/// updated（UNIX秒）からの経過秒数
+pub fn elapsed_since(updated: u64) -> u64 {
     use std::time::{SystemTime, UNIX_EPOCH};
 
-    if let Ok(parsed) = chrono::DateTime::parse_from_rfc3339(timestamp_str) {
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs() as i64;
-        let modified_ts = parsed.timestamp();
-        let diff = now - modified_ts;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    now.saturating_sub(updated)
+}
 
-        if diff < 60 {
-            return "たった今".to_string();
-        } else if diff < 3600 {
-            return format!("{}分前", diff / 60);
-        } else if diff < 86400 {
-            return format!("{}時間前", diff / 3600);
-        } else {
-            return format!("{}日前", diff / 86400);
-        }
+/// 実行中・承認待ちのセッションの経過時間（それ以外はNone）
+pub fn status_elapsed(session: &Session) -> Option<u64> {
+    session.status.is_running().then(|| elapsed_since(session.updated))
+}
+
+/// この秒数以内の未来の時刻は時計のずれとみなして「たった今」にする
+const CLOCK_SKEW_TOLERANCE: i64 = 300;
+
+/// 現在との差（秒、正なら過去）を "5分前" / "5 min ago" 形式にする
+fn format_relative_secs(diff: i64) -> String {
+    let future = diff < -CLOCK_SKEW_TOLERANCE;
+    let abs = diff.unsigned_abs();
+    if !future && diff < 60 {
+        return match locale() {
+            Locale::Ja => "たった今".to_string(),
+            Locale::En => "just now".to_string(),
+        };
+    }
+
+    let (value, ja_unit, en_unit) = if abs < 3600 {
+        (abs / 60, "分", "min")
+    } else if abs < 86400 {
+        (abs / 3600, "時間", "h")
+    } else {
+        (abs / 86400, "日", "d")
+    };
+    match (locale(), future) {
+        (Locale::Ja, false) => format!("{}{}前", value, ja_unit),
+        (Locale::Ja, true) => format!("{}{}後", value, ja_unit),
+        (Locale::En, false) => format!("{} {} ago", value, en_unit),
+        (Locale::En, true) => format!("in {} {}", value, en_unit),
     }
+}
 
-    "不明".to_string()
+fn unknown_time() -> String {
+    match locale() {
+        Locale::Ja => "不明".to_string(),
+        Locale::En => "unknown".to_string(),
+    }
 }
 
-pub fn display_sessions(sessions: &[Session]) {
-    println!("\n📋 Claude Codeセッション一覧\n");
+pub fn format_relative_time(timestamp_str: &str) -> String {
+    match chrono::DateTime::parse_from_rfc3339(timestamp_str) {
+        Ok(parsed) => format_relative_secs(chrono::Utc::now().timestamp() - parsed.timestamp()),
+        Err(_) => unknown_time(),
+    }
+}
+
+/// UNIX秒をローカル時刻の "HH:MM"（今日以外は "MM/DD HH:MM"）にする
+pub fn format_clock_time(timestamp: i64) -> String {
+    let Some(time) = chrono::DateTime::from_timestamp(timestamp, 0) else {
+        return unknown_time();
+    };
+    let time = time.with_timezone(&chrono::Local);
+    if time.date_naive() == chrono::Local::now().date_naive() {
+        time.format("%H:%M").to_string()
+    } else {
+        time.format("%m/%d %H:%M").to_string()
+    }
+}
 
+/// トランスクリプトのタイムスタンプ（RFC 3339）を時刻表示にする
+pub fn format_message_time(timestamp: &str) -> Option<String> {
+    let time = chrono::DateTime::parse_from_rfc3339(timestamp).ok()?;
+    Some(format_clock_time(time.timestamp()))
+}
+
+/// RFC3339のタイムスタンプを format_clock_time の形式にする
+pub fn format_absolute_time(timestamp_str: &str) -> String {
+    match chrono::DateTime::parse_from_rfc3339(timestamp_str) {
+        Ok(parsed) => format_clock_time(parsed.timestamp()),
+        Err(_) => unknown_time(),
+    }
+}
+
+/// RFC3339のタイムスタンプをローカル時刻の "YYYY-MM-DD HH:MM" にする
+pub fn format_datetime(timestamp_str: &str) -> String {
+    match chrono::DateTime::parse_from_rfc3339(timestamp_str) {
+        Ok(parsed) => parsed
+            .with_timezone(&chrono::Local)
+            .format("%Y-%m-%d %H:%M")
+            .to_string(),
+        Err(_) => "不明".to_string(),
+    }
+}
+
+pub fn display_sessions(sessions: &[Session], state: &State) {
+    println!("\n{}Claude Codeセッション一覧\n", glyph("📋 ", ""));
+
+    // リモートのセッションがあればホストごとに見出しを付ける
+    let multi_host = sessions.iter().any(|s| s.host.is_some());
+    let mut current_host = None;
     for session in sessions {
-        let icon = get_status_icon(&session.status);
-        let status_label = get_status_label(&session.status);
+        if multi_host && current_host != Some(&session.host) {
+            current_host = Some(&session.host);
+            let host = session.host.as_deref().unwrap_or("local");
+            println!("{} {} {}\n", separator(), host, separator());
+        }
+        let icon = session.status_icon();
+        let status_label = session.status_label();
         let cwd = format_cwd(&session.cwd);
+        let pin = if state.is_pinned(&session.session_id) { glyph(" 📌", " ^") } else { "" };
 
-        println!(
-            "{} {:<10} {}  (pane:{})",
-            icon, status_label, cwd, session.pane_id
-        );
+        let location = match session.host {
+            Some(ref host) => format!("{}:pane:{}", host, session.pane_id),
+            None => format!("pane:{}", session.pane_id),
+        };
+        println!("{} {:<10} {}{}  ({})", icon, status_label, cwd, pin, location);
 
-        // notification_messageがあれば表示
-        if let Some(ref msg) = session.notification_message {
-            println!("   └─ {}", msg);
+        // 承認待ちの理由か実行中のツールがあれば表示
+        if let Some(ref reason) = session.waiting_reason {
+            println!("   {} {}", tree(), reason.label());
+        } else if let Some(ref tool) = session.current_tool {
+            println!("   {} {}{}", tree(), glyph("⚙ ", ""), truncate_text(tool, 60));
+        }
+        if let Some(ref message) = session.last_message {
+            println!("   {} {}{}", tree(), glyph("💬 ", "> "), truncate_text(message, 60));
+        }
+
+        if !session.subagents.is_empty() {
+            println!("   {} {}サブエージェント{}件が実行中", tree(), glyph("🤖 ", ""), session.subagents.len());
+        }
+        if !session.pane_conflicts.is_empty() {
+            let files: Vec<String> = session
+                .pane_conflicts
+                .iter()
+                .map(|c| c.path.file_name().unwrap_or_default().to_string_lossy().to_string())
+                .collect();
+            println!(
+                "   {} {}同じペインを指す古いセッションファイル: {}",
+                tree(),
+                glyph("⚠ ", "! "),
+                files.join(", ")
+            );
+        }
+
+        // メモ・タグがあれば表示
+        if let Some(note) = state.note(&session.session_id) {
+            println!("   {} {}{}", tree(), glyph("📝 ", ""), note.to_input());
         }
 
         // summaryまたはfirst_promptがあれば表示
         if let Some(ref summary) = session.summary {
-            println!("   └─ \"{}\"", truncate_text(summary, 60));
+            println!("   {} \"{}\"", tree(), truncate_text(summary, 60));
         } else if let Some(ref first_prompt) = session.first_prompt {
-            println!("   └─ \"{}\"", truncate_text(first_prompt, 60));
+            println!("   {} \"{}\"", tree(), truncate_text(first_prompt, 60));
         }
 
         // メッセージ数、メモリ使用量、Gitブランチ、最終更新時刻を表示
@@ -118,24 +351,39 @@ pub fn display_sessions(sessions: &[Session]) {
         }
 
         if let Some(mem_kb) = session.memory_usage_kb {
-            let mem_mb = mem_kb / 1024;
-            if mem_mb >= 1024 {
-                meta_parts.push(format!("{:.1}GB", mem_mb as f64 / 1024.0));
-            } else {
-                meta_parts.push(format!("{}MB", mem_mb));
-            }
+            meta_parts.push(format_memory(mem_kb));
         }
 
         if let Some(ref branch) = session.git_branch {
             meta_parts.push(format!("@{}", branch));
         }
 
+        if let Some(git) = session.git_status.as_ref().and_then(format_git_status) {
+            meta_parts.push(git);
+        }
+
+        if let Some(ref usage) = session.token_usage {
+            meta_parts.push(format_token_usage(usage));
+        }
+
+        if let Some(percent) = session.context_percent() {
+            meta_parts.push(format!("ctx {}%", percent));
+        }
+
+        if let Some(level) = session_budget_level(session) {
+            meta_parts.push(format!("{} {}", level.icon(), level.label()));
+        }
+
+        if let Some(model) = session.model_family() {
+            meta_parts.push(format!("[{}]", model));
+        }
+
         if let Some(ref modified) = session.modified {
             meta_parts.push(format_relative_time(modified));
         }
 
         if !meta_parts.is_empty() {
-            println!("   └─ {}", meta_parts.join(" · "));
+            println!("   {} {}", tree(), meta_parts.join(" · "));
         }
 
         println!();
@@ -143,3 +391,401 @@ pub fn display_sessions(sessions: &[Session]) {
 
     println!("合計: {}セッション\n", sessions.len());
 }
+
+/// "2 waiting · 1 active" 形式の集計テキスト（優先度の高いステータスから、スヌーズ中は除く）
+fn status_summary_text(sessions: &[Session]) -> String {
+    let mut statuses: Vec<&SessionStatus> = Vec::new();
+    for session in sessions.iter().filter(|s| !s.snoozed) {
+        if !statuses.contains(&&session.status) {
+            statuses.push(&session.status);
+        }
+    }
+    statuses.sort_by_key(|s| std::cmp::Reverse(s.severity()));
+    statuses
+        .iter()
+        .map(|status| {
+            let count = sessions.iter().filter(|s| s.status == **status && !s.snoozed).count();
+            format!("{} {}", count, status)
+        })
+        .collect::<Vec<_>>()
+        .join(" · ")
+}
+
+/// `check` サブコマンドの出力（スヌーズ中でない承認待ちのセッションを列挙）
+pub fn display_check(sessions: &[Session]) {
+    let waiting: Vec<&Session> = sessions
+        .iter()
+        .filter(|s| s.status == SessionStatus::Waiting && !s.snoozed)
+        .collect();
+    if waiting.is_empty() {
+        println!("{}承認待ちのセッションはありません", glyph("✅ ", ""));
+        return;
+    }
+
+    println!("{} {}件のセッションが承認待ちです", SessionStatus::Waiting.icon(), waiting.len());
+    for session in waiting {
+        let mut line = format!("  {}  (pane:{})", format_cwd(&session.cwd), session.pane_id);
+        if let Some(ref reason) = session.waiting_reason {
+            line.push_str(&format!(" {}", reason.label()));
+        }
+        println!("{}", line);
+    }
+}
+
+/// ステータスバー向けの1行出力（waybar: JSON / plain: テキスト）
+pub fn display_status(sessions: &[Session], format: &str) -> anyhow::Result<()> {
+    let text = status_summary_text(sessions);
+
+    match format {
+        "waybar" => {
+            let class = sessions
+                .iter()
+                .filter(|s| !s.snoozed)
+                .max_by_key(|s| s.status.severity())
+                .map(|s| s.status.as_str())
+                .unwrap_or("none");
+
+            let tooltip = sessions
+                .iter()
+                .filter(|s| !s.snoozed)
+                .map(|s| {
+                    let mut line = format!(
+                        "{} {} {}",
+                        s.status_icon(),
+                        s.status_label(),
+                        format_cwd(&s.cwd)
+                    );
+                    if let Some(ref reason) = s.waiting_reason {
+                        line.push_str(&format!(" ({})", reason.label()));
+                    }
+                    line
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            let payload = serde_json::json!({
+                "text": text,
+                "tooltip": tooltip,
+                "class": class,
+            });
+            println!("{}", payload);
+        }
+        "plain" => {
+            println!("{}", text);
+        }
+        _ => {
+            return Err(anyhow::anyhow!(
+                "不明なフォーマット: {} (waybar, plain のいずれかを指定)",
+                format
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+pub fn display_cost_summary(summaries: &[ProjectUsage]) {
+    println!("\n{}プロジェクト別トークン使用量（推定）\n", glyph("💰 ", ""));
+
+    if summaries.is_empty() {
+        println!("トランスクリプトが見つかりません\n");
+        return;
+    }
+
+    println!(
+        "{:<40} {:>6} {:>10} {:>10} {:>10}",
+        "プロジェクト", "件数", "入力", "出力", "コスト"
+    );
+
+    let mut total = TokenUsage::default();
+    for summary in summaries {
+        println!(
+            "{:<40} {:>6} {:>10} {:>10} {:>10}",
+            truncate_text(&format_cwd(&summary.project), 40),
+            summary.session_count,
+            format_tokens(summary.usage.total_input()),
+            format_tokens(summary.usage.output_tokens),
+            format_cost(&summary.usage)
+        );
+        total.add(&summary.usage);
+    }
+
+    println!(
+        "\n合計: 入力 {} · 出力 {} · {}\n",
+        format_tokens(total.total_input()),
+        format_tokens(total.output_tokens),
+        format_cost(&total)
+    );
+    if total.unpriced {
+        println!("? は単価の分からないモデルの分です（[pricing] で単価を指定できます）\n");
+    }
+}
+
+pub fn display_history(entries: &[HistoryEntry]) {
+    println!("\n{}過去のセッション\n", glyph("📜 ", ""));
+
+    for entry in entries {
+        let date = entry
+            .modified
+            .as_deref()
+            .map(format_datetime)
+            .unwrap_or_else(|| "不明".to_string());
+
+        println!("{}  {}  {}", date, format_cwd(&entry.project_path), entry.session_id);
+
+        if let Some(ref summary) = entry.summary {
+            println!("   {} \"{}\"", tree(), truncate_text(summary, 60));
+        } else if let Some(ref first_prompt) = entry.first_prompt {
+            println!("   {} \"{}\"", tree(), truncate_text(first_prompt, 60));
+        }
+
+        let mut meta_parts = vec![];
+        if let Some(count) = entry.message_count {
+            meta_parts.push(format!("{}msg", count));
+        }
+        if let Some(ref branch) = entry.git_branch {
+            meta_parts.push(format!("@{}", branch));
+        }
+        if !meta_parts.is_empty() {
+            println!("   {} {}", tree(), meta_parts.join(" · "));
+        }
+
+        println!();
+    }
+
+    println!("合計: {}セッション（claude --resume <id> で再開）\n", entries.len());
+}
+
+pub fn display_orphaned_sessions(sessions: &[Session]) {
+    println!("\n{}ペインがなくなったセッション\n", glyph("👻 ", ""));
+
+    if sessions.is_empty() {
+        println!("ありません\n");
+        return;
+    }
+
+    for session in sessions {
+        println!(
+            "{} {}  {}  (pane:{})",
+            session.status_icon(),
+            format_cwd(&session.cwd),
+            format_relative_secs(chrono::Utc::now().timestamp() - session.updated as i64),
+            session.pane_id
+        );
+        if let Some(ref summary) = session.summary {
+            println!("   {} \"{}\"", tree(), truncate_text(summary, 60));
+        } else if let Some(ref first_prompt) = session.first_prompt {
+            println!("   {} \"{}\"", tree(), truncate_text(first_prompt, 60));
+        }
+        println!("   {} claude-watch resume {}", tree(), session.session_id);
+        println!();
+    }
+
+    println!("合計: {}セッション（claude-watch clean で古いセッションファイルを削除）\n", sessions.len());
+}
+
+pub fn display_search_results(query: &str, results: &[SearchResult], sessions: &[Session]) {
+    println!("\n{}「{}」の検索結果\n", glyph("🔍 ", ""), query);
+
+    if results.is_empty() {
+        println!("一致する会話はありません\n");
+        return;
+    }
+
+    for result in results {
+        let modified = chrono::DateTime::<chrono::Utc>::from(result.modified).to_rfc3339();
+        let active = sessions.iter().find(|s| s.session_id == result.session_id);
+        let state = match active {
+            Some(session) => format!("  {} {}", session.status.icon(), session.status.label()),
+            None => String::new(),
+        };
+        println!(
+            "{}  {}  {}{}",
+            format_relative_time(&modified),
+            format_cwd(&result.cwd),
+            result.session_id,
+            state
+        );
+
+        for m in &result.matches {
+            let time = m.timestamp.as_deref().and_then(format_message_time);
+            let time = time.map(|t| format!(" {}", t)).unwrap_or_default();
+            println!("   {} [{}{}] {}", tree(), m.role.label(), time, m.snippet);
+        }
+        if result.match_count > result.matches.len() {
+            println!("   {} ほか{}件", tree(), result.match_count - result.matches.len());
+        }
+        if active.is_none() {
+            println!("   {} claude --resume {}", tree(), result.session_id);
+        }
+        println!();
+    }
+
+    println!("合計: {}セッション（claude-watch jump <id> / resume <id>）\n", results.len());
+}
+
+/// ステータス変化を "new" / "gone" も含めて表示用の名前にする
+fn transition_label(status: &str) -> String {
+    match status {
+        "new" => "開始".to_string(),
+        "gone" => "終了".to_string(),
+        status => SessionStatus::parse(status).label().to_string(),
+    }
+}
+
+pub fn display_events(events: &[StoredTransition]) {
+    println!("\n{}ステータスの変化\n", glyph("📒 ", ""));
+
+    for event in events {
+        display_event(event);
+    }
+
+    println!("\n合計: {}件\n", events.len());
+}
+
+/// ステータス変化1件（`events --follow` でも使う）
+pub fn display_event(event: &StoredTransition) {
+    println!(
+        "{}  {}  {} → {}  {}",
+        format_clock_time(event.updated as i64),
+        format_cwd(&event.cwd),
+        transition_label(&event.from),
+        transition_label(&event.to),
+        event.session_id
+    );
+    if let Some(from_updated) = event.from_updated
+        && event.from != "new"
+    {
+        let stayed = event.updated.saturating_sub(from_updated);
+        println!("   {} {}のまま {}", tree(), transition_label(&event.from), format_duration(stayed));
+    }
+    if let Some(ref summary) = event.summary {
+        println!("   {} \"{}\"", tree(), truncate_text(summary, 60));
+    }
+}
+
+/// 最大値に対する割合の棒（幅widthの文字数）
+fn text_bar(value: u64, max: u64, width: usize) -> String {
+    if max == 0 {
+        return String::new();
+    }
+    let len = ((value * width as u64).div_ceil(max)) as usize;
+    glyph("█", "#").repeat(len)
+}
+
+/// `stats` サブコマンドの出力
+pub fn display_stats(stats: &Stats) {
+    println!(
+        "\n{}セッション統計（直近{}日 · {}セッション）\n",
+        glyph("📊 ", ""),
+        stats.per_day.len(),
+        stats.total_sessions
+    );
+
+    println!("日ごとのセッション数");
+    let max = stats.per_day.iter().map(|&(_, n)| n as u64).max().unwrap_or(0);
+    for &(date, count) in &stats.per_day {
+        println!("  {}  {:>3} {}", date.format("%m/%d"), count, text_bar(count as u64, max, 30));
+    }
+
+    if !stats.projects.is_empty() {
+        println!("\nセッションの多いプロジェクト");
+        for project in &stats.projects {
+            println!(
+                "  {:<40} {:>4}件 {:>8}",
+                truncate_text(&format_cwd(&project.project), 40),
+                project.sessions,
+                format_duration(project.total_secs)
+            );
+        }
+    }
+
+    if !stats.longest.is_empty() {
+        println!("\n長いセッション");
+        for entry in &stats.longest {
+            let summary = entry.summary.as_deref().or(entry.first_prompt.as_deref()).unwrap_or("");
+            println!(
+                "  {:>8}  {}  {}",
+                format_duration(entry.duration_secs().unwrap_or(0)),
+                format_cwd(&entry.project_path),
+                truncate_text(summary, 40)
+            );
+        }
+    }
+
+    println!("\n現在のステータス");
+    if stats.statuses.is_empty() {
+        println!("  アクティブなセッションはありません");
+    }
+    for (status, count) in &stats.statuses {
+        println!("  {} {:<8} {:>3}", status.icon(), status.label(), count);
+    }
+    if let Some(avg) = stats.waiting_avg {
+        println!("  承認待ちの平均待ち時間: {}", format_duration(avg));
+    }
+
+    println!("\n承認までの時間");
+    match stats.approval_today {
+        Some((avg, count)) => println!("  今日の平均: {}（{}件）", format_duration(avg), count),
+        None => println!("  今日の記録はありません（TUIかdaemonの実行中に記録されます）"),
+    }
+    for session in &stats.waiting_over_avg {
+        println!(
+            "  {}{} が平均より長く待っています（{}）",
+            glyph("⏳ ", "! "),
+            format_cwd(&session.cwd),
+            format_duration(elapsed_since(session.updated))
+        );
+    }
+    println!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rfc3339_ago(secs: i64) -> String {
+        (chrono::Utc::now() - chrono::Duration::seconds(secs)).to_rfc3339()
+    }
+
+    #[test]
+    fn truncate_text_keeps_short_text() {
+        assert_eq!(truncate_text("hello", 5), "hello");
+        assert_eq!(truncate_text("", 3), "");
+    }
+
+    #[test]
+    fn truncate_text_appends_ellipsis() {
+        assert_eq!(truncate_text("hello world", 8), "hello...");
+        assert_eq!(truncate_text("hello", 2), "...");
+    }
+
+    #[test]
+    fn truncate_text_counts_wide_characters_as_one() {
+        assert_eq!(truncate_text("日本語のテキスト", 8), "日本語のテキスト");
+        assert_eq!(truncate_text("日本語のテキストです", 8), "日本語のテ...");
+        assert_eq!(truncate_text("🟢🟡⚪🟢🟡", 4), "🟢...");
+    }
+
+    #[test]
+    fn format_relative_time_in_japanese() {
+        init_locale(Some(Locale::Ja));
+        assert_eq!(format_relative_time(&rfc3339_ago(10)), "たった今");
+        assert_eq!(format_relative_time(&rfc3339_ago(5 * 60)), "5分前");
+        assert_eq!(format_relative_time(&rfc3339_ago(3 * 3600)), "3時間前");
+        assert_eq!(format_relative_time(&rfc3339_ago(2 * 86400)), "2日前");
+    }
+
+    #[test]
+    fn format_relative_time_tolerates_clock_skew() {
+        init_locale(Some(Locale::Ja));
+        assert_eq!(format_relative_time(&rfc3339_ago(-60)), "たった今");
+        assert_eq!(format_relative_time(&rfc3339_ago(-2 * 3600)), "2時間後");
+    }
+
+    #[test]
+    fn format_relative_time_rejects_invalid_timestamp() {
+        init_locale(Some(Locale::Ja));
+        assert_eq!(format_relative_time("yesterday"), "不明");
+    }
+}
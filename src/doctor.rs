@@ -0,0 +1,220 @@
+use anyhow::Result;
+use std::fs;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::backend::current_backend;
+use crate::display::{format_duration, glyph, tree};
+use crate::hooks::HOOK_EVENTS;
+use crate::process::list_claude_processes;
+use crate::session::{
+    check_index_files, get_claude_settings_path, get_sessions_dir, load_sessions_lenient, LoadError,
+};
+
+enum Level {
+    Ok,
+    Warn,
+    Fail,
+}
+
+/// 1項目の診断結果
+struct Check {
+    level: Level,
+    title: &'static str,
+    detail: String,
+    details: Vec<String>,
+    fix: Option<String>,
+}
+
+impl Check {
+    fn new(level: Level, title: &'static str, detail: impl Into<String>) -> Self {
+        Check {
+            level,
+            title,
+            detail: detail.into(),
+            details: Vec::new(),
+            fix: None,
+        }
+    }
+
+    fn fix(mut self, fix: impl Into<String>) -> Self {
+        self.fix = Some(fix.into());
+        self
+    }
+
+    fn print(&self) {
+        let icon = match self.level {
+            Level::Ok => glyph("✅", "[OK]"),
+            Level::Warn => glyph("⚠️ ", "[WARN]"),
+            Level::Fail => glyph("❌", "[FAIL]"),
+        };
+        println!("{} {}: {}", icon, self.title, self.detail);
+        for line in &self.details {
+            println!("   {}", line);
+        }
+        if let Some(ref fix) = self.fix {
+            println!("   {} {}", glyph("→", "->"), fix);
+        }
+    }
+}
+
+fn file_error_lines(errors: &[LoadError]) -> Vec<String> {
+    errors
+        .iter()
+        .flat_map(|error| {
+            [
+                error.path.display().to_string(),
+                format!("  {} {}", tree(), error.message),
+            ]
+        })
+        .collect()
+}
+
+fn check_sessions_dir() -> Result<Check> {
+    let dir = get_sessions_dir()?;
+    Ok(if dir.is_dir() {
+        Check::new(Level::Ok, "セッションディレクトリ", dir.display().to_string())
+    } else {
+        Check::new(Level::Fail, "セッションディレクトリ", format!("{} がありません", dir.display()))
+            .fix("claude-watch install-hooks でフックを登録してから、Claude Codeを起動してください")
+    })
+}
+
+fn check_hooks() -> Result<Check> {
+    let path = get_claude_settings_path()?;
+    let title = "フック設定";
+    let required: Vec<&str> = HOOK_EVENTS.iter().map(|(event, _)| *event).collect();
+    let Ok(content) = fs::read_to_string(&path) else {
+        return Ok(Check::new(Level::Fail, title, format!("{} がありません", path.display()))
+            .fix("claude-watch install-hooks でフックを登録してください"));
+    };
+    let settings: serde_json::Value = match serde_json::from_str(&content) {
+        Ok(settings) => settings,
+        Err(e) => {
+            return Ok(Check::new(Level::Fail, title, format!("{} を解析できません: {}", path.display(), e))
+                .fix("JSONの構文を修正してください"));
+        }
+    };
+
+    let missing: Vec<&str> = required
+        .iter()
+        .copied()
+        .filter(|event| {
+            settings["hooks"][*event]
+                .as_array()
+                .is_none_or(|entries| entries.is_empty())
+        })
+        .collect();
+    Ok(if missing.is_empty() {
+        Check::new(Level::Ok, title, format!("{} を登録済み", required.join(" / ")))
+    } else {
+        Check::new(Level::Warn, title, format!("{} のフックがありません", missing.join(" / ")))
+            .fix("claude-watch install-hooks でフックを登録してください")
+    })
+}
+
+fn check_session_files() -> Result<Vec<Check>> {
+    let (sessions, errors) = load_sessions_lenient()?;
+    let mut checks = Vec::new();
+
+    // 最後にフックが書き込んだ時刻
+    let title = "ステータスファイル";
+    checks.push(match sessions.iter().map(|s| s.updated).max() {
+        Some(latest) => {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs();
+            Check::new(
+                Level::Ok,
+                title,
+                format!("{}件（最終更新 {}前）", sessions.len(), format_duration(now.saturating_sub(latest))),
+            )
+        }
+        None => Check::new(Level::Warn, title, "セッションファイルがありません")
+            .fix("Claude Codeを起動して、フックが ~/.claude/sessions/<id>.json を書き出すか確認してください"),
+    });
+
+    if !errors.is_empty() {
+        let sessions_dir = get_sessions_dir()?;
+        let mut check = Check::new(
+            Level::Fail,
+            "セッションファイルの解析",
+            format!("{}件中{}件が読み込めません", sessions.len() + errors.len(), errors.len()),
+        )
+        .fix(format!("不要なファイルなら削除してください: rm {}/<ファイル名>", sessions_dir.display()));
+        check.details = file_error_lines(&errors);
+        checks.push(check);
+    }
+    Ok(checks)
+}
+
+fn check_index() -> Result<Check> {
+    let (parsed, errors) = check_index_files()?;
+    let title = "sessions-index.json";
+    Ok(if errors.is_empty() {
+        Check::new(Level::Ok, title, format!("{}件すべて解析できました", parsed))
+    } else {
+        let mut check = Check::new(
+            Level::Warn,
+            title,
+            format!("{}件中{}件が解析できません（要約・履歴が表示されません）", parsed + errors.len(), errors.len()),
+        )
+        .fix("Claude Codeがindexを再生成するまで待つか、壊れたファイルを削除してください");
+        check.details = file_error_lines(&errors);
+        check
+    })
+}
+
+fn check_backend() -> Check {
+    let backend = current_backend();
+    let title = "バックエンド";
+    match backend.list_panes() {
+        Ok(Some(panes)) => Check::new(Level::Ok, title, format!("{}（{}ペイン）", backend.name(), panes.len())),
+        Ok(None) => Check::new(
+            Level::Ok,
+            title,
+            format!("{}（ペインを列挙できないためペインでの絞り込みなし）", backend.name()),
+        ),
+        Err(e) => Check::new(Level::Fail, title, format!("{}: {:#}", backend.name(), e))
+            .fix("CLIのパスと実行権限を確認するか、--backend で別のバックエンドを指定してください"),
+    }
+}
+
+fn check_processes() -> Check {
+    let title = "プロセス検出";
+    match list_claude_processes() {
+        Some(processes) => Check::new(
+            Level::Ok,
+            title,
+            format!("Claude Codeのプロセス {}件", processes.len()),
+        ),
+        None => Check::new(Level::Warn, title, "無効（/procが無いか process_liveness = false）")
+            .fix("終了したセッションが「実行中」のまま残る場合は process_liveness を有効にしてください"),
+    }
+}
+
+/// セットアップの問題を調べて修正方法と一緒に表示する
+pub fn run_doctor() -> Result<()> {
+    println!("\n{}claude-watch doctor\n", glyph("🩺 ", ""));
+
+    let mut checks = vec![check_sessions_dir()?, check_hooks()?];
+    checks.extend(check_session_files()?);
+    checks.push(check_index()?);
+    checks.push(check_backend());
+    checks.push(check_processes());
+
+    for check in &checks {
+        check.print();
+    }
+
+    let problems = checks
+        .iter()
+        .filter(|c| !matches!(c.level, Level::Ok))
+        .count();
+    if problems == 0 {
+        println!("\n{}問題は見つかりませんでした\n", glyph("✨ ", ""));
+    } else {
+        println!("\n{}件の項目を確認してください\n", problems);
+    }
+    Ok(())
+}
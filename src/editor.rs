@@ -0,0 +1,37 @@
+use anyhow::{anyhow, Context, Result};
+use std::process::Stdio;
+
+use crate::alerts::{shell_args, shell_command, shell_quote};
+use crate::backend::current_backend;
+use crate::config::EditorConfig;
+
+/// セッションのディレクトリをエディタで開く
+///
+/// コマンドの指定がなければ $VISUAL / $EDITOR を新しいペインで開く。
+pub fn open_in_editor(config: &EditorConfig, cwd: &str) -> Result<()> {
+    let (command, pane) = match config.command {
+        Some(ref template) => (template.replace("{cwd}", &shell_quote(cwd)), config.pane),
+        None => {
+            let editor = ["VISUAL", "EDITOR"]
+                .iter()
+                .find_map(|var| std::env::var(var).ok().filter(|v| !v.is_empty()))
+                .ok_or_else(|| anyhow!("エディタが設定されていません（[editor] command か $EDITOR を指定してください）"))?;
+            (format!("{} .", editor), true)
+        }
+    };
+
+    tracing::debug!(cwd, %command, pane, "エディタを起動");
+    if pane {
+        current_backend().spawn_in_cwd(cwd, &shell_args(&command))?;
+        return Ok(());
+    }
+    // GUIのエディタは終了を待たない
+    shell_command(&command)
+        .current_dir(cwd)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("エディタの起動に失敗: {}", cwd))?;
+    Ok(())
+}
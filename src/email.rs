@@ -0,0 +1,220 @@
+use anyhow::{anyhow, Context, Result};
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use crate::config::EmailConfig;
+use crate::display::{elapsed_since, format_clock_time, format_cwd, format_dir_name, format_duration};
+use crate::session::Session;
+use crate::status::SessionStatus;
+use crate::transitions::Transition;
+
+// 夜通しのバッチ実行などで、ステータス変化のまとめをメールで送る（curlのSMTP機能を使う）。
+
+/// まとめに載せるステータス変化の上限
+const MAX_LINES: usize = 500;
+
+struct Digest {
+    /// 前回の確認で実行中・承認待ちのセッションがあったか
+    busy: bool,
+    /// 前回送ってからのステータス変化
+    lines: Vec<String>,
+    /// 前回送った時刻（起動直後は起動時刻）
+    last_sent: Option<u64>,
+}
+
+static DIGEST: Mutex<Digest> = Mutex::new(Digest {
+    busy: false,
+    lines: Vec::new(),
+    last_sent: None,
+});
+
+/// 一時ファイル名の連番（送信が重なっても衝突しないように）
+static MAIL_SEQ: AtomicU64 = AtomicU64::new(0);
+
+const BASE64: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn base64(data: &[u8]) -> String {
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(BASE64[(n >> (18 - 6 * i)) as usize & 0x3f] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// "名前 <addr>" からアドレスだけを取り出す
+fn address(mailbox: &str) -> &str {
+    mailbox
+        .split_once('<')
+        .and_then(|(_, rest)| rest.split_once('>'))
+        .map_or(mailbox, |(addr, _)| addr)
+        .trim()
+}
+
+/// curlの設定ファイル形式の文字列
+fn curl_quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn password(email: &EmailConfig) -> Result<Option<String>> {
+    match email.password_env {
+        Some(ref var) => std::env::var(var)
+            .map(Some)
+            .with_context(|| format!("[email] password_env の環境変数 {} がありません", var)),
+        None => Ok(email.password.clone()),
+    }
+}
+
+fn build_message(email: &EmailConfig, subject: &str, body: &str) -> String {
+    let to: Vec<&str> = email.to.items().iter().map(String::as_str).collect();
+    let headers = [
+        format!("From: {}", email.from),
+        format!("To: {}", to.join(", ")),
+        format!("Subject: =?UTF-8?B?{}?=", base64(subject.as_bytes())),
+        format!("Date: {}", chrono::Local::now().to_rfc2822()),
+        "MIME-Version: 1.0".to_string(),
+        "Content-Type: text/plain; charset=UTF-8".to_string(),
+        "Content-Transfer-Encoding: 8bit".to_string(),
+    ];
+    format!("{}\r\n\r\n{}\r\n", headers.join("\r\n"), body.lines().collect::<Vec<_>>().join("\r\n"))
+}
+
+/// curlでメールを送る（認証情報はコマンドラインに出さず標準入力の設定で渡す）
+fn send_mail(email: &EmailConfig, subject: &str, body: &str) -> Result<()> {
+    let mut curl_config = String::new();
+    if let Some(ref username) = email.username {
+        let password = password(email)?.unwrap_or_default();
+        curl_config.push_str(&format!("user = {}\n", curl_quote(&format!("{}:{}", username, password))));
+    }
+
+    let message_path = std::env::temp_dir().join(format!(
+        "claude-watch-mail-{}-{}.eml",
+        std::process::id(),
+        MAIL_SEQ.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::write(&message_path, build_message(email, subject, body))
+        .with_context(|| format!("メールの一時ファイルを書き込めません: {:?}", message_path))?;
+
+    let mut command = Command::new("curl");
+    command
+        .args(["-sS", "-m", "30", "-K", "-", "--url", &email.smtp])
+        .args(["--mail-from", address(&email.from)]);
+    for to in email.to.items() {
+        command.args(["--mail-rcpt", address(to)]);
+    }
+    if email.tls {
+        command.arg("--ssl-reqd");
+    }
+    command.arg("--upload-file").arg(&message_path);
+
+    let result = (|| {
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("curlの実行に失敗")?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(curl_config.as_bytes())?;
+        }
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!("メールの送信に失敗しました: {}", stderr.trim()));
+        }
+        Ok(())
+    })();
+    let _ = std::fs::remove_file(&message_path);
+    result
+}
+
+/// セッション一覧（まとめの本文用）
+fn session_lines(sessions: &[Session]) -> String {
+    sessions
+        .iter()
+        .map(|session| {
+            let mut line = format!(
+                "{} {}  {}  ({}前)",
+                session.status.icon(),
+                session.status.label(),
+                format_cwd(&session.cwd),
+                format_duration(elapsed_since(session.updated))
+            );
+            if let Some(ref summary) = session.summary {
+                line.push_str(&format!("\n    {}", summary));
+            }
+            line
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn transition_line(transition: &Transition) -> String {
+    let mut line = format!(
+        "{}  {}  {} → {}",
+        format_clock_time(transition.timestamp as i64),
+        format_dir_name(&transition.session.cwd),
+        transition.old_label(),
+        transition.new_label()
+    );
+    if let Some(ref host) = transition.session.host {
+        line.push_str(&format!("  ({})", host));
+    }
+    line
+}
+
+fn spawn_send(email: &EmailConfig, subject: String, body: String) {
+    let email = email.clone();
+    thread::spawn(move || {
+        if let Err(e) = send_mail(&email, &subject, &body) {
+            tracing::warn!("{:#}", e);
+        }
+    });
+}
+
+/// ステータス変化を記録し、すべて完了したときと一定時間ごとにまとめを送る
+pub fn dispatch_email(email: Option<&EmailConfig>, sessions: &[Session], transitions: &[Transition]) {
+    let Some(email) = email else {
+        return;
+    };
+    let mut digest = DIGEST.lock().unwrap_or_else(|e| e.into_inner());
+    for transition in transitions {
+        if digest.lines.len() < MAX_LINES {
+            digest.lines.push(transition_line(transition));
+        }
+    }
+    let now = chrono::Utc::now().timestamp() as u64;
+    let last_sent = *digest.last_sent.get_or_insert(now);
+
+    let busy = sessions
+        .iter()
+        .any(|s| matches!(s.status, SessionStatus::Active | SessionStatus::Waiting));
+    let all_stopped = email.on_all_stopped && digest.busy && !busy;
+    digest.busy = busy;
+
+    let subject = if all_stopped {
+        format!("[claude-watch] すべてのセッションが完了しました（{}件）", sessions.len())
+    } else if email.digest_minutes > 0 && !digest.lines.is_empty() && now >= last_sent + email.digest_minutes * 60 {
+        format!("[claude-watch] ステータス変化のまとめ（{}件）", digest.lines.len())
+    } else {
+        return;
+    };
+
+    let mut body = format!("セッション\n\n{}\n", session_lines(sessions));
+    if !digest.lines.is_empty() {
+        body.push_str(&format!("\nステータス変化\n\n{}\n", digest.lines.join("\n")));
+    }
+    digest.lines.clear();
+    digest.last_sent = Some(now);
+    tracing::debug!(%subject, "まとめのメールを送る");
+    spawn_send(email, subject, body);
+}
@@ -0,0 +1,165 @@
+use anyhow::{anyhow, Result};
+use std::collections::HashSet;
+use std::time::Duration;
+
+use crate::display::{format_cwd, format_datetime, format_duration};
+use crate::session::{load_history, HistoryEntry};
+
+/// レポートの形式
+#[derive(Debug, Clone, Copy)]
+pub enum ReportFormat {
+    Csv,
+    Markdown,
+    Json,
+}
+
+impl ReportFormat {
+    pub fn parse(name: &str) -> Result<ReportFormat> {
+        match name {
+            "csv" => Ok(ReportFormat::Csv),
+            "md" | "markdown" => Ok(ReportFormat::Markdown),
+            "json" => Ok(ReportFormat::Json),
+            _ => Err(anyhow!("不明な形式: {} (csv, md, json のいずれかを指定)", name)),
+        }
+    }
+}
+
+/// "7d" / "12h" / "2w" / "30m" / "10s" 形式の期間
+pub fn parse_period(period: &str) -> Result<Duration> {
+    let invalid = || anyhow!("期間は 7d / 12h / 2w / 30m / 10s のように指定してください: {}", period);
+    let split = period.find(|c: char| !c.is_ascii_digit()).ok_or_else(invalid)?;
+    let (value, unit) = period.split_at(split);
+    let value: u64 = value.parse().map_err(|_| invalid())?;
+    let unit_secs = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86400,
+        "w" => 7 * 86400,
+        _ => return Err(invalid()),
+    };
+    Ok(Duration::from_secs(value * unit_secs))
+}
+
+/// 期間内（最終更新がsince以内）のセッション。期間の指定がなければ全件
+pub fn sessions_since(since: Option<Duration>) -> Result<Vec<HistoryEntry>> {
+    let history = load_history(&HashSet::new())?;
+    let Some(since) = since else {
+        return Ok(history);
+    };
+    let threshold = chrono::Utc::now() - chrono::Duration::seconds(since.as_secs() as i64);
+    Ok(history
+        .into_iter()
+        .filter(|entry| {
+            entry
+                .modified
+                .as_deref()
+                .and_then(|m| chrono::DateTime::parse_from_rfc3339(m).ok())
+                .is_some_and(|modified| modified >= threshold)
+        })
+        .collect())
+}
+
+/// 要約（なければ最初のプロンプト）
+fn entry_summary(entry: &HistoryEntry) -> &str {
+    entry
+        .summary
+        .as_deref()
+        .or(entry.first_prompt.as_deref())
+        .unwrap_or("")
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn render_csv(entries: &[HistoryEntry]) -> String {
+    let mut out = String::from("date,project,branch,duration_secs,messages,summary,session_id\n");
+    for entry in entries {
+        let fields = [
+            entry.modified.as_deref().map(format_datetime).unwrap_or_default(),
+            entry.project_path.clone(),
+            entry.git_branch.clone().unwrap_or_default(),
+            entry.duration_secs().map(|d| d.to_string()).unwrap_or_default(),
+            entry.message_count.map(|c| c.to_string()).unwrap_or_default(),
+            entry_summary(entry).to_string(),
+            entry.session_id.clone(),
+        ];
+        let line: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        out.push_str(&line.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// Markdownの表のセル（改行と | はそのままだと表が崩れる）
+fn md_cell(value: &str) -> String {
+    value.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+fn render_markdown(entries: &[HistoryEntry], since: Option<&str>) -> String {
+    let mut out = match since {
+        Some(since) => format!("# Claude Code セッションレポート（直近{}）\n\n", since),
+        None => "# Claude Code セッションレポート\n\n".to_string(),
+    };
+    let total_secs: u64 = entries.iter().filter_map(HistoryEntry::duration_secs).sum();
+    let total_messages: u32 = entries.iter().filter_map(|e| e.message_count).sum();
+    out.push_str(&format!(
+        "{}セッション · 合計 {} · {}メッセージ\n\n",
+        entries.len(),
+        format_duration(total_secs),
+        total_messages
+    ));
+
+    out.push_str("| 日時 | プロジェクト | ブランチ | 時間 | メッセージ | 要約 |\n");
+    out.push_str("|---|---|---|---:|---:|---|\n");
+    for entry in entries {
+        out.push_str(&format!(
+            "| {} | {} | {} | {} | {} | {} |\n",
+            entry.modified.as_deref().map(format_datetime).unwrap_or_default(),
+            md_cell(&format_cwd(&entry.project_path)),
+            md_cell(entry.git_branch.as_deref().unwrap_or("")),
+            entry.duration_secs().map(format_duration).unwrap_or_default(),
+            entry.message_count.map(|c| c.to_string()).unwrap_or_default(),
+            md_cell(entry_summary(entry)),
+        ));
+    }
+    out
+}
+
+fn render_json(entries: &[HistoryEntry]) -> Result<String> {
+    let items: Vec<serde_json::Value> = entries
+        .iter()
+        .map(|entry| {
+            serde_json::json!({
+                "session_id": entry.session_id,
+                "project": entry.project_path,
+                "branch": entry.git_branch,
+                "created": entry.created,
+                "modified": entry.modified,
+                "duration_secs": entry.duration_secs(),
+                "message_count": entry.message_count,
+                "summary": entry.summary,
+                "first_prompt": entry.first_prompt,
+            })
+        })
+        .collect();
+    Ok(serde_json::to_string_pretty(&items)? + "\n")
+}
+
+/// `claude-watch export`: 期間内のセッションのレポートを標準出力に書き出す
+pub fn run_export(format: ReportFormat, since: Option<&str>) -> Result<()> {
+    let period = since.map(parse_period).transpose()?;
+    let entries = sessions_since(period)?;
+    let report = match format {
+        ReportFormat::Csv => render_csv(&entries),
+        ReportFormat::Markdown => render_markdown(&entries, since),
+        ReportFormat::Json => render_json(&entries)?,
+    };
+    print!("{}", report);
+    Ok(())
+}
@@ -0,0 +1,169 @@
+use anyhow::{anyhow, Context, Result};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::session::{canonical_cwd, Session};
+
+/// 作業ツリーの状態（`git status --porcelain=v2 --branch` から）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GitStatus {
+    /// 変更・未追跡のファイル数
+    pub dirty: usize,
+    /// upstreamより進んでいるコミット数（upstreamがなければ0）
+    pub ahead: u32,
+    pub behind: u32,
+}
+
+impl GitStatus {
+    pub fn is_clean(&self) -> bool {
+        self.dirty == 0 && self.ahead == 0 && self.behind == 0
+    }
+}
+
+/// この時間が経ったら取得し直す
+const CACHE_TTL: Duration = Duration::from_secs(10);
+
+// cwd → (取得した時刻, 状態)。gitのリポジトリでなければNone
+static CACHE: Mutex<BTreeMap<String, (Instant, Option<GitStatus>)>> = Mutex::new(BTreeMap::new());
+// 取得中のcwd（同じディレクトリに対してgitを重ねて起動しない）
+static IN_FLIGHT: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+/// cwdでgit statusを実行して状態を取得する（gitのリポジトリでなければNone）
+pub fn read_git_status(cwd: &str) -> Option<GitStatus> {
+    let output = Command::new("git")
+        .args(["-C", cwd, "status", "--porcelain=v2", "--branch"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let mut status = GitStatus::default();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        if let Some(ab) = line.strip_prefix("# branch.ab ") {
+            // "+1 -2"
+            for part in ab.split_whitespace() {
+                if let Some(n) = part.strip_prefix('+') {
+                    status.ahead = n.parse().unwrap_or(0);
+                } else if let Some(n) = part.strip_prefix('-') {
+                    status.behind = n.parse().unwrap_or(0);
+                }
+            }
+        } else if !line.starts_with('#') && !line.is_empty() {
+            status.dirty += 1;
+        }
+    }
+    Some(status)
+}
+
+/// キャッシュ済みの状態を返し、古ければバックグラウンドで取得し直す（呼び出し側は待たない）
+///
+/// 初回は取得が終わるまでNoneを返す。
+pub fn cached_git_status(cwd: &str) -> Option<GitStatus> {
+    let cached = CACHE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(cwd)
+        .copied();
+    if cached.is_none_or(|(fetched, _)| fetched.elapsed() >= CACHE_TTL) {
+        refresh_in_background(cwd);
+    }
+    cached.and_then(|(_, status)| status)
+}
+
+fn refresh_in_background(cwd: &str) {
+    let started = IN_FLIGHT
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(cwd.to_string());
+    if !started {
+        return;
+    }
+    let cwd = cwd.to_string();
+    thread::spawn(move || {
+        let status = read_git_status(&cwd);
+        tracing::debug!(cwd, ?status, "git statusを取得");
+        CACHE
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(cwd.clone(), (Instant::now(), status));
+        IN_FLIGHT.lock().unwrap_or_else(|e| e.into_inner()).remove(&cwd);
+    });
+}
+
+/// ローカルのセッションにキャッシュ済みの状態を付ける（TUI用、待たない）
+pub fn apply_cached_git_status(sessions: &mut [Session]) {
+    for session in sessions.iter_mut().filter(|s| s.host.is_none()) {
+        // 同じディレクトリを別の経路で開いたセッションでキャッシュを共有する
+        session.git_status = cached_git_status(&canonical_cwd(&session.cwd));
+    }
+}
+
+/// ローカルのセッションの状態を並行して取得する（一覧表示用、取得が終わるまで待つ）
+pub fn load_git_status(sessions: &mut [Session]) {
+    thread::scope(|scope| {
+        for session in sessions.iter_mut().filter(|s| s.host.is_none()) {
+            scope.spawn(move || {
+                session.git_status = read_git_status(&session.cwd);
+            });
+        }
+    });
+}
+
+/// gitを実行して標準出力を返す
+fn run_git(cwd: &str, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(["-C", cwd])
+        .args(args)
+        .output()
+        .context("gitの実行に失敗")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!("git {} が失敗しました: {}", args.join(" "), stderr.trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// 作業ツリーの差分（HEADとの比較）の行。未追跡のファイルは末尾に名前だけ並べる
+///
+/// sinceを指定するとその時刻以降に更新されたファイルだけに絞る。
+pub fn load_diff(cwd: &str, since: Option<SystemTime>) -> Result<Vec<String>> {
+    let untracked = run_git(cwd, &["ls-files", "--others", "--exclude-standard"])?;
+    let mut untracked: Vec<&str> = untracked.lines().collect();
+
+    let diff = match since {
+        None => run_git(cwd, &["diff", "HEAD", "--no-color"])?,
+        Some(since) => {
+            let modified_since = |file: &&str| {
+                fs::metadata(Path::new(cwd).join(file))
+                    .and_then(|m| m.modified())
+                    // 削除されたファイルも対象にする
+                    .map_or(true, |modified| modified >= since)
+            };
+            let changed = run_git(cwd, &["diff", "HEAD", "--name-only"])?;
+            let files: Vec<&str> = changed.lines().filter(modified_since).collect();
+            untracked.retain(modified_since);
+            if files.is_empty() {
+                String::new()
+            } else {
+                let mut args = vec!["diff", "HEAD", "--no-color", "--"];
+                args.extend(files);
+                run_git(cwd, &args)?
+            }
+        }
+    };
+
+    let mut lines: Vec<String> = diff.lines().map(str::to_string).collect();
+    if !untracked.is_empty() {
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+        lines.extend(untracked.iter().map(|file| format!("?? {}", file)));
+    }
+    Ok(lines)
+}
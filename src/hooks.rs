@@ -0,0 +1,230 @@
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use std::fs;
+use std::io::Read;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::backend::{current_backend, Backend};
+use crate::display::glyph;
+use crate::iterm2;
+use crate::session::{get_claude_settings_path, get_sessions_dir, sessions_dir_override};
+use crate::status::SessionStatus;
+use crate::transcript::describe_tool_use;
+
+/// 登録するフックのイベントと、そのとき書き込むステータス
+pub const HOOK_EVENTS: &[(&str, &str)] = &[
+    ("Notification", "waiting"),
+    ("PreToolUse", "active"),
+    ("Stop", "stopped"),
+];
+
+/// フックからstdinで渡されるJSON
+#[derive(Debug, Deserialize)]
+struct HookInput {
+    session_id: String,
+    cwd: String,
+    message: Option<String>,
+    notification_type: Option<String>,
+    /// PreToolUse のときだけ渡される
+    tool_name: Option<String>,
+    tool_input: Option<serde_json::Value>,
+}
+
+/// ペインの識別子（端末ごとの環境変数）
+fn current_pane_id() -> Option<String> {
+    ["WEZTERM_PANE", "WT_SESSION"]
+        .iter()
+        .find_map(|var| std::env::var(var).ok().filter(|v| !v.is_empty()))
+        .or_else(|| {
+            let id = std::env::var("ITERM_SESSION_ID").ok().filter(|v| !v.is_empty())?;
+            Some(iterm2::pane_id_from_env(&id).to_string())
+        })
+}
+
+/// `claude-watch hook <status>`: フックの入力を受け取ってセッションファイルを書き出す
+pub fn run_hook(status: &str) -> Result<()> {
+    let mut input = String::new();
+    std::io::stdin()
+        .read_to_string(&mut input)
+        .context("フック入力の読み込みに失敗")?;
+    let input: HookInput = serde_json::from_str(&input).context("フック入力のJSON解析に失敗")?;
+
+    // windowバックエンドはペインを区別できないので、cwdでウィンドウを探す
+    let pane_id = match current_backend() {
+        Backend::Window => Some(input.cwd.clone()),
+        _ => current_pane_id(),
+    };
+    let Some(pane_id) = pane_id else {
+        // 対応する端末の外で起動されたセッションは追跡しない
+        tracing::debug!(session_id = %input.session_id, "ペインIDが無いためスキップ");
+        return Ok(());
+    };
+
+    let updated = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let waiting = SessionStatus::parse(status) == SessionStatus::Waiting;
+    let current_tool = input
+        .tool_name
+        .as_deref()
+        .filter(|_| SessionStatus::parse(status) == SessionStatus::Active)
+        .map(|name| describe_tool_use(name, input.tool_input.as_ref().unwrap_or(&serde_json::Value::Null)));
+    let session = serde_json::json!({
+        "session_id": input.session_id,
+        "pane_id": pane_id,
+        "cwd": input.cwd,
+        "status": status,
+        "notification_message": if waiting { input.message } else { None },
+        "notification_type": if waiting { input.notification_type } else { None },
+        "current_tool": current_tool,
+        "updated": updated,
+    });
+
+    write_session_file(&input.session_id, &session)?;
+    // `new` で仮に登録したペインは本物のセッションファイルに置き換える
+    let spawned = get_sessions_dir()?.join(format!("{}.json", spawned_session_id(&pane_id)));
+    if spawned.exists() {
+        fs::remove_file(&spawned).with_context(|| format!("削除に失敗: {:?}", spawned))?;
+    }
+    Ok(())
+}
+
+fn write_session_file(session_id: &str, session: &serde_json::Value) -> Result<()> {
+    let sessions_dir = get_sessions_dir()?;
+    fs::create_dir_all(&sessions_dir)
+        .with_context(|| format!("セッションディレクトリの作成に失敗: {:?}", sessions_dir))?;
+
+    // 読み込み途中のファイルを見せないよう一時ファイル経由で置き換える
+    let path = sessions_dir.join(format!("{}.json", session_id));
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, session.to_string())
+        .with_context(|| format!("セッションファイルの書き込みに失敗: {:?}", tmp_path))?;
+    fs::rename(&tmp_path, &path)
+        .with_context(|| format!("セッションファイルの書き込みに失敗: {:?}", path))?;
+    Ok(())
+}
+
+/// 起動したばかりでセッションIDがまだないペインの仮のID
+fn spawned_session_id(pane_id: &str) -> String {
+    format!("spawned-{}", pane_id)
+}
+
+/// `new` で起動したペインを最初のフックより前から一覧に出すため、仮のセッションファイルを書く
+pub fn register_spawned_pane(pane_id: &str, cwd: &str) -> Result<()> {
+    let updated = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let session_id = spawned_session_id(pane_id);
+    // Claude Codeは入力待ちで起動するので完了（次の指示待ち）と同じ扱いにする
+    let session = serde_json::json!({
+        "session_id": session_id,
+        "pane_id": pane_id,
+        "cwd": cwd,
+        "status": "stopped",
+        "updated": updated,
+    });
+    write_session_file(&session_id, &session)
+}
+
+/// claude-watchが登録したフックのコマンドか
+fn is_our_command(command: &str) -> bool {
+    command.contains("claude-watch") && command.contains(" hook ")
+}
+
+/// イベントのフック一覧からclaude-watchのエントリを取り除く（取り除いた数を返す）
+fn remove_our_entries(entries: &mut Vec<serde_json::Value>) -> usize {
+    let before = entries.len();
+    entries.retain(|entry| {
+        !entry["hooks"].as_array().is_some_and(|hooks| {
+            hooks
+                .iter()
+                .any(|hook| hook["command"].as_str().is_some_and(is_our_command))
+        })
+    });
+    before - entries.len()
+}
+
+/// ~/.claude/settings.json にフックを登録する（uninstallなら取り除く）
+///
+/// 既存の設定はタイムスタンプ付きでバックアップしてから書き換える。
+pub fn install_hooks(uninstall: bool) -> Result<()> {
+    let path = get_claude_settings_path()?;
+    let mut settings: serde_json::Value = if path.exists() {
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("設定ファイル読み込みエラー: {:?}", path))?;
+        serde_json::from_str(&content).with_context(|| format!("設定ファイル解析エラー: {:?}", path))?
+    } else {
+        serde_json::json!({})
+    };
+
+    let exe = std::env::current_exe().context("実行ファイルのパスを取得できません")?;
+    // 置き場所を変えているときはフックにも同じ場所へ書き込ませる
+    let sessions_dir_arg = sessions_dir_override()
+        .map(|dir| format!(" --sessions-dir \"{}\"", dir.display()))
+        .unwrap_or_default();
+    let settings_obj = settings
+        .as_object_mut()
+        .ok_or_else(|| anyhow!("設定ファイルの形式が不正です: {:?}", path))?;
+    let hooks = settings_obj
+        .entry("hooks")
+        .or_insert_with(|| serde_json::json!({}))
+        .as_object_mut()
+        .ok_or_else(|| anyhow!("hooks の形式が不正です: {:?}", path))?;
+
+    let mut removed = 0;
+    for &(event, status) in HOOK_EVENTS {
+        let entries = hooks
+            .entry(event)
+            .or_insert_with(|| serde_json::json!([]))
+            .as_array_mut()
+            .ok_or_else(|| anyhow!("hooks.{} の形式が不正です: {:?}", event, path))?;
+        removed += remove_our_entries(entries);
+
+        if !uninstall {
+            entries.push(serde_json::json!({
+                "matcher": "",
+                "hooks": [{
+                    "type": "command",
+                    "command": format!("\"{}\" hook {}{}", exe.display(), status, sessions_dir_arg),
+                }],
+            }));
+        }
+    }
+    // 空になったイベントは残さない
+    hooks.retain(|_, entries| entries.as_array().is_none_or(|a| !a.is_empty()));
+    if hooks.is_empty() {
+        settings_obj.remove("hooks");
+    }
+
+    if uninstall && removed == 0 {
+        println!("claude-watchのフックは登録されていません");
+        return Ok(());
+    }
+
+    if path.exists() {
+        let backup = path.with_extension(format!(
+            "json.bak-{}",
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        ));
+        // 同じ秒に続けて実行したときは最初のバックアップを残す
+        if !backup.exists() {
+            fs::copy(&path, &backup).with_context(|| format!("バックアップに失敗: {:?}", backup))?;
+            println!("{}{} にバックアップしました", glyph("💾 ", ""), backup.display());
+        }
+    } else if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("ディレクトリの作成に失敗: {:?}", dir))?;
+    }
+
+    let json = serde_json::to_string_pretty(&settings)?;
+    fs::write(&path, json + "\n").with_context(|| format!("設定ファイルの書き込みに失敗: {:?}", path))?;
+
+    if uninstall {
+        println!("{}{} からフックを削除しました", glyph("✅ ", ""), path.display());
+    } else {
+        let events: Vec<&str> = HOOK_EVENTS.iter().map(|(event, _)| *event).collect();
+        println!("{}{} に {} のフックを登録しました", glyph("✅ ", ""), path.display(), events.join(" / "));
+    }
+    Ok(())
+}
@@ -0,0 +1,379 @@
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write};
+#[cfg(windows)]
+use std::net::{TcpListener as Listener, TcpStream as Stream};
+#[cfg(unix)]
+use std::os::unix::fs::PermissionsExt;
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener as Listener, UnixStream as Stream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crate::backend::jump_to_pane;
+use crate::config::RemoteConfig;
+use crate::remote::{jump_to_remote_pane, session_from_json};
+use crate::session::{find_session_by_id, Session};
+use crate::state::get_state_dir;
+
+/// デーモンのソケット（$XDG_RUNTIME_DIR があればそこ、なければ状態ディレクトリ）
+///
+/// WindowsにはUnixソケットが無いので、localhostで待ち受けたTCPのポート番号を書いたファイル。
+pub fn socket_path() -> Result<PathBuf> {
+    if cfg!(windows) {
+        return Ok(get_state_dir()?.join("daemon.port"));
+    }
+    if let Ok(dir) = std::env::var("XDG_RUNTIME_DIR")
+        && !dir.is_empty()
+    {
+        return Ok(PathBuf::from(dir).join("claude-watch.sock"));
+    }
+    Ok(get_state_dir()?.join("daemon.sock"))
+}
+
+#[cfg(unix)]
+fn connect(path: &Path) -> io::Result<Stream> {
+    Stream::connect(path)
+}
+
+#[cfg(windows)]
+fn connect(path: &Path) -> io::Result<Stream> {
+    let port: u16 = fs::read_to_string(path)?.trim().parse().map_err(io::Error::other)?;
+    Stream::connect(("127.0.0.1", port))
+}
+
+#[cfg(unix)]
+fn listen(path: &Path) -> Result<Listener> {
+    let listener = Listener::bind(path).with_context(|| format!("ソケットを作成できません: {:?}", path))?;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+    Ok(listener)
+}
+
+#[cfg(windows)]
+fn listen(path: &Path) -> Result<Listener> {
+    let listener = Listener::bind(("127.0.0.1", 0)).context("デーモンのポートを開けません")?;
+    fs::write(path, listener.local_addr()?.port().to_string())
+        .with_context(|| format!("書き込みエラー: {:?}", path))?;
+    Ok(listener)
+}
+
+/// デーモンへのリクエスト（Unixソケットで1行に1つのJSONを送り合う）
+///
+/// - リクエスト: `{"id": 1, "method": "list" | "subscribe" | "jump" | "ack", "params": {"session_id": "..."}}`
+/// - レスポンス: `{"id": 1, "result": ...}` または `{"id": 1, "error": "..."}`
+/// - subscribe直後の通知: `{"event": "sessions", "sessions": [...]}`（その時点の一覧）
+/// - 以降の通知: `{"event": "added" | "changed", "session": {...}}` / `{"event": "removed", "session_id": "..."}`
+#[derive(Debug, Deserialize)]
+struct Request {
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+impl Request {
+    fn session_id(&self) -> Result<&str> {
+        self.params
+            .get("session_id")
+            .and_then(Value::as_str)
+            .ok_or_else(|| anyhow!("params.session_id がありません"))
+    }
+}
+
+/// to_json にセッションファイルのパスを加える（TUIから削除できるように）
+fn session_to_json(session: &Session) -> Value {
+    let mut value = session.to_json();
+    value["path"] = json!(session.path);
+    value
+}
+
+fn write_line(stream: &mut Stream, value: &Value) -> Result<()> {
+    let mut line = serde_json::to_string(value)?;
+    line.push('\n');
+    stream.write_all(line.as_bytes())?;
+    Ok(())
+}
+
+#[derive(Default)]
+struct Shared {
+    sessions: Vec<Session>,
+    snapshot: Vec<Value>,
+    subscribers: Vec<Stream>,
+    // ackされたセッションID（デーモンのループで通知ルールに反映する）
+    acks: Vec<String>,
+}
+
+impl Shared {
+    fn sessions_event(&self) -> Value {
+        json!({"event": "sessions", "sessions": self.snapshot})
+    }
+}
+
+/// デーモン側: ソケットで待ち受け、クライアントのリクエストに応える
+pub struct DaemonServer {
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl DaemonServer {
+    pub fn start(remotes: &[RemoteConfig]) -> Result<Self> {
+        let path = socket_path()?;
+        if path.exists() {
+            if connect(&path).is_ok() {
+                return Err(anyhow!("別のデーモンが起動しています: {:?}", path));
+            }
+            // 前回のデーモンが残したソケット
+            fs::remove_file(&path).with_context(|| format!("古いソケットを削除できません: {:?}", path))?;
+        } else if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).with_context(|| format!("ディレクトリ作成エラー: {:?}", dir))?;
+        }
+        let listener = listen(&path)?;
+        tracing::debug!(?path, "ソケットで待ち受け");
+
+        let shared = Arc::new(Mutex::new(Shared::default()));
+        let server = Self {
+            shared: Arc::clone(&shared),
+        };
+        let remotes = remotes.to_vec();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(stream) = stream else { continue };
+                let shared = Arc::clone(&shared);
+                let remotes = remotes.clone();
+                thread::spawn(move || {
+                    if let Err(e) = handle_client(stream, &shared, &remotes) {
+                        tracing::debug!("クライアントとの接続を終了: {:#}", e);
+                    }
+                });
+            }
+        });
+        Ok(server)
+    }
+
+    /// 最新のセッション一覧を反映し、前回からの変化を購読中のクライアントへ送る
+    pub fn publish(&self, sessions: &[Session]) {
+        let mut shared = self.shared.lock().unwrap_or_else(|e| e.into_inner());
+        let snapshot: Vec<Value> = sessions.iter().map(session_to_json).collect();
+        shared.sessions = sessions.to_vec();
+        let events = diff_events(&shared.snapshot, &snapshot);
+        shared.snapshot = snapshot;
+        if events.is_empty() {
+            return;
+        }
+        // 書き込めなくなったクライアントは外す
+        shared
+            .subscribers
+            .retain_mut(|stream| events.iter().all(|event| write_line(stream, event).is_ok()));
+    }
+
+    /// クライアントからackされたセッションID
+    pub fn take_acks(&self) -> Vec<String> {
+        std::mem::take(&mut self.shared.lock().unwrap_or_else(|e| e.into_inner()).acks)
+    }
+}
+
+fn session_key(value: &Value) -> &str {
+    value["session_id"].as_str().unwrap_or_default()
+}
+
+/// 前回の一覧から今回の一覧への変化（追加・変更・終了）の通知
+fn diff_events(old: &[Value], new: &[Value]) -> Vec<Value> {
+    let mut events = Vec::new();
+    for session in new {
+        match old.iter().find(|o| session_key(o) == session_key(session)) {
+            None => events.push(json!({"event": "added", "session": session})),
+            Some(previous) if previous != session => events.push(json!({"event": "changed", "session": session})),
+            Some(_) => {}
+        }
+    }
+    for session in old {
+        if !new.iter().any(|n| session_key(n) == session_key(session)) {
+            events.push(json!({"event": "removed", "session_id": session_key(session)}));
+        }
+    }
+    events
+}
+
+fn handle_client(stream: Stream, shared: &Mutex<Shared>, remotes: &[RemoteConfig]) -> Result<()> {
+    let reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream;
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let request: Request = match serde_json::from_str(&line) {
+            Ok(request) => request,
+            Err(e) => {
+                write_line(&mut writer, &json!({"id": null, "error": format!("リクエストを解析できません: {}", e)}))?;
+                continue;
+            }
+        };
+
+        if request.method == "subscribe" {
+            // 登録までの間に一覧が変わって取りこぼさないよう、ロックしたまま現在の一覧を送る
+            let mut shared = shared.lock().unwrap_or_else(|e| e.into_inner());
+            write_line(&mut writer, &json!({"id": request.id, "result": true}))?;
+            write_line(&mut writer, &shared.sessions_event())?;
+            let subscriber = writer.try_clone()?;
+            // 読み取りの止まったクライアントでデーモンが止まらないようにする
+            subscriber.set_write_timeout(Some(Duration::from_secs(1)))?;
+            shared.subscribers.push(subscriber);
+            continue;
+        }
+
+        let response = match handle_request(&request, shared, remotes) {
+            Ok(result) => json!({"id": request.id, "result": result}),
+            Err(e) => json!({"id": request.id, "error": format!("{:#}", e)}),
+        };
+        write_line(&mut writer, &response)?;
+    }
+    Ok(())
+}
+
+fn handle_request(request: &Request, shared: &Mutex<Shared>, remotes: &[RemoteConfig]) -> Result<Value> {
+    match request.method.as_str() {
+        "list" => Ok(Value::Array(
+            shared.lock().unwrap_or_else(|e| e.into_inner()).snapshot.clone(),
+        )),
+        "jump" => {
+            let session_id = request.session_id()?;
+            let session = find_session_by_id(&shared.lock().unwrap_or_else(|e| e.into_inner()).sessions, session_id)
+                .cloned()
+                .ok_or_else(|| anyhow!("セッションID {} が見つかりません", session_id))?;
+            match session.host {
+                Some(_) => jump_to_remote_pane(remotes, &session)?,
+                None => jump_to_pane(&session.pane_id)?,
+            }
+            Ok(json!(true))
+        }
+        "ack" => {
+            let session_id = request.session_id()?;
+            let mut shared = shared.lock().unwrap_or_else(|e| e.into_inner());
+            if find_session_by_id(&shared.sessions, session_id).is_none() {
+                return Err(anyhow!("セッションID {} が見つかりません", session_id));
+            }
+            shared.acks.push(session_id.to_string());
+            Ok(json!(true))
+        }
+        other => Err(anyhow!("不明なメソッド: {}", other)),
+    }
+}
+
+/// クライアント側: デーモンに接続してリクエストを送る
+pub struct DaemonClient {
+    reader: BufReader<Stream>,
+    writer: Stream,
+    next_id: u64,
+}
+
+impl DaemonClient {
+    /// デーモンが起動していなければエラー
+    pub fn connect() -> Result<Self> {
+        let path = socket_path()?;
+        let stream = connect(&path).with_context(|| format!("デーモンに接続できません: {:?}", path))?;
+        Ok(Self {
+            reader: BufReader::new(stream.try_clone()?),
+            writer: stream,
+            next_id: 1,
+        })
+    }
+
+    /// リクエストを送り、同じidのレスポンスの result を返す
+    pub fn call(&mut self, method: &str, params: Value) -> Result<Value> {
+        let id = self.next_id;
+        self.next_id += 1;
+        write_line(&mut self.writer, &json!({"id": id, "method": method, "params": params}))?;
+
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if self.reader.read_line(&mut line)? == 0 {
+                return Err(anyhow!("デーモンとの接続が切れました"));
+            }
+            let response: Value = serde_json::from_str(&line).context("デーモンの応答を解析できません")?;
+            // 購読中の通知は読み飛ばす
+            if response.get("id") != Some(&json!(id)) {
+                continue;
+            }
+            if let Some(error) = response.get("error") {
+                return Err(anyhow!("{}", error.as_str().unwrap_or_default()));
+            }
+            return Ok(response.get("result").cloned().unwrap_or(Value::Null));
+        }
+    }
+
+    /// 一覧の変化の通知を受け取り始める（受信はバックグラウンドのスレッドで行う）
+    pub fn subscribe(mut self) -> Result<DaemonSubscription> {
+        self.call("subscribe", Value::Null)?;
+        let latest = Arc::new(Mutex::new(None));
+        let connected = Arc::new(AtomicBool::new(true));
+        let subscription = DaemonSubscription {
+            latest: Arc::clone(&latest),
+            connected: Arc::clone(&connected),
+        };
+        let mut reader = self.reader;
+        thread::spawn(move || {
+            let mut sessions: Vec<Session> = Vec::new();
+            let mut line = String::new();
+            while reader.read_line(&mut line).is_ok_and(|n| n > 0) {
+                if let Ok(event) = serde_json::from_str::<Value>(&line)
+                    && apply_event(&mut sessions, event)
+                {
+                    *latest.lock().unwrap_or_else(|e| e.into_inner()) = Some(sessions.clone());
+                }
+                line.clear();
+            }
+            connected.store(false, Ordering::Relaxed);
+        });
+        Ok(subscription)
+    }
+}
+
+/// 通知を手元の一覧に反映する（一覧が変わればtrue）
+fn apply_event(sessions: &mut Vec<Session>, mut event: Value) -> bool {
+    match event["event"].as_str() {
+        Some("sessions") => {
+            let Value::Array(items) = event["sessions"].take() else {
+                return false;
+            };
+            *sessions = items.into_iter().filter_map(|v| session_from_json(v).ok()).collect();
+        }
+        Some("added" | "changed") => {
+            let Ok(session) = session_from_json(event["session"].take()) else {
+                return false;
+            };
+            sessions.retain(|s| s.session_id != session.session_id);
+            sessions.push(session);
+        }
+        Some("removed") => {
+            let session_id = event["session_id"].as_str().unwrap_or_default();
+            sessions.retain(|s| s.session_id != session_id);
+        }
+        _ => return false,
+    }
+    sessions.sort_by_key(|s| std::cmp::Reverse(s.updated));
+    true
+}
+
+/// デーモンから受け取った最新のセッション一覧
+pub struct DaemonSubscription {
+    latest: Arc<Mutex<Option<Vec<Session>>>>,
+    connected: Arc<AtomicBool>,
+}
+
+impl DaemonSubscription {
+    /// 前回から届いた一覧（届いていなければNone）
+    pub fn take(&self) -> Option<Vec<Session>> {
+        self.latest.lock().unwrap_or_else(|e| e.into_inner()).take()
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::Relaxed)
+    }
+}
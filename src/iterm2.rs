@@ -0,0 +1,166 @@
+use anyhow::{anyhow, Context, Result};
+use std::collections::HashMap;
+use std::process::Command;
+
+use crate::alerts::shell_quote;
+
+// iTerm2はosascript（AppleScript）で操作する。
+// ペインIDはセッションの unique id（$ITERM_SESSION_ID の ':' より後ろ）を使う。
+
+/// AppleScriptを実行して標準出力を返す（引数は `on run argv` で受け取る）
+fn osascript(script: &str, args: &[&str]) -> Result<String> {
+    let output = Command::new("osascript")
+        .args(["-e", script])
+        .args(args)
+        .output()
+        .context("osascriptの実行に失敗")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!("iTerm2の操作に失敗しました: {}", stderr.trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim_end().to_string())
+}
+
+/// 全ウィンドウのセッションの unique id を1行ずつ出力する
+const LIST_SCRIPT: &str = r#"
+tell application "iTerm2"
+    set ids to {}
+    repeat with w in windows
+        repeat with t in tabs of w
+            repeat with s in sessions of t
+                set end of ids to unique id of s
+            end repeat
+        end repeat
+    end repeat
+    set AppleScript's text item delimiters to linefeed
+    return ids as text
+end tell
+"#;
+
+/// 指定したセッションのウィンドウとタブを選んで前面に出す
+const ACTIVATE_SCRIPT: &str = r#"
+on run argv
+    tell application "iTerm2"
+        repeat with w in windows
+            repeat with t in tabs of w
+                repeat with s in sessions of t
+                    if unique id of s is item 1 of argv then
+                        select w
+                        select t
+                        select s
+                        activate
+                        return "ok"
+                    end if
+                end repeat
+            end repeat
+        end repeat
+    end tell
+    return ""
+end run
+"#;
+
+/// 現在のウィンドウ（なければ新しいウィンドウ）に新しいタブを開いてコマンドを実行する
+const SPAWN_SCRIPT: &str = r#"
+on run argv
+    tell application "iTerm2"
+        if (count of windows) is 0 then
+            set w to (create window with default profile)
+        else
+            set w to current window
+            tell w to create tab with default profile
+        end if
+        set s to current session of w
+        tell s to write text (item 1 of argv)
+        return unique id of s
+    end tell
+end run
+"#;
+
+/// セッションの画面の内容を返す（見つからなければ何も返さない）
+const GET_TEXT_SCRIPT: &str = r#"
+on run argv
+    tell application "iTerm2"
+        repeat with w in windows
+            repeat with t in tabs of w
+                repeat with s in sessions of t
+                    if unique id of s is item 1 of argv then
+                        return "ok" & linefeed & (contents of s)
+                    end if
+                end repeat
+            end repeat
+        end repeat
+    end tell
+    return ""
+end run
+"#;
+
+/// セッションに改行なしでテキストを送る
+const SEND_TEXT_SCRIPT: &str = r#"
+on run argv
+    tell application "iTerm2"
+        repeat with w in windows
+            repeat with t in tabs of w
+                repeat with s in sessions of t
+                    if unique id of s is item 1 of argv then
+                        tell s to write text (item 2 of argv) newline no
+                        return "ok"
+                    end if
+                end repeat
+            end repeat
+        end repeat
+    end tell
+    return ""
+end run
+"#;
+
+/// $ITERM_SESSION_ID（"w0t0p0:UUID"）からペインIDを取り出す
+pub fn pane_id_from_env(value: &str) -> &str {
+    value.rsplit(':').next().unwrap_or(value)
+}
+
+/// pane_id → フォアグラウンドプロセスのPID（iTerm2からはPIDを取得できないので常にNone）
+pub fn list_panes() -> Result<HashMap<String, Option<u32>>> {
+    tracing::debug!("osascript: iTerm2のセッション一覧");
+    let output = osascript(LIST_SCRIPT, &[])?;
+    let map = output
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|id| (id.to_string(), None))
+        .collect::<HashMap<_, _>>();
+    tracing::debug!(count = map.len(), "iTerm2のセッションを取得");
+    Ok(map)
+}
+
+pub fn activate_pane(pane_id: &str) -> Result<()> {
+    tracing::debug!(pane_id, "osascript: iTerm2のセッションを選択");
+    if osascript(ACTIVATE_SCRIPT, &[pane_id])?.is_empty() {
+        return Err(anyhow!("iTerm2のセッション {} が見つかりません", pane_id));
+    }
+    Ok(())
+}
+
+/// 新しいタブでcwdに移動してコマンドを実行し、新しいセッションのIDを返す
+pub fn spawn_in_cwd(cwd: &str, command: &[&str]) -> Result<String> {
+    let command: Vec<String> = command.iter().map(|arg| shell_quote(arg)).collect();
+    let line = format!("cd {} && {}", shell_quote(cwd), command.join(" "));
+    tracing::debug!(cwd, %line, "osascript: iTerm2で新しいタブを開く");
+    osascript(SPAWN_SCRIPT, &[&line])
+}
+
+/// セッションに表示されている内容
+pub fn get_text(pane_id: &str) -> Result<String> {
+    tracing::debug!(pane_id, "osascript: iTerm2のセッションの内容を取得");
+    // 空の画面と見つからないセッションを区別するため先頭に "ok" を付けて返す
+    match osascript(GET_TEXT_SCRIPT, &[pane_id])?.strip_prefix("ok") {
+        Some(text) => Ok(text.strip_prefix('\n').unwrap_or(text).to_string()),
+        None => Err(anyhow!("iTerm2のセッション {} が見つかりません", pane_id)),
+    }
+}
+
+pub fn send_text(pane_id: &str, text: &str) -> Result<()> {
+    tracing::debug!(pane_id, "osascript: iTerm2のセッションにテキストを送信");
+    if osascript(SEND_TEXT_SCRIPT, &[pane_id, text])?.is_empty() {
+        return Err(anyhow!("iTerm2のセッション {} が見つかりません", pane_id));
+    }
+    Ok(())
+}
@@ -0,0 +1,388 @@
+use anyhow::{anyhow, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::HashMap;
+
+use crate::config::Config;
+
+/// TUIで割り当て可能な操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    First,
+    Last,
+    Jump,
+    Menu,
+    SwitchTab,
+    CopyId,
+    CopyCwd,
+    CopyResumeCommand,
+    CopyPaneId,
+    Resume,
+    NewSession,
+    Interrupt,
+    SendText,
+    Approve,
+    Deny,
+    MarkStopped,
+    Snooze,
+    ToggleMark,
+    ClearMarks,
+    CycleMarked,
+    CleanMarked,
+    ExportMarked,
+    Pin,
+    ToggleTime,
+    ToggleDetailed,
+    ToggleFollow,
+    ToggleOrphans,
+    EditNote,
+    FilterTag,
+    Search,
+    ViewTranscript,
+    ViewDiff,
+    Monitor,
+    PaneConflicts,
+    OpenEditor,
+    Help,
+    Quit,
+}
+
+impl Action {
+    /// ヘルプの表示順
+    pub const ALL: &'static [Action] = &[
+        Action::Up,
+        Action::Down,
+        Action::PageUp,
+        Action::PageDown,
+        Action::First,
+        Action::Last,
+        Action::Jump,
+        Action::Menu,
+        Action::SwitchTab,
+        Action::CopyId,
+        Action::CopyCwd,
+        Action::CopyResumeCommand,
+        Action::CopyPaneId,
+        Action::Resume,
+        Action::NewSession,
+        Action::Interrupt,
+        Action::SendText,
+        Action::Approve,
+        Action::Deny,
+        Action::MarkStopped,
+        Action::Snooze,
+        Action::ToggleMark,
+        Action::ClearMarks,
+        Action::CycleMarked,
+        Action::CleanMarked,
+        Action::ExportMarked,
+        Action::Pin,
+        Action::ToggleTime,
+        Action::ToggleDetailed,
+        Action::ToggleFollow,
+        Action::ToggleOrphans,
+        Action::EditNote,
+        Action::FilterTag,
+        Action::Search,
+        Action::ViewTranscript,
+        Action::ViewDiff,
+        Action::Monitor,
+        Action::PaneConflicts,
+        Action::OpenEditor,
+        Action::Help,
+        Action::Quit,
+    ];
+
+    /// 設定ファイルでのアクション名
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Up => "up",
+            Action::Down => "down",
+            Action::PageUp => "page_up",
+            Action::PageDown => "page_down",
+            Action::First => "first",
+            Action::Last => "last",
+            Action::Jump => "jump",
+            Action::Menu => "menu",
+            Action::SwitchTab => "switch_tab",
+            Action::CopyId => "copy_id",
+            Action::CopyCwd => "copy_cwd",
+            Action::CopyResumeCommand => "copy_resume_command",
+            Action::CopyPaneId => "copy_pane_id",
+            Action::Resume => "resume",
+            Action::NewSession => "new_session",
+            Action::Interrupt => "interrupt",
+            Action::SendText => "send_text",
+            Action::Approve => "approve",
+            Action::Deny => "deny",
+            Action::MarkStopped => "mark_stopped",
+            Action::Snooze => "snooze",
+            Action::ToggleMark => "toggle_mark",
+            Action::ClearMarks => "clear_marks",
+            Action::CycleMarked => "cycle_marked",
+            Action::CleanMarked => "clean_marked",
+            Action::ExportMarked => "export_marked",
+            Action::Pin => "pin",
+            Action::ToggleTime => "toggle_time",
+            Action::ToggleDetailed => "toggle_detailed",
+            Action::ToggleFollow => "toggle_follow",
+            Action::ToggleOrphans => "toggle_orphans",
+            Action::EditNote => "edit_note",
+            Action::FilterTag => "filter_tag",
+            Action::Search => "search",
+            Action::ViewTranscript => "view_transcript",
+            Action::ViewDiff => "view_diff",
+            Action::Monitor => "monitor",
+            Action::PaneConflicts => "pane_conflicts",
+            Action::OpenEditor => "open_editor",
+            Action::Help => "help",
+            Action::Quit => "quit",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::Up => "前のセッション",
+            Action::Down => "次のセッション",
+            Action::PageUp => "1ページ上へ",
+            Action::PageDown => "1ページ下へ",
+            Action::First => "先頭へ",
+            Action::Last => "末尾へ",
+            Action::Jump => "選択中のペインにジャンプ（プロジェクトタブでは履歴を表示）",
+            Action::Menu => "選択中のセッションの操作メニュー",
+            Action::SwitchTab => "セッション / 履歴 / プロジェクトタブの切り替え",
+            Action::CopyId => "セッションIDをコピー",
+            Action::CopyCwd => "ディレクトリをコピー",
+            Action::CopyResumeCommand => "再開コマンド（claude --resume）をコピー",
+            Action::CopyPaneId => "ペインIDをコピー",
+            Action::Resume => "セッションを新しいペインで再開",
+            Action::NewSession => "新しいペインでclaudeを起動（プロジェクトを選ぶ）",
+            Action::Interrupt => "Ctrl-Cを送って中断（マーク時は全件）",
+            Action::SendText => "選択中のペインにテキストを送信（質問に答える）",
+            Action::Approve => "承認待ちのツール実行を許可（マーク時は全件）",
+            Action::Deny => "承認待ちのツール実行を拒否（マーク時は全件）",
+            Action::MarkStopped => "完了扱いにする（idle? のセッションなど、マーク時は全件）",
+            Action::Snooze => "承認待ちの通知をしばらく止める / 再開（マーク時は全件）",
+            Action::ToggleMark => "セッションをマーク/解除",
+            Action::ClearMarks => "すべてのマークを解除",
+            Action::CycleMarked => "マーク中のペインに順番にジャンプ",
+            Action::CleanMarked => "セッションファイルを削除（マーク時は全件）",
+            Action::ExportMarked => "マーク中のセッションをJSONに書き出し",
+            Action::Pin => "先頭にピン留め / 解除（マーク時は全件）",
+            Action::ToggleTime => "相対時刻 / 時刻表示の切り替え",
+            Action::ToggleDetailed => "1行表示 / 2行表示（要約・承認待ちの理由）の切り替え",
+            Action::ToggleFollow => "最新のセッションを常に選択する（追従）の切り替え",
+            Action::ToggleOrphans => "ペインがなくなったセッションの表示切り替え（再開・削除できる）",
+            Action::EditNote => "メモ・タグを編集（#タグ）",
+            Action::FilterTag => "タグで絞り込み（順に切り替え）",
+            Action::Search => "すべてのプロジェクトの会話ログを全文検索",
+            Action::ViewTranscript => "会話ログを表示",
+            Action::ViewDiff => "作業ディレクトリの差分を表示",
+            Action::Monitor => "選択中のペインの表示を詳細の代わりに映す（モニター）",
+            Action::PaneConflicts => "同じペインを指すセッションファイルの確認・削除",
+            Action::OpenEditor => "プロジェクトをエディタで開く",
+            Action::Help => "ヘルプの表示/非表示",
+            Action::Quit => "終了",
+        }
+    }
+
+    fn default_keys(self) -> &'static [&'static str] {
+        match self {
+            Action::Up => &["Up", "k"],
+            Action::Down => &["Down", "j"],
+            Action::PageUp => &["PageUp"],
+            Action::PageDown => &["PageDown"],
+            Action::First => &["Home", "g"],
+            Action::Last => &["End", "G"],
+            Action::Jump => &["Enter"],
+            Action::Menu => &["m"],
+            Action::SwitchTab => &["Tab"],
+            Action::CopyId => &["y"],
+            Action::CopyCwd => &["c"],
+            Action::CopyResumeCommand => &["C"],
+            Action::CopyPaneId => &["p"],
+            Action::Resume => &["r"],
+            Action::NewSession => &["N"],
+            Action::Interrupt => &["K"],
+            Action::SendText => &["i"],
+            Action::Approve => &["a"],
+            Action::Deny => &["X"],
+            Action::MarkStopped => &["S"],
+            Action::Snooze => &["x"],
+            Action::ToggleMark => &["Space"],
+            Action::ClearMarks => &["Esc"],
+            Action::CycleMarked => &["J"],
+            Action::CleanMarked => &["D"],
+            Action::ExportMarked => &["E"],
+            Action::Pin => &["P"],
+            Action::ToggleTime => &["t"],
+            Action::ToggleDetailed => &["V"],
+            Action::ToggleFollow => &["F"],
+            Action::ToggleOrphans => &["o"],
+            Action::EditNote => &["n"],
+            Action::FilterTag => &["f"],
+            Action::Search => &["/"],
+            Action::ViewTranscript => &["v"],
+            Action::ViewDiff => &["d"],
+            Action::Monitor => &["w"],
+            Action::PaneConflicts => &["!"],
+            Action::OpenEditor => &["e"],
+            Action::Help => &["?"],
+            Action::Quit => &["q"],
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Action> {
+        Action::ALL.iter().copied().find(|a| a.name() == name)
+    }
+}
+
+/// Ctrl修飾の有無を含めたキー
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Key {
+    code: KeyCode,
+    ctrl: bool,
+}
+
+impl Key {
+    /// "q" / "Enter" / "PageDown" / "ctrl-d" 形式の文字列をパース
+    pub fn parse(s: &str) -> Result<Key> {
+        let (ctrl, name) = match s.strip_prefix("ctrl-").or_else(|| s.strip_prefix("C-")) {
+            Some(rest) => (true, rest),
+            None => (false, s),
+        };
+
+        let code = match name {
+            "Up" => KeyCode::Up,
+            "Down" => KeyCode::Down,
+            "Left" => KeyCode::Left,
+            "Right" => KeyCode::Right,
+            "Enter" => KeyCode::Enter,
+            "Tab" => KeyCode::Tab,
+            "BackTab" => KeyCode::BackTab,
+            "Esc" => KeyCode::Esc,
+            "Space" => KeyCode::Char(' '),
+            "Backspace" => KeyCode::Backspace,
+            "Delete" => KeyCode::Delete,
+            "Home" => KeyCode::Home,
+            "End" => KeyCode::End,
+            "PageUp" => KeyCode::PageUp,
+            "PageDown" => KeyCode::PageDown,
+            _ => {
+                if let Some(n) = name.strip_prefix('F').and_then(|n| n.parse().ok()) {
+                    KeyCode::F(n)
+                } else {
+                    let mut chars = name.chars();
+                    match (chars.next(), chars.next()) {
+                        (Some(c), None) => KeyCode::Char(c),
+                        _ => return Err(anyhow!("不明なキー: {}", s)),
+                    }
+                }
+            }
+        };
+
+        Ok(Key { code, ctrl })
+    }
+
+    fn from_event(event: &KeyEvent) -> Key {
+        Key {
+            code: event.code,
+            ctrl: event.modifiers.contains(KeyModifiers::CONTROL),
+        }
+    }
+
+    /// ヘルプ・フッター用の表示名
+    pub fn label(&self) -> String {
+        let name = match self.code {
+            KeyCode::Up => "↑".to_string(),
+            KeyCode::Down => "↓".to_string(),
+            KeyCode::Left => "←".to_string(),
+            KeyCode::Right => "→".to_string(),
+            KeyCode::Char(' ') => "Space".to_string(),
+            KeyCode::Char(c) => c.to_string(),
+            KeyCode::F(n) => format!("F{}", n),
+            KeyCode::BackTab => "Shift-Tab".to_string(),
+            KeyCode::PageUp => "PgUp".to_string(),
+            KeyCode::PageDown => "PgDn".to_string(),
+            other => format!("{:?}", other),
+        };
+        if self.ctrl {
+            format!("Ctrl-{}", name)
+        } else {
+            name
+        }
+    }
+}
+
+pub struct KeyBindings {
+    key_to_action: HashMap<Key, Action>,
+    action_to_keys: HashMap<Action, Vec<Key>>,
+}
+
+impl KeyBindings {
+    /// デフォルトの割り当てに設定ファイルの [keys] を上書きして構築する
+    ///
+    /// 同じキーが複数のアクションに割り当てられている場合はエラーにする。
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let mut action_to_keys: HashMap<Action, Vec<Key>> = HashMap::new();
+        for &action in Action::ALL {
+            let keys = action
+                .default_keys()
+                .iter()
+                .map(|k| Key::parse(k))
+                .collect::<Result<Vec<_>>>()?;
+            action_to_keys.insert(action, keys);
+        }
+
+        for (name, keys) in &config.keys {
+            let action = Action::from_name(name)
+                .ok_or_else(|| anyhow!("不明なアクション: {} ([keys] セクション)", name))?;
+            let keys = keys
+                .items()
+                .iter()
+                .map(|k| Key::parse(k))
+                .collect::<Result<Vec<_>>>()?;
+            action_to_keys.insert(action, keys);
+        }
+
+        let mut key_to_action: HashMap<Key, Action> = HashMap::new();
+        let mut conflicts = Vec::new();
+        for &action in Action::ALL {
+            for key in &action_to_keys[&action] {
+                if let Some(existing) = key_to_action.insert(*key, action) {
+                    conflicts.push(format!(
+                        "キー '{}' が {} と {} に重複しています",
+                        key.label(),
+                        existing.name(),
+                        action.name()
+                    ));
+                }
+            }
+        }
+
+        if !conflicts.is_empty() {
+            return Err(anyhow!("キー設定の競合:\n  {}", conflicts.join("\n  ")));
+        }
+
+        Ok(Self {
+            key_to_action,
+            action_to_keys,
+        })
+    }
+
+    pub fn action_for(&self, event: &KeyEvent) -> Option<Action> {
+        // Shift付きの文字は大文字のCharとして届くため、修飾キーはCtrlだけを見る
+        self.key_to_action.get(&Key::from_event(event)).copied()
+    }
+
+    /// アクションに割り当てられたキーの表示名（"↑ / k"）
+    pub fn label(&self, action: Action) -> String {
+        self.action_to_keys
+            .get(&action)
+            .map(|keys| keys.iter().map(|k| k.label()).collect::<Vec<_>>().join(" / "))
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,48 @@
+use anyhow::Result;
+use chrono::{Local, TimeZone};
+
+use crate::store::EventStore;
+
+/// 承認待ちから実行中に戻った1回分の記録
+#[derive(Debug, Clone)]
+pub struct ApprovalRecord {
+    /// 承認待ちになった時刻（UNIX秒）
+    pub waiting_since: u64,
+    /// 承認された（実行中に戻った）時刻（UNIX秒）
+    pub approved_at: u64,
+}
+
+impl ApprovalRecord {
+    pub fn latency_secs(&self) -> u64 {
+        self.approved_at.saturating_sub(self.waiting_since)
+    }
+}
+
+/// 指定時刻（UNIX秒）以降に承認されたもの
+pub fn load_approvals(store: &EventStore, since: u64) -> Result<Vec<ApprovalRecord>> {
+    Ok(store
+        .transitions_between("waiting", "active", since)?
+        .into_iter()
+        .filter_map(|t| {
+            Some(ApprovalRecord {
+                waiting_since: t.from_updated?,
+                approved_at: t.updated,
+            })
+        })
+        .collect())
+}
+
+/// 今日（ローカル時刻）承認されたものの平均待ち時間と件数
+pub fn average_latency_today(store: &EventStore) -> Option<(u64, usize)> {
+    let midnight = Local::now().date_naive().and_hms_opt(0, 0, 0)?;
+    let since = Local.from_local_datetime(&midnight).earliest()?.timestamp();
+    let latencies: Vec<u64> = load_approvals(store, since as u64)
+        .ok()?
+        .iter()
+        .map(ApprovalRecord::latency_secs)
+        .collect();
+    if latencies.is_empty() {
+        return None;
+    }
+    Some((latencies.iter().sum::<u64>() / latencies.len() as u64, latencies.len()))
+}
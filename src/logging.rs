@@ -0,0 +1,46 @@
+use anyhow::{anyhow, Context, Result};
+use std::path::{Path, PathBuf};
+use tracing_subscriber::EnvFilter;
+
+use crate::state::get_state_dir;
+
+/// TUI実行中のログの書き出し先（$XDG_STATE_HOME/claude-watch/claude-watch.log）
+fn default_log_file() -> Result<PathBuf> {
+    Ok(get_state_dir()?.join("claude-watch.log"))
+}
+
+/// tracingを初期化する
+///
+/// RUST_LOGがあればそれに従い、なければ `--verbose` でdebug、指定なしはwarnだけを出す。
+/// ログファイルを指定すると日付ごとにローテーションしながら書き出す。
+/// TUIは画面が崩れるためstderrには出さず、詳細ログを求められたときだけデフォルトのファイルに書く。
+pub fn init_logging(verbose: bool, log_file: Option<&Path>, tui: bool) -> Result<()> {
+    let env_filter = EnvFilter::try_from_default_env().ok();
+    let detailed = verbose || env_filter.is_some();
+    let filter = env_filter.unwrap_or_else(|| {
+        EnvFilter::new(if verbose { "claude_watch=debug" } else { "warn" })
+    });
+
+    let log_file = match log_file {
+        Some(path) => Some(path.to_path_buf()),
+        None if tui && detailed => Some(default_log_file()?),
+        None if tui => return Ok(()),
+        None => None,
+    };
+
+    let builder = tracing_subscriber::fmt().with_env_filter(filter);
+    match log_file {
+        Some(path) => {
+            let dir = path.parent().filter(|d| !d.as_os_str().is_empty()).unwrap_or(Path::new("."));
+            let file_name = path
+                .file_name()
+                .ok_or_else(|| anyhow!("ログファイル名が不正です: {:?}", path))?;
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("ログディレクトリの作成に失敗: {:?}", dir))?;
+            let appender = tracing_appender::rolling::daily(dir, file_name);
+            builder.with_writer(appender).with_ansi(false).init();
+        }
+        None => builder.with_writer(std::io::stderr).init(),
+    }
+    Ok(())
+}
@@ -1,28 +1,620 @@
+mod alerts;
+mod backend;
+mod budget;
+mod clean;
+mod clipboard;
+mod columns;
+mod compositor;
+mod config;
+mod daemon;
 mod display;
+mod doctor;
+mod editor;
+mod email;
+mod export;
+mod git;
+mod hooks;
+mod ipc;
+mod iterm2;
+mod keybindings;
+mod latency;
+mod logging;
+mod mcp;
+mod porcelain;
+mod process;
+mod projects;
+mod push;
+mod quiet;
+mod reason;
+mod remote;
+mod search;
+mod search_index;
+mod serve;
+mod service;
 mod session;
+mod state;
+mod stats;
+mod status;
+mod store;
+mod template;
+mod theme;
+mod transcript;
+mod transitions;
+mod triggers;
 mod ui;
+mod watcher;
+mod webhook;
 mod wezterm;
+mod window;
+mod windows_terminal;
 
-use anyhow::{anyhow, Result};
-use display::display_sessions;
-use session::{enrich_sessions_with_index, filter_active_sessions, find_session_by_id, load_sessions};
-use ui::run_tui;
-use wezterm::jump_to_pane;
+use anyhow::{anyhow, Context, Result};
+use std::io::{self, Read, Write};
+use std::thread;
+use std::time::Duration;
+use clean::{run_clean, CleanOptions};
+use clipboard::{copy_to_clipboard, CopyField};
+use config::{load_config, Config};
+use daemon::run_daemon;
+use doctor::run_doctor;
+use editor::open_in_editor;
+use export::{parse_period, run_export, ReportFormat};
+use git::load_git_status;
+use hooks::{install_hooks, run_hook};
+use ipc::DaemonClient;
+use logging::init_logging;
+use process::set_process_liveness;
+use display::{confirm, glyph, init_locale, set_ascii_mode, Locale, display_check, display_cost_summary, format_cwd, display_event, display_events, display_history, display_orphaned_sessions, display_search_results, display_sessions, display_stats, display_status};
+use session::{
+    find_history_entry, find_session_by_id, last_load_errors, load_active_sessions, load_history, load_orphaned_sessions,
+    init_sessions_dir, set_canonicalize_paths, set_idle_after, set_reconcile_status, set_strict_loading, Session,
+};
+use state::{load_state, update_state};
+use status::{init_custom_statuses, SessionStatus};
+use template::Template;
+use theme::{init_theme, Theme};
+use transcript::{init_pricing, summarize_usage_by_project};
+use reason::WaitingReason;
+use search::{init_search, search_transcripts};
+use mcp::run_mcp;
+use serve::run_server;
+use service::{install_service, service_status, uninstall_service};
+use remote::{group_by_host, jump_to_remote_pane, load_remote_sessions, RemotePoller};
+use stats::collect_stats;
+use store::{EventStore, StoredTransition};
+use transitions::StatusTracker;
+use watcher::SessionWatcher;
+use ui::{run_stats_view, run_tui};
+use backend::{backend_unavailable, init_backend, interrupt_pane, jump_to_pane, new_session, resolve_project_dir, resume_session, send_prompt, Backend};
+use porcelain::{display_porcelain, PorcelainVersion};
+use projects::{init_ignore, init_projects, is_hidden_by_project};
+use quiet::init_quiet;
+use triggers::init_triggers;
+use budget::{daily_budget, init_budget, refresh_daily_cost};
+use window::init_window_config;
+
+fn has_flag(args: &[String], flag: &str) -> bool {
+    args.iter().any(|a| a == flag)
+}
+
+fn flag_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|a| a == flag)
+        .and_then(|i| args.get(i + 1))
+        .map(|s| s.as_str())
+}
+
+/// `--flag` を取り除いて指定の有無を返す
+fn take_flag(args: &mut Vec<String>, flags: &[&str]) -> bool {
+    let before = args.len();
+    args.retain(|a| !flags.contains(&a.as_str()));
+    args.len() != before
+}
+
+/// `--flag value` を取り除いて値を返す（サブコマンドの位置をずらさないため）
+fn take_flag_value(args: &mut Vec<String>, flag: &str) -> Option<String> {
+    let i = args.iter().position(|a| a == flag)?;
+    if i + 1 >= args.len() {
+        args.remove(i);
+        return None;
+    }
+    let value = args.remove(i + 1);
+    args.remove(i);
+    Some(value)
+}
+
+fn print_usage() {
+    println!("\n使い方:");
+    println!("  claude-watch           TUIモードで起動（デフォルト）");
+    println!("  claude-watch list      セッション一覧を表示 [--tag <tag>] [--json]");
+    println!("      [--reason tool|plan|question|idle|other]（承認待ちの理由で絞り込み）");
+    println!("      [--model opus|sonnet|haiku]（Claudeのモデルで絞り込み）");
+    println!("      [--all]（[[projects]] で隠したセッションも表示）");
+    println!("      [--orphaned]（ペインがなくなった最近のセッションも表示）");
+    println!("      [--watch] [--interval 2s]（一定間隔で表示し直す）");
+    println!("      [--template '{{icon}} {{cwd}} {{status}} {{age}}']（1行ごとの書式、{{cwd:30}} で幅指定）");
+    println!("      [--porcelain=v1]（スクリプト向けのタブ区切り、フィールドの順と意味は版ごとに固定）");
+    println!("  claude-watch tui       TUIモードで起動");
+    println!("  claude-watch watch --auto-jump  承認待ちになったペインへ自動でジャンプ");
+    println!("      [--countdown 秒]（ジャンプ前に確認の猶予を置く）");
+    println!("  claude-watch jump <id> 指定セッションにジャンプ");
+    println!("  claude-watch copy <id> セッションの値をクリップボードにコピー");
+    println!("      [--field id|cwd|resume|pane]");
+    println!("  claude-watch open <id> セッションのディレクトリをエディタで開く");
+    println!("  claude-watch ack <id>  デーモンの通知を止める（ステータスが変わるまで）");
+    println!("  claude-watch kill <id> セッションにCtrl-Cを送って中断 [--yes]");
+    println!("  claude-watch send <id> [text]  ペインにテキストを送信（textなしで標準入力） [--no-enter]");
+    println!("  claude-watch status    ステータスバー向けの1行出力");
+    println!("      [--format waybar|plain]");
+    println!("  claude-watch check     承認待ちがあれば終了コード1（なし: 0 / エラー: 2）");
+    println!("      [--quiet]");
+    println!("  claude-watch tag <id> <tag>...  セッションにタグを付ける [--remove]");
+    println!("  claude-watch note <id> [text]   セッションにメモを付ける（textなしで削除）");
+    println!("  claude-watch history   過去のセッション一覧 [--limit N]");
+    println!("  claude-watch search <query>  すべてのプロジェクトの会話ログを全文検索 [--limit N] [--json]");
+    println!("      （[search] index = true なら索引を使い、デーモンが追記分を取り込む）");
+    println!("  claude-watch events    記録したステータスの変化 [--session <id>] [--since 1d] [--limit N]");
+    println!("      [--json]（1行1イベントのNDJSON） [--follow]（新しい変化を出力し続ける）");
+    println!("  claude-watch resume <id> 過去のセッションを新しいペインで再開");
+    println!("  claude-watch new [path]  新しいペインで claude を起動（既定は現在のディレクトリ）");
+    println!("  claude-watch cost      プロジェクト別のトークン使用量と推定コスト");
+    println!("  claude-watch stats     日ごとのセッション数・プロジェクト・待ち時間の集計");
+    println!("      [--days N] [--tui]");
+    println!("  claude-watch export    セッションのレポートを出力");
+    println!("      [--format csv|md|json] [--since 7d]");
+    println!("  claude-watch daemon    画面なしで監視し通知ルールを実行 [--interval 秒]");
+    println!("  claude-watch daemon install|status|uninstall  ログイン時に起動するよう登録");
+    println!("      （Linux: systemdのユーザーユニット / macOS: launchd）");
+    println!("  claude-watch serve     HTTPでセッションを公開（GET /sessions, GET /events はSSE、起動時に表示するトークンが必要）");
+    println!("      [--listen 127.0.0.1:7878] [--web]（ブラウザ用のダッシュボードとジャンプを有効にする）");
+    println!("  claude-watch mcp       MCPサーバーとして起動（標準入出力、Claude Codeから他のセッションを参照）");
+    println!("  claude-watch doctor    セットアップの問題を診断");
+    println!("  claude-watch install-hooks  Claude Codeのフックを ~/.claude/settings.json に登録");
+    println!("      [--uninstall]");
+    println!("  claude-watch clean     古いセッションファイルを削除");
+    println!("      [--days N] [--dry-run] [--archive] [--yes]");
+    println!("\n共通オプション:");
+    println!("  --backend <name>       wezterm（デフォルト） / windows-terminal / iterm2 / window");
+    println!("  --verbose, -v          詳細ログを出力（RUST_LOGでも指定可）");
+    println!("  --log-file <path>      ログをファイルに出力（日付ごとにローテーション）");
+    println!("  --strict               壊れたセッションファイルがあればエラーにする");
+    println!("  --sessions-dir <path>  セッションファイルの置き場所（デフォルト: ~/.claude/sessions）");
+    println!("  --ascii                絵文字と罫線を使わずASCIIで表示");
+    println!("  --bell                 TUIで新しく承認待ちになったらベルを鳴らす");
+}
+
+/// 設定ファイルを読み込み、共通オプションに従ってグローバルな設定を初期化する
+fn setup(args: &mut Vec<String>) -> Result<Config> {
+    let verbose = take_flag(args, &["--verbose", "-v"]);
+    let log_file = take_flag_value(args, "--log-file").map(std::path::PathBuf::from);
+    let tui = args.len() < 2 || matches!(args[1].as_str(), "tui" | "watch");
+    init_logging(verbose, log_file.as_deref(), tui)?;
+    set_strict_loading(take_flag(args, &["--strict"]));
+
+    let mut config = load_config()?;
+    if take_flag(args, &["--bell"]) {
+        config.bell.enabled = true;
+    }
+    // セッションファイルの置き場所（コマンドライン > 設定ファイル > CLAUDE_CONFIG_DIR / ~/.claude）
+    let sessions_dir = take_flag_value(args, "--sessions-dir")
+        .map(std::path::PathBuf::from)
+        .or_else(|| config.sessions_dir.clone());
+    if let Some(dir) = sessions_dir {
+        init_sessions_dir(&dir)?;
+    }
+
+    // バックエンドの選択（コマンドライン > 設定ファイル > iTerm2の中ならiTerm2 > WezTerm）
+    let backend_name = take_flag_value(args, "--backend")
+        .or_else(|| config.backend.clone())
+        .or_else(|| (std::env::var("TERM_PROGRAM").as_deref() == Ok("iTerm.app")).then(|| "iterm2".to_string()));
+    if let Some(name) = backend_name {
+        init_backend(Backend::parse(&name)?);
+    }
+    init_window_config(&config.window);
+    init_quiet(&config.quiet)?;
+    init_projects(&config.projects)?;
+    init_ignore(&config.ignore)?;
+    init_triggers(&config.triggers)?;
+    init_budget(&config.budget)?;
+    init_pricing(&config.pricing);
+    init_search(&config.search);
+    set_process_liveness(config.process_liveness);
+    set_canonicalize_paths(config.canonicalize_paths);
+    set_idle_after(config.timer.idle_after);
+    set_reconcile_status(config.reconcile_status);
+    set_ascii_mode(take_flag(args, &["--ascii"]) || config.ascii);
+    if let Some(ref name) = config.theme {
+        init_theme(Theme::parse(name)?);
+    }
+    init_locale(config.locale.as_deref().map(Locale::parse).transpose()?);
+    init_custom_statuses(&config.statuses)?;
+    Ok(config)
+}
 
 fn main() -> Result<()> {
     let args: Vec<String> = std::env::args().collect();
 
-    let all_sessions = load_sessions()?;
-    let mut sessions = filter_active_sessions(all_sessions)?;
+    // check はスクリプト向けに終了コードで結果を返す（0: 承認待ちなし / 1: あり / 2: エラー）
+    if args.get(1).is_some_and(|a| a == "check") {
+        let quiet = has_flag(&args, "--quiet") || has_flag(&args, "-q");
+        let code = match run_check(args, quiet) {
+            Ok(true) => 1,
+            Ok(false) => 0,
+            Err(e) => {
+                if !quiet {
+                    eprintln!("Error: {:?}", e);
+                }
+                2
+            }
+        };
+        std::process::exit(code);
+    }
+
+    run(args)
+}
+
+/// 承認待ちのセッションがあればtrueを返す
+fn run_check(mut args: Vec<String>, quiet: bool) -> Result<bool> {
+    setup(&mut args)?;
+    let mut sessions = load_active_sessions()?;
+    load_state()?.apply_snoozes(&mut sessions);
+    if !quiet {
+        display_check(&sessions);
+    }
+    Ok(sessions.iter().any(|s| s.status == SessionStatus::Waiting && !s.snoozed))
+}
+
+/// `list` のテキスト表示（リモートのセッションも含める）
+fn print_list(mut sessions: Vec<Session>, args: &[String], config: &Config) -> Result<()> {
+    load_git_status(&mut sessions);
+    let (remote_sessions, errors) = load_remote_sessions(&config.remote);
+    for e in &errors {
+        eprintln!("{}リモートのセッションを取得できません: {:#}", glyph("⚠️  ", "! "), e);
+    }
+    sessions.extend(remote_sessions);
+    sessions.sort_by_key(|s| std::cmp::Reverse(s.updated));
+    // シンプルなリスト表示
+    let state = load_state()?;
+    let mut sessions: Vec<_> = match flag_value(args, "--tag") {
+        Some(tag) => sessions
+            .into_iter()
+            .filter(|s| state.has_tag(&s.session_id, tag))
+            .collect(),
+        None => sessions,
+    };
+    if let Some(reason) = flag_value(args, "--reason") {
+        WaitingReason::validate_name(reason)?;
+        sessions.retain(|s| s.waiting_reason.as_ref().is_some_and(|r| r.name() == reason));
+    }
+    if let Some(model) = flag_value(args, "--model") {
+        // "opus" のような系統でも "claude-opus-4-1" のような名前の一部でもよい
+        sessions.retain(|s| s.model.as_deref().is_some_and(|m| m.contains(model)));
+    }
+    if !has_flag(args, "--all") {
+        sessions.retain(|s| !is_hidden_by_project(s));
+    }
+    state.sort_pinned_first(&mut sessions);
+    group_by_host(&mut sessions);
+    let porcelain = args
+        .iter()
+        .find_map(|a| a.strip_prefix("--porcelain"))
+        .map(|version| PorcelainVersion::parse(version.trim_start_matches('=')))
+        .transpose()?;
+    let template = flag_value(args, "--template").or(config.list_template.as_deref());
+    if let Some(version) = porcelain {
+        display_porcelain(&sessions, &state, version);
+    } else if let Some(template) = template {
+        let template = Template::parse(template)?;
+        for session in &sessions {
+            println!("{}", template.render(session, &state));
+        }
+    } else {
+        display_sessions(&sessions, &state);
+        refresh_daily_cost();
+        if let Some(daily) = daily_budget() {
+            let level = daily.level.map(|l| format!(" {} {}", l.icon(), l.label())).unwrap_or_default();
+            println!("今日のコスト: {}{}\n", daily.amounts(), level);
+        }
+    }
+    let errors = last_load_errors();
+    if !errors.is_empty() {
+        eprintln!(
+            "{}{}件のセッションファイルを読み込めませんでした（claude-watch doctor で確認）",
+            glyph("⚠️  ", "! "),
+            errors.len()
+        );
+    }
+    Ok(())
+}
+
+/// `list --watch`: watch(1) のように画面を消して一覧を表示し直し続ける（Ctrl-Cで終了）
+fn watch_list(args: &[String], config: &Config, interval: Duration) -> Result<()> {
+    loop {
+        // 画面を消してカーソルを左上へ（代替スクリーンは使わない）
+        print!("\x1b[2J\x1b[H");
+        println!(
+            "{}秒ごとに更新: claude-watch list  {}\n",
+            interval.as_secs(),
+            chrono::Local::now().format("%H:%M:%S")
+        );
+        match load_active_sessions() {
+            Ok(sessions) => {
+                if let Err(e) = print_list(sessions, args, config) {
+                    eprintln!("{}{:#}", glyph("⚠️  ", "! "), e);
+                }
+            }
+            Err(e) => eprintln!("{}セッションを読み込めません: {:#}", glyph("⚠️  ", "! "), e),
+        }
+        io::stdout().flush()?;
+        thread::sleep(interval);
+    }
+}
 
-    // sessions-index.jsonからsummaryとfirst_promptを取得
-    enrich_sessions_with_index(&mut sessions)?;
+/// `events --follow`: 監視を続けてステータス変化を検出するたびに出力する（Ctrl-Cで終了）
+fn follow_events(config: &Config, session_id: Option<&str>, json: bool) -> Result<()> {
+    let mut tracker = StatusTracker::new();
+    let remotes = RemotePoller::start(&config.remote);
+    let watcher = SessionWatcher::start()
+        .inspect_err(|e| eprintln!("{}ファイルの監視を開始できません: {:#}", glyph("⚠️  ", "! "), e))
+        .ok();
+    let interval = Duration::from_secs(2);
+    let mut stdout = io::stdout();
+    loop {
+        match load_active_sessions() {
+            Ok(sessions) => {
+                let sessions = remotes.merge(sessions);
+                for transition in tracker.update(&sessions) {
+                    if session_id.is_some_and(|id| id != transition.session.session_id) {
+                        continue;
+                    }
+                    let event = StoredTransition::from_transition(&transition);
+                    if json {
+                        writeln!(stdout, "{}", event.to_json())?;
+                    } else {
+                        display_event(&event);
+                    }
+                }
+                // パイプ先（jqなど）にすぐ届ける
+                stdout.flush()?;
+            }
+            Err(e) => eprintln!("{}セッションを読み込めません: {:#}", glyph("⚠️  ", "! "), e),
+        }
+        match watcher {
+            Some(ref watcher) => watcher.wait(interval),
+            None => thread::sleep(interval),
+        }
+    }
+}
 
-    if sessions.is_empty() {
-        println!("⚠️  アクティブなClaude Codeセッションが見つかりません");
+fn run(mut args: Vec<String>) -> Result<()> {
+    let config = setup(&mut args)?;
+
+    // アクティブなセッションを必要としないサブコマンド
+    if args.len() >= 2 && args[1] == "clean" {
+        let days = match flag_value(&args, "--days") {
+            Some(v) => v
+                .parse()
+                .map_err(|_| anyhow!("--days には日数を指定してください: {}", v))?,
+            None => 7,
+        };
+        return run_clean(&CleanOptions {
+            days,
+            dry_run: has_flag(&args, "--dry-run"),
+            archive: has_flag(&args, "--archive"),
+            yes: has_flag(&args, "--yes") || has_flag(&args, "-y"),
+        });
+    }
+
+    if args.len() >= 2 && args[1] == "daemon" {
+        let interval = match flag_value(&args, "--interval") {
+            Some(v) => v
+                .parse()
+                .map_err(|_| anyhow!("--interval には秒数を指定してください: {}", v))?,
+            None => 2,
+        };
+        match args.get(2).map(String::as_str) {
+            Some("install") => return install_service(interval),
+            Some("status") => return service_status(),
+            Some("uninstall") => return uninstall_service(),
+            _ => {}
+        }
+        return run_daemon(&config, std::time::Duration::from_secs(interval));
+    }
+
+    if args.len() >= 2 && args[1] == "mcp" {
+        return run_mcp(&config);
+    }
+
+    if args.len() >= 2 && args[1] == "serve" {
+        let listen = flag_value(&args, "--listen").unwrap_or("127.0.0.1:7878");
+        return run_server(&config, listen, has_flag(&args, "--web"));
+    }
+
+    if args.len() >= 2 && args[1] == "history" {
+        let limit = match flag_value(&args, "--limit") {
+            Some(v) => v
+                .parse()
+                .map_err(|_| anyhow!("--limit には件数を指定してください: {}", v))?,
+            None => 30,
+        };
+        // アクティブなセッションは除外（WezTermが使えなければ全件）
+        let active_ids = load_active_sessions()
+            .map(|sessions| sessions.into_iter().map(|s| s.session_id).collect())
+            .unwrap_or_default();
+        let mut history = load_history(&active_ids)?;
+        history.truncate(limit);
+        display_history(&history);
+        return Ok(());
+    }
+
+    if args.len() >= 2 && args[1] == "search" {
+        // 残りの引数をつないで検索する語にする
+        let mut rest = args[2..].to_vec();
+        let limit = match take_flag_value(&mut rest, "--limit") {
+            Some(v) => v
+                .parse()
+                .map_err(|_| anyhow!("--limit には件数を指定してください: {}", v))?,
+            None => 20,
+        };
+        let json = take_flag(&mut rest, &["--json"]);
+        let query = rest.join(" ");
+        if query.trim().is_empty() {
+            return Err(anyhow!("使い方: claude-watch search <query> [--limit N] [--json]"));
+        }
+        let results = search_transcripts(&query, limit)?;
+        if json {
+            let items: Vec<serde_json::Value> = results.iter().map(|r| r.to_json()).collect();
+            println!("{}", serde_json::to_string_pretty(&items)?);
+        } else {
+            // 実行中のセッションにはステータスを添える（WezTermが使えなければ添えない）
+            display_search_results(&query, &results, &load_active_sessions().unwrap_or_default());
+        }
+        return Ok(());
+    }
+
+    if args.len() >= 2 && args[1] == "events" {
+        let limit = match flag_value(&args, "--limit") {
+            Some(v) => v
+                .parse()
+                .map_err(|_| anyhow!("--limit には件数を指定してください: {}", v))?,
+            None => 50,
+        };
+        let since = flag_value(&args, "--since")
+            .map(parse_period)
+            .transpose()?
+            .map(|period| (chrono::Utc::now().timestamp() as u64).saturating_sub(period.as_secs()));
+        let json = has_flag(&args, "--json");
+        if has_flag(&args, "--follow") {
+            return follow_events(&config, flag_value(&args, "--session"), json);
+        }
+        let store = EventStore::open()?;
+        let events = store.transitions(flag_value(&args, "--session"), since, limit)?;
+        if json {
+            // NDJSON（1行1イベント、古い順）
+            for event in events.iter().rev() {
+                println!("{}", event.to_json());
+            }
+        } else {
+            display_events(&events);
+        }
+        return Ok(());
+    }
+
+    if args.len() >= 2 && args[1] == "export" {
+        let format = ReportFormat::parse(flag_value(&args, "--format").unwrap_or("md"))?;
+        return run_export(format, flag_value(&args, "--since"));
+    }
+
+    if args.len() >= 2 && args[1] == "stats" {
+        let days = match flag_value(&args, "--days") {
+            Some(v) => v
+                .parse()
+                .ok()
+                .filter(|&d| d > 0)
+                .ok_or_else(|| anyhow!("--days には日数を指定してください: {}", v))?,
+            None => 7,
+        };
+        // ステータスの内訳は現在のセッションから（取得できなければ空）
+        let active = load_active_sessions().unwrap_or_default();
+        let stats = collect_stats(days, &active)?;
+        if has_flag(&args, "--tui") {
+            return run_stats_view(&stats);
+        }
+        display_stats(&stats);
+        return Ok(());
+    }
+
+    if args.len() >= 2 && args[1] == "resume" {
+        let Some(session_id) = args.get(2) else {
+            return Err(anyhow!("使い方: claude-watch resume <session_id>"));
+        };
+        let entry = find_history_entry(session_id)?
+            .ok_or_else(|| anyhow!("セッションID {} が見つかりません", session_id))?;
+        let pane_id = resume_session(&entry.project_path, &entry.session_id)?;
+        println!("{}Pane {} でセッション {} を再開しました", glyph("✅ ", ""), pane_id, entry.session_id);
+        return Ok(());
+    }
+
+    if args.len() >= 2 && args[1] == "new" {
+        let path = args.get(2).map_or(".", String::as_str);
+        let cwd = resolve_project_dir(path)?;
+        let pane_id = new_session(&cwd)?;
+        println!("{}Pane {} で claude を起動しました（{}）", glyph("✅ ", ""), pane_id, format_cwd(&cwd));
+        return Ok(());
+    }
+
+    // Claude Codeのフックから呼ばれる（stdinのJSONからセッションファイルを書き出す）
+    if args.len() >= 2 && args[1] == "hook" {
+        let Some(status) = args.get(2) else {
+            return Err(anyhow!("使い方: claude-watch hook <waiting|active|stopped>"));
+        };
+        return run_hook(status);
+    }
+
+    if args.len() >= 2 && args[1] == "tag" {
+        let remove = has_flag(&args, "--remove");
+        let rest: Vec<String> = args[2..].iter().filter(|a| *a != "--remove").cloned().collect();
+        let [session_id, tags @ ..] = rest.as_slice() else {
+            return Err(anyhow!("使い方: claude-watch tag <session_id> <tag>... [--remove]"));
+        };
+        if tags.is_empty() {
+            return Err(anyhow!("使い方: claude-watch tag <session_id> <tag>... [--remove]"));
+        }
+        let (state, ()) = update_state(|state| {
+            if remove {
+                state.remove_tags(session_id, tags);
+            } else {
+                state.add_tags(session_id, tags);
+            }
+        })?;
+        let tags = state.note(session_id).map(|n| n.tags.join(", ")).unwrap_or_default();
+        println!("{}{}: [{}]", glyph("🏷  ", ""), session_id, tags);
         return Ok(());
     }
 
+    if args.len() >= 2 && args[1] == "note" {
+        let Some(session_id) = args.get(2) else {
+            return Err(anyhow!("使い方: claude-watch note <session_id> [text]"));
+        };
+        let text = args[3..].join(" ");
+        update_state(|state| {
+            // タグはそのままにメモだけを置き換える
+            let mut note = state.note(session_id).cloned().unwrap_or_default();
+            note.note = (!text.trim().is_empty()).then(|| text.trim().to_string());
+            state.set_note(session_id, note);
+        })?;
+        if text.trim().is_empty() {
+            println!("{}のメモを削除しました", session_id);
+        } else {
+            println!("{}{}: {}", glyph("📝 ", ""), session_id, text.trim());
+        }
+        return Ok(());
+    }
+
+    if args.len() >= 2 && args[1] == "install-hooks" {
+        return install_hooks(has_flag(&args, "--uninstall"));
+    }
+
+    if args.len() >= 2 && args[1] == "doctor" {
+        return run_doctor();
+    }
+
+    if args.len() >= 2 && args[1] == "cost" {
+        display_cost_summary(&summarize_usage_by_project()?);
+        return Ok(());
+    }
+
+    // アクティブなセッションをsessions-index.jsonの情報付きで取得
+    let sessions = load_active_sessions()?;
+
+    // ステータスバー向け出力はセッションが0件でも空の結果を出す
+    if args.len() >= 2 && args[1] == "status" {
+        let format = flag_value(&args, "--format").unwrap_or("plain");
+        let mut sessions = sessions;
+        load_state()?.apply_snoozes(&mut sessions);
+        return display_status(&sessions, format);
+    }
+
     // サブコマンドの処理
     if args.len() >= 2 {
         match args[1].as_str() {
@@ -34,20 +626,143 @@ fn main() -> Result<()> {
                 if let Some(session) = find_session_by_id(&sessions, session_id) {
                     jump_to_pane(&session.pane_id)?;
                 } else {
-                    return Err(anyhow!("セッションID {} が見つかりません", session_id));
+                    // ローカルになければリモートから探す
+                    let (remote_sessions, _) = load_remote_sessions(&config.remote);
+                    let session = find_session_by_id(&remote_sessions, session_id)
+                        .ok_or_else(|| anyhow!("セッションID {} が見つかりません", session_id))?;
+                    jump_to_remote_pane(&config.remote, session)?;
+                    println!(
+                        "{}{} の Pane {} にジャンプしました",
+                        glyph("✅ ", ""),
+                        session.host.as_deref().unwrap_or_default(),
+                        session.pane_id
+                    );
+                }
+            }
+            "copy" => {
+                let Some(session_id) = args.get(2) else {
+                    return Err(anyhow!("使い方: claude-watch copy <session_id> [--field id|cwd|resume|pane]"));
+                };
+                let field = CopyField::parse(flag_value(&args, "--field").unwrap_or("id"))?;
+                // 終了したセッションも履歴から探す（ペインIDは取れない）
+                let value = match find_session_by_id(&sessions, session_id) {
+                    Some(session) => field.value(&session.session_id, &session.cwd, Some(&session.pane_id)),
+                    None => {
+                        let entry = find_history_entry(session_id)?
+                            .ok_or_else(|| anyhow!("セッションID {} が見つかりません", session_id))?;
+                        field.value(&entry.session_id, &entry.project_path, None)
+                    }
+                }
+                .ok_or_else(|| anyhow!("セッション {} にはペインがありません", session_id))?;
+                copy_to_clipboard(&value)?;
+                println!("{}コピーしました: {}", glyph("📋 ", ""), value);
+            }
+            "open" => {
+                let Some(session_id) = args.get(2) else {
+                    return Err(anyhow!("使い方: claude-watch open <session_id>"));
+                };
+                // 終了したセッションも履歴から探す
+                let cwd = match find_session_by_id(&sessions, session_id) {
+                    Some(session) => session.cwd.clone(),
+                    None => find_history_entry(session_id)?
+                        .map(|entry| entry.project_path)
+                        .ok_or_else(|| anyhow!("セッションID {} が見つかりません", session_id))?,
+                };
+                open_in_editor(&config.editor, &cwd)?;
+                println!("{}{} をエディタで開きました", glyph("✅ ", ""), format_cwd(&cwd));
+            }
+            "ack" => {
+                let Some(session_id) = args.get(2) else {
+                    return Err(anyhow!("使い方: claude-watch ack <session_id>"));
+                };
+                DaemonClient::connect()?.call("ack", serde_json::json!({"session_id": session_id}))?;
+                println!("{}{} を確認済みにしました（ステータスが変わるまで通知しません）", glyph("✅ ", ""), session_id);
+            }
+            "kill" => {
+                let Some(session_id) = args.get(2) else {
+                    return Err(anyhow!("使い方: claude-watch kill <session_id> [--yes]"));
+                };
+                let session = find_session_by_id(&sessions, session_id)
+                    .ok_or_else(|| anyhow!("セッションID {} が見つかりません", session_id))?;
+                let prompt = format!(
+                    "pane {} ({}) にCtrl-Cを送信しますか？",
+                    session.pane_id,
+                    format_cwd(&session.cwd)
+                );
+                if has_flag(&args, "--yes") || has_flag(&args, "-y") || confirm(&prompt)? {
+                    interrupt_pane(&session.pane_id)?;
+                    println!("{}Pane {} にCtrl-Cを送信しました", glyph("✅ ", ""), session.pane_id);
+                }
+            }
+            "send" => {
+                let submit = !has_flag(&args, "--no-enter");
+                let rest: Vec<&str> = args[2..].iter().map(String::as_str).filter(|a| *a != "--no-enter").collect();
+                let Some((session_id, words)) = rest.split_first() else {
+                    return Err(anyhow!("使い方: claude-watch send <session_id> [text] [--no-enter]"));
+                };
+                let text = if words.is_empty() {
+                    let mut text = String::new();
+                    io::stdin().read_to_string(&mut text).context("標準入力の読み込みに失敗")?;
+                    text.trim_end_matches(['\r', '\n']).to_string()
+                } else {
+                    words.join(" ")
+                };
+                if text.is_empty() {
+                    return Err(anyhow!("送信するテキストがありません"));
                 }
+                let session = find_session_by_id(&sessions, session_id)
+                    .ok_or_else(|| anyhow!("セッションID {} が見つかりません", session_id))?;
+                send_prompt(&session.pane_id, &text, submit)?;
+                println!("{}Pane {} に送信しました", glyph("✅ ", ""), session.pane_id);
             }
             "list" => {
-                // シンプルなリスト表示
-                display_sessions(&sessions);
+                if let Some(reason) = backend_unavailable() {
+                    eprintln!("{}{}（ペインでの絞り込みなしで表示します）", glyph("⚠️  ", "! "), reason);
+                }
+                if has_flag(&args, "--json") {
+                    // リモートから取得されるときの形式（ここでは他のリモートを含めない）
+                    let mut sessions = sessions;
+                    if has_flag(&args, "--orphaned") {
+                        sessions.extend(load_orphaned_sessions()?);
+                    }
+                    load_git_status(&mut sessions);
+                    let items: Vec<serde_json::Value> = sessions.iter().map(Session::to_json).collect();
+                    println!("{}", serde_json::to_string(&items)?);
+                    return Ok(());
+                }
+                if has_flag(&args, "--watch") {
+                    let interval = match flag_value(&args, "--interval") {
+                        Some(v) if v.chars().all(|c| c.is_ascii_digit()) => Duration::from_secs(
+                            v.parse()
+                                .map_err(|_| anyhow!("--interval には秒数を指定してください: {}", v))?,
+                        ),
+                        Some(v) => parse_period(v)?,
+                        None => Duration::from_secs(2),
+                    };
+                    return watch_list(&args, &config, interval.max(Duration::from_secs(1)));
+                }
+                // `list --orphaned` はアクティブなセッションがなくてもペインのなくなったセッションを出す
+                if sessions.is_empty() && !has_flag(&args, "--orphaned") {
+                    println!("{}アクティブなClaude Codeセッションが見つかりません", glyph("⚠️  ", "! "));
+                    return Ok(());
+                }
+                print_list(sessions, &args, &config)?;
+                if has_flag(&args, "--orphaned") {
+                    display_orphaned_sessions(&load_orphaned_sessions()?);
+                }
             }
             "tui" | "watch" => {
                 // TUIモード
-                if let Some(session_id) = run_tui(sessions)? {
+                let auto_jump = match flag_value(&args, "--countdown") {
+                    Some(v) => Some(
+                        v.parse()
+                            .map_err(|_| anyhow!("--countdown には秒数を指定してください: {}", v))?,
+                    ),
+                    None => has_flag(&args, "--auto-jump").then_some(0),
+                };
+                if let Some(session_id) = run_tui(sessions, &config, auto_jump.map(Duration::from_secs))? {
                     // Enterが押されたセッションにジャンプ
-                    let all_sessions = load_sessions()?;
-                    let mut sessions = filter_active_sessions(all_sessions)?;
-                    enrich_sessions_with_index(&mut sessions)?;
+                    let sessions = load_active_sessions()?;
                     if let Some(session) = find_session_by_id(&sessions, &session_id) {
                         jump_to_pane(&session.pane_id)?;
                     }
@@ -55,19 +770,13 @@ fn main() -> Result<()> {
             }
             _ => {
                 println!("不明なコマンド: {}", args[1]);
-                println!("\n使い方:");
-                println!("  claude-watch           TUIモードで起動（デフォルト）");
-                println!("  claude-watch list      セッション一覧を表示");
-                println!("  claude-watch tui       TUIモードで起動");
-                println!("  claude-watch jump <id> 指定セッションにジャンプ");
+                print_usage();
             }
         }
     } else {
         // デフォルト: TUIモード
-        if let Some(session_id) = run_tui(sessions)? {
-            let all_sessions = load_sessions()?;
-            let mut sessions = filter_active_sessions(all_sessions)?;
-            enrich_sessions_with_index(&mut sessions)?;
+        if let Some(session_id) = run_tui(sessions, &config, None)? {
+            let sessions = load_active_sessions()?;
             if let Some(session) = find_session_by_id(&sessions, &session_id) {
                 jump_to_pane(&session.pane_id)?;
             }
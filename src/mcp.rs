@@ -0,0 +1,186 @@
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+use std::io::{self, BufRead, Write};
+
+use crate::backend::current_backend;
+use crate::config::Config;
+use crate::display::truncate_text;
+use crate::remote::{jump_to_remote_pane, load_remote_sessions};
+use crate::session::{find_session_by_id, load_active_sessions, Session};
+use crate::transcript::{load_conversation, transcript_path};
+
+// `claude-watch mcp`: Model Context Protocol のサーバー（標準入出力で1行に1つのJSON-RPC）。
+// Claude Code から他のセッションの状態や会話を調べ、ペインへジャンプできるようにする。
+// 標準出力はプロトコル専用なので、ログやメッセージは標準エラーに出す。
+
+/// 対応するプロトコルのバージョン（新しい順）
+const PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+/// session_transcript で返す発言数の既定値
+const DEFAULT_TRANSCRIPT_LIMIT: usize = 20;
+/// 1発言の最大文字数（長いツール結果で文脈を埋めないように）
+const MAX_MESSAGE_CHARS: usize = 2000;
+
+fn tools() -> Value {
+    let session_id = json!({"type": "string", "description": "セッションID（list_sessions の session_id）"});
+    json!([
+        {
+            "name": "list_sessions",
+            "description": "claude-watchが監視しているClaude Codeセッションの一覧（ステータス、作業ディレクトリ、要約、承認待ちの理由など）",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "status": {"type": "string", "description": "このステータスのセッションだけにする（active / waiting / stopped など）"},
+                    "model": {"type": "string", "description": "このモデルのセッションだけにする（opus / sonnet / haiku、またはモデル名の一部）"}
+                }
+            }
+        },
+        {
+            "name": "session_transcript",
+            "description": "セッションの会話の最近の発言（ユーザー・アシスタント・ツール呼び出しと結果）",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "session_id": session_id,
+                    "limit": {"type": "integer", "description": "返す発言数（最新から、既定は20）"}
+                },
+                "required": ["session_id"]
+            }
+        },
+        {
+            "name": "jump",
+            "description": "セッションのターミナルのペインを前面に出す",
+            "inputSchema": {
+                "type": "object",
+                "properties": {"session_id": session_id},
+                "required": ["session_id"]
+            }
+        }
+    ])
+}
+
+/// ローカルとリモートのセッション
+fn load_all_sessions(config: &Config) -> Result<Vec<Session>> {
+    let mut sessions = load_active_sessions()?;
+    let (remote_sessions, errors) = load_remote_sessions(&config.remote);
+    for e in &errors {
+        tracing::warn!("リモートのセッションを取得できません: {:#}", e);
+    }
+    sessions.extend(remote_sessions);
+    sessions.sort_by_key(|s| std::cmp::Reverse(s.updated));
+    Ok(sessions)
+}
+
+fn find_session(sessions: &[Session], arguments: &Value) -> Result<Session> {
+    let session_id = arguments["session_id"]
+        .as_str()
+        .ok_or_else(|| anyhow!("session_id を指定してください"))?;
+    find_session_by_id(sessions, session_id)
+        .cloned()
+        .ok_or_else(|| anyhow!("セッションID {} が見つかりません", session_id))
+}
+
+/// ツールを実行して結果のテキストを返す
+fn call_tool(config: &Config, name: &str, arguments: &Value) -> Result<String> {
+    match name {
+        "list_sessions" => {
+            let mut sessions = load_all_sessions(config)?;
+            if let Some(status) = arguments["status"].as_str() {
+                sessions.retain(|s| s.status.as_str() == status);
+            }
+            if let Some(model) = arguments["model"].as_str() {
+                sessions.retain(|s| s.model.as_deref().is_some_and(|m| m.contains(model)));
+            }
+            let items: Vec<Value> = sessions.iter().map(Session::to_json).collect();
+            Ok(serde_json::to_string_pretty(&items)?)
+        }
+        "session_transcript" => {
+            let session = find_session(&load_all_sessions(config)?, arguments)?;
+            if let Some(ref host) = session.host {
+                return Err(anyhow!("リモート（{}）のセッションの会話は読めません", host));
+            }
+            let limit = arguments["limit"].as_u64().map_or(DEFAULT_TRANSCRIPT_LIMIT, |n| n as usize);
+            let messages = load_conversation(&transcript_path(&session.cwd, &session.session_id)?)?;
+            let lines: Vec<String> = messages[messages.len().saturating_sub(limit)..]
+                .iter()
+                .map(|m| {
+                    format!(
+                        "[{}] {}{}",
+                        m.role.name(),
+                        m.timestamp.as_deref().map(|t| format!("{} ", t)).unwrap_or_default(),
+                        truncate_text(&m.text, MAX_MESSAGE_CHARS)
+                    )
+                })
+                .collect();
+            Ok(lines.join("\n\n"))
+        }
+        "jump" => {
+            let session = find_session(&load_all_sessions(config)?, arguments)?;
+            match session.host {
+                Some(_) => jump_to_remote_pane(&config.remote, &session)?,
+                // jump_to_pane は標準出力にメッセージを出すので使わない
+                None => current_backend().activate_pane(&session.pane_id)?,
+            }
+            Ok(format!("{} のペインにジャンプしました", session.cwd))
+        }
+        other => Err(anyhow!("不明なツール: {}", other)),
+    }
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({"jsonrpc": "2.0", "id": id, "error": {"code": code, "message": message}})
+}
+
+/// 1つのメッセージに応える（通知には応答しない）
+fn handle_message(config: &Config, message: &Value) -> Option<Value> {
+    let id = message.get("id")?.clone();
+    let params = &message["params"];
+    let result = match message["method"].as_str().unwrap_or_default() {
+        "initialize" => {
+            let requested = params["protocolVersion"].as_str().unwrap_or_default();
+            let version = PROTOCOL_VERSIONS
+                .iter()
+                .find(|v| **v == requested)
+                .unwrap_or(&PROTOCOL_VERSIONS[0]);
+            json!({
+                "protocolVersion": version,
+                "capabilities": {"tools": {}},
+                "serverInfo": {"name": "claude-watch", "version": env!("CARGO_PKG_VERSION")}
+            })
+        }
+        "ping" => json!({}),
+        "tools/list" => json!({"tools": tools()}),
+        "tools/call" => {
+            let name = params["name"].as_str().unwrap_or_default();
+            tracing::debug!(name, "MCPのツール呼び出し");
+            // ツールの失敗はプロトコルのエラーではなく結果として返す
+            let (text, is_error) = match call_tool(config, name, &params["arguments"]) {
+                Ok(text) => (text, false),
+                Err(e) => (format!("{:#}", e), true),
+            };
+            json!({"content": [{"type": "text", "text": text}], "isError": is_error})
+        }
+        method => return Some(error_response(id, -32601, &format!("不明なメソッド: {}", method))),
+    };
+    Some(json!({"jsonrpc": "2.0", "id": id, "result": result}))
+}
+
+/// 標準入力からリクエストを読み、標準出力に応答する（入力が閉じたら終了）
+pub fn run_mcp(config: &Config) -> Result<()> {
+    let mut stdout = io::stdout();
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match serde_json::from_str::<Value>(&line) {
+            Ok(message) => handle_message(config, &message),
+            Err(e) => Some(error_response(Value::Null, -32700, &format!("JSONを解析できません: {}", e))),
+        };
+        if let Some(response) = response {
+            writeln!(stdout, "{}", response)?;
+            stdout.flush()?;
+        }
+    }
+    Ok(())
+}
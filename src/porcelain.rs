@@ -0,0 +1,82 @@
+use anyhow::{anyhow, Result};
+
+use crate::session::Session;
+use crate::state::State;
+
+// `list --porcelain=v1`: エディタの拡張やスクリプト向けの安定した出力。
+//
+// v1 の約束:
+// - 1行に1セッション、見出しなし。並びは通常の list と同じ（ピン留め優先、ホストごと、新しい順）
+// - フィールドはタブ区切りで次の順（値のない項目は空文字列）
+//   1. session_id
+//   2. status        active / waiting / stopped / 独自ステータス名
+//   3. pane_id
+//   4. host          ローカルなら空
+//   5. updated       ステータスが変わった時刻（UNIX秒）
+//   6. reason        承認待ちの理由（tool / plan / question / idle / other）
+//   7. flags         状態を表す文字の並び（i: idle?、s: スヌーズ中、p: ピン留め）
+//   8. branch        gitのブランチ
+//   9. cwd           作業ディレクトリ（絶対パス）
+//   10. summary      要約（なければ最初のプロンプト）
+//   11. model        Claudeのモデル名（claude-opus-4-1 など）
+//   12. context      コンテキストウィンドウの使用率（0〜100の整数、%は付けない）
+// - 値の中のタブと改行は空白に置き換える
+// - 互換性を保ったまま末尾にフィールドを足すことはある。既存のフィールドの順と意味を変えるときは v2 にする
+
+/// 出力の版
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PorcelainVersion {
+    V1,
+}
+
+impl PorcelainVersion {
+    /// "v1"（`--porcelain` だけなら最新ではなく v1）
+    pub fn parse(version: &str) -> Result<PorcelainVersion> {
+        match version {
+            "" | "v1" | "1" => Ok(PorcelainVersion::V1),
+            _ => Err(anyhow!("不明な --porcelain の版: {}（v1 のみ）", version)),
+        }
+    }
+}
+
+/// タブと改行を空白にする
+fn field(value: &str) -> String {
+    value.replace(['\t', '\n', '\r'], " ")
+}
+
+fn line_v1(session: &Session, state: &State) -> String {
+    let mut flags = String::new();
+    if session.idle {
+        flags.push('i');
+    }
+    if session.snoozed || state.is_snoozed(session) {
+        flags.push('s');
+    }
+    if state.is_pinned(&session.session_id) {
+        flags.push('p');
+    }
+    let summary = session.summary.as_ref().or(session.first_prompt.as_ref());
+    [
+        field(&session.session_id),
+        field(session.status.as_str()),
+        field(&session.pane_id),
+        field(session.host.as_deref().unwrap_or_default()),
+        session.updated.to_string(),
+        session.waiting_reason.as_ref().map(|r| r.name().to_string()).unwrap_or_default(),
+        flags,
+        field(session.git_branch.as_deref().unwrap_or_default()),
+        field(&session.cwd),
+        field(summary.map(String::as_str).unwrap_or_default()),
+        field(session.model.as_deref().unwrap_or_default()),
+        session.context_percent().map(|p| p.to_string()).unwrap_or_default(),
+    ]
+    .join("\t")
+}
+
+pub fn display_porcelain(sessions: &[Session], state: &State, version: PorcelainVersion) {
+    for session in sessions {
+        match version {
+            PorcelainVersion::V1 => println!("{}", line_v1(session, state)),
+        }
+    }
+}
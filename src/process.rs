@@ -0,0 +1,178 @@
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::session::{canonical_cwd, Session};
+
+static ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// プロセスによる生存確認を有効/無効にする（設定ファイルの process_liveness）
+pub fn set_process_liveness(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// 実行中のClaude Codeプロセス
+#[derive(Debug, Clone)]
+pub struct ClaudeProcess {
+    pub pid: u32,
+    pub cwd: Option<String>,
+    pub args: Vec<String>,
+}
+
+fn is_claude_command(args: &[String]) -> bool {
+    // ネイティブ版の `claude` と、node経由の `.../claude` や `@anthropic-ai/claude-code/cli.js` の両方を拾う
+    // （Windowsでは `claude.exe` や `@anthropic-ai\claude-code\cli.js`）
+    args.iter().take(2).any(|arg| {
+        let name = arg.rsplit(['/', '\\']).next().unwrap_or(arg);
+        let name = name.strip_suffix(".exe").unwrap_or(name);
+        name == "claude" || arg.replace('\\', "/").contains("@anthropic-ai/claude-code")
+    })
+}
+
+/// Windowsのコマンドラインを引数に分ける（ダブルクォートで囲まれた空白は区切らない）
+fn split_command_line(command_line: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in command_line.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    args.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        args.push(current);
+    }
+    args
+}
+
+/// Windowsのプロセス一覧を取得し直す間隔（PowerShellの起動が遅いため）
+const WINDOWS_CACHE_TTL: Duration = Duration::from_secs(5);
+
+// 最後に取得した時刻とClaude Codeのプロセス
+static WINDOWS_CACHE: Mutex<Option<(Instant, Vec<ClaudeProcess>)>> = Mutex::new(None);
+
+/// PowerShellでClaude Codeのプロセスを列挙する（Windows用、cwdは取得できない）
+fn list_windows_processes() -> Option<Vec<ClaudeProcess>> {
+    let mut cache = WINDOWS_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((fetched, ref processes)) = *cache
+        && fetched.elapsed() < WINDOWS_CACHE_TTL
+    {
+        return Some(processes.clone());
+    }
+
+    let output = Command::new("powershell.exe")
+        .args([
+            "-NoProfile",
+            "-Command",
+            "Get-CimInstance Win32_Process -Filter \"Name='claude.exe' OR Name='node.exe'\" \
+             | Select-Object ProcessId, CommandLine | ConvertTo-Json -Compress",
+        ])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        tracing::debug!("プロセス一覧を取得できません: {}", String::from_utf8_lossy(&output.stderr).trim());
+        return None;
+    }
+    // 該当が1件なら配列ではなくオブジェクト、0件なら空の出力になる
+    let value: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap_or_default();
+    let items = match value {
+        serde_json::Value::Array(items) => items,
+        serde_json::Value::Null => Vec::new(),
+        item => vec![item],
+    };
+    let processes: Vec<ClaudeProcess> = items
+        .iter()
+        .filter_map(|item| {
+            let pid = u32::try_from(item["ProcessId"].as_u64()?).ok()?;
+            let args = split_command_line(item["CommandLine"].as_str()?);
+            is_claude_command(&args).then_some(ClaudeProcess { pid, cwd: None, args })
+        })
+        .collect();
+    *cache = Some((Instant::now(), processes.clone()));
+    Some(processes)
+}
+
+/// Claude Codeのプロセスを列挙する（/procまたはWindowsのプロセス一覧、取得できない環境や無効時はNone）
+pub fn list_claude_processes() -> Option<Vec<ClaudeProcess>> {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return None;
+    }
+    if cfg!(windows) {
+        return list_windows_processes();
+    }
+    if !Path::new("/proc").exists() {
+        return None;
+    }
+
+    let mut processes = Vec::new();
+    for entry in fs::read_dir("/proc").ok()? {
+        let Ok(entry) = entry else {
+            continue;
+        };
+        let Some(pid) = entry.file_name().to_str().and_then(|s| s.parse::<u32>().ok()) else {
+            continue;
+        };
+
+        let Ok(cmdline) = fs::read(entry.path().join("cmdline")) else {
+            continue;
+        };
+        let args: Vec<String> = cmdline
+            .split(|&b| b == 0)
+            .filter(|a| !a.is_empty())
+            .map(|a| String::from_utf8_lossy(a).to_string())
+            .collect();
+        if !is_claude_command(&args) {
+            continue;
+        }
+
+        let cwd = fs::read_link(entry.path().join("cwd"))
+            .ok()
+            .map(|p| p.to_string_lossy().to_string());
+        processes.push(ClaudeProcess { pid, cwd, args });
+    }
+
+    Some(processes)
+}
+
+/// セッションに対応するClaude Codeプロセス（セッションID、なければcwdで照合）
+///
+/// /proc/<pid>/cwd はシンボリックリンクを解決したパスなので、セッションのcwdも解決してから比べる。
+pub fn find_session_process<'a>(
+    session: &Session,
+    processes: &'a [ClaudeProcess],
+) -> Option<&'a ClaudeProcess> {
+    processes
+        .iter()
+        .find(|p| p.args.iter().any(|a| a == &session.session_id))
+        .or_else(|| {
+            let canonical = canonical_cwd(&session.cwd);
+            processes.iter().find(|p| {
+                p.cwd
+                    .as_deref()
+                    .is_some_and(|cwd| cwd == session.cwd || cwd == canonical)
+            })
+        })
+}
+
+/// セッションのClaude Codeプロセスが動いているか（分からなければNone）
+///
+/// Windowsではcwdを取得できないため、引数のセッションID（`--resume <id>`）で見つからなくても
+/// cwdを取得できないプロセスが残っていれば終了したとは言えない。
+pub fn is_session_alive(session: &Session, processes: &[ClaudeProcess]) -> Option<bool> {
+    if find_session_process(session, processes).is_some() {
+        Some(true)
+    } else if processes.iter().any(|p| p.cwd.is_none()) {
+        None
+    } else {
+        Some(false)
+    }
+}
@@ -0,0 +1,118 @@
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::config::{home_dir, ProjectConfig};
+use crate::display::format_dir_name;
+use crate::session::Session;
+use crate::status::SessionStatus;
+
+/// プロジェクトの指定（ディレクトリ名、またはパスでその下も含む）
+#[derive(Debug, Clone)]
+pub enum ProjectPattern {
+    Name(String),
+    Path(PathBuf),
+}
+
+impl ProjectPattern {
+    /// "sandbox" はディレクトリ名、"~/scratch" や "/work/api" はパス
+    pub fn parse(pattern: &str) -> Result<ProjectPattern> {
+        if let Some(rest) = pattern.strip_prefix('~') {
+            let home = home_dir().context("プロジェクトのパスの ~ を展開できません")?;
+            Ok(ProjectPattern::Path(home.join(rest.trim_start_matches(['/', '\\']))))
+        } else if Path::new(pattern).is_absolute() {
+            Ok(ProjectPattern::Path(PathBuf::from(pattern)))
+        } else {
+            Ok(ProjectPattern::Name(pattern.to_string()))
+        }
+    }
+
+    pub fn matches(&self, cwd: &str) -> bool {
+        match self {
+            ProjectPattern::Name(name) => name == format_dir_name(cwd),
+            ProjectPattern::Path(path) => Path::new(cwd).starts_with(path),
+        }
+    }
+}
+
+static PROJECTS: OnceLock<Vec<(ProjectPattern, ProjectConfig)>> = OnceLock::new();
+
+/// 起動時に [[projects]] の設定を反映する（以降は変更しない）
+pub fn init_projects(projects: &[ProjectConfig]) -> Result<()> {
+    let projects = projects
+        .iter()
+        .map(|project| Ok((ProjectPattern::parse(&project.path)?, project.clone())))
+        .collect::<Result<Vec<_>>>()?;
+    let _ = PROJECTS.set(projects);
+    Ok(())
+}
+
+/// cwdに一致する [[projects]] の設定（複数一致すればすべて）
+fn matching(cwd: &str) -> impl Iterator<Item = &'static ProjectConfig> {
+    PROJECTS
+        .get()
+        .into_iter()
+        .flatten()
+        .filter(move |(pattern, _)| pattern.matches(cwd))
+        .map(|(_, project)| project)
+}
+
+/// 設定で一覧から隠すセッションか
+pub fn is_hidden_by_project(session: &Session) -> bool {
+    matching(&session.cwd).any(|project| project.hide || (project.hide_stopped && session.status == SessionStatus::Stopped))
+}
+
+/// 設定で常にピン留めするプロジェクトのセッションか
+pub fn is_pinned_by_project(session: &Session) -> bool {
+    matching(&session.cwd).any(|project| project.pin)
+}
+
+/// globの一致（* と ? はパスの区切りをまたがない、** はまたぐ）
+fn glob_match(pattern: &[char], path: &[char]) -> bool {
+    let is_separator = |c: char| c == '/' || c == '\\';
+    match pattern {
+        [] => path.is_empty(),
+        ['*', '*', rest @ ..] => {
+            // "**/" は0個のディレクトリにも一致する
+            (rest.first().is_some_and(|&c| is_separator(c)) && glob_match(&rest[1..], path))
+                || (0..=path.len()).any(|i| glob_match(rest, &path[i..]))
+        }
+        ['*', rest @ ..] => (0..=path.len())
+            .take_while(|&i| i == 0 || !is_separator(path[i - 1]))
+            .any(|i| glob_match(rest, &path[i..])),
+        ['?', rest @ ..] => path.first().is_some_and(|&c| !is_separator(c)) && glob_match(rest, &path[1..]),
+        [c, rest @ ..] => path.first() == Some(c) && glob_match(rest, &path[1..]),
+    }
+}
+
+/// 無視するcwdのglob（絶対パスならパス全体、そうでなければディレクトリ名と比べる）
+static IGNORE: OnceLock<Vec<String>> = OnceLock::new();
+
+/// 起動時に ignore の設定と環境変数 CLAUDE_WATCH_IGNORE（PATHと同じ区切り）を反映する
+pub fn init_ignore(patterns: &[String]) -> Result<()> {
+    let from_env: Vec<String> = std::env::var_os("CLAUDE_WATCH_IGNORE")
+        .map(|value| std::env::split_paths(&value).map(|p| p.to_string_lossy().to_string()).collect())
+        .unwrap_or_default();
+    let mut expanded = Vec::new();
+    for pattern in patterns.iter().chain(&from_env).filter(|p| !p.is_empty()) {
+        match pattern.strip_prefix('~') {
+            Some(rest) => {
+                let home = home_dir().context("ignore の ~ を展開できません")?;
+                expanded.push(format!("{}/{}", home.display(), rest.trim_start_matches(['/', '\\'])));
+            }
+            None => expanded.push(pattern.clone()),
+        }
+    }
+    let _ = IGNORE.set(expanded);
+    Ok(())
+}
+
+/// 一覧からも通知からも除くcwdか
+pub fn is_ignored(cwd: &str) -> bool {
+    IGNORE.get().into_iter().flatten().any(|pattern| {
+        let target = if Path::new(pattern).is_absolute() { cwd } else { format_dir_name(cwd) };
+        // "/tmp/**" は /tmp 自体にも一致させる
+        pattern.strip_suffix("/**").is_some_and(|dir| dir == target)
+            || glob_match(&pattern.chars().collect::<Vec<_>>(), &target.chars().collect::<Vec<_>>())
+    })
+}
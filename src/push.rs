@@ -0,0 +1,125 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use crate::config::{PushConfig, PushService};
+use crate::display::format_cwd;
+use crate::quiet::should_notify;
+use crate::status::SessionStatus;
+use crate::transitions::Transition;
+use crate::webhook::{detail, expand_link, post_json, title};
+
+// 席を外しているときに承認待ちをスマートフォンへ知らせる（ntfy / Pushover）。
+// 送りすぎないよう、セッションごとの間隔と1時間あたりの件数で間引く。
+
+const PUSHOVER_URL: &str = "https://api.pushover.net/1/messages.json";
+
+/// 送信の記録（チャンネルは [[push]] の順番）
+struct Sent {
+    /// (チャンネル, セッションID) → 最後に送った時刻
+    last: BTreeMap<(usize, String), u64>,
+    /// チャンネル → 直近1時間に送った時刻
+    recent: BTreeMap<usize, Vec<u64>>,
+}
+
+static SENT: Mutex<Sent> = Mutex::new(Sent {
+    last: BTreeMap::new(),
+    recent: BTreeMap::new(),
+});
+
+impl Sent {
+    /// 間隔と件数の制限内なら送信を記録してtrue
+    fn try_record(&mut self, channel: usize, push: &PushConfig, session_id: &str, now: u64) -> bool {
+        let key = (channel, session_id.to_string());
+        if self.last.get(&key).is_some_and(|&at| now < at + push.min_interval) {
+            tracing::debug!(session_id, "同じセッションへのプッシュ通知の間隔が短いため送らない");
+            return false;
+        }
+        let recent = self.recent.entry(channel).or_default();
+        recent.retain(|&at| now < at + 3600);
+        if push.max_per_hour > 0 && recent.len() >= push.max_per_hour {
+            tracing::warn!(channel, "1時間あたりのプッシュ通知の上限に達したため送らない");
+            return false;
+        }
+        recent.push(now);
+        self.last.insert(key, now);
+        true
+    }
+}
+
+/// 承認待ちは目立たせる（ntfyの優先度、3が通常）
+fn ntfy_priority(status: Option<&SessionStatus>) -> u8 {
+    match status {
+        Some(SessionStatus::Waiting) => 4,
+        _ => 3,
+    }
+}
+
+fn message(transition: &Transition) -> String {
+    let session = &transition.session;
+    let mut message = format_cwd(&session.cwd);
+    if let Some(detail) = detail(session) {
+        message.push('\n');
+        message.push_str(&detail);
+    }
+    if let Some(ref host) = session.host {
+        message.push_str(&format!("\n({})", host));
+    }
+    message
+}
+
+fn send(push: &PushConfig, transition: &Transition) {
+    let link = push.link.as_deref().map(|template| expand_link(template, &transition.session));
+    match &push.service {
+        PushService::Ntfy { topic, server, token } => {
+            // JSONで送るとタイトルに日本語や絵文字を使える
+            let mut payload = serde_json::json!({
+                "topic": topic,
+                "title": title(transition),
+                "message": message(transition),
+                "priority": ntfy_priority(transition.to.as_ref()),
+            });
+            if let Some(url) = link {
+                payload["click"] = url.into();
+            }
+            let headers: Vec<String> = token.iter().map(|token| format!("Authorization: Bearer {}", token)).collect();
+            post_json(server.trim_end_matches('/'), &headers, &payload);
+        }
+        PushService::Pushover { token, user, device } => {
+            let mut payload = serde_json::json!({
+                "token": token,
+                "user": user,
+                "title": title(transition),
+                "message": message(transition),
+                "timestamp": transition.timestamp,
+            });
+            if let Some(device) = device {
+                payload["device"] = device.as_str().into();
+            }
+            if let Some(url) = link {
+                payload["url"] = url.into();
+            }
+            post_json(PUSHOVER_URL, &[], &payload);
+        }
+    }
+}
+
+/// 設定されたステータスになったセッションをプッシュ通知する
+pub fn dispatch_push(pushes: &[PushConfig], transitions: &[Transition]) {
+    if pushes.is_empty() {
+        return;
+    }
+    let mut sent = SENT.lock().unwrap_or_else(|e| e.into_inner());
+    for (channel, push) in pushes.iter().enumerate() {
+        for transition in transitions {
+            let Some(ref to) = transition.to else {
+                continue;
+            };
+            if !push.statuses.contains(to) || transition.session.snoozed || !should_notify(&transition.session) {
+                continue;
+            }
+            if sent.try_record(channel, push, &transition.session.session_id, transition.timestamp) {
+                send(push, transition);
+            }
+        }
+    }
+}
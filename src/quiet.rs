@@ -0,0 +1,85 @@
+use anyhow::{anyhow, Result};
+use chrono::Timelike;
+use std::sync::OnceLock;
+
+use crate::config::QuietConfig;
+use crate::projects::ProjectPattern;
+use crate::session::Session;
+
+// 夜間やサンドボックスのプロジェクトでは通知（ベル・デスクトップ通知・コマンド・Webhook）を送らない。
+// TUIの表示は変えない。
+
+struct Quiet {
+    /// 通知しない時間帯（0時からの分、開始 > 終了なら日をまたぐ）
+    hours: Option<(u32, u32)>,
+    /// 通知しないプロジェクト
+    muted: Vec<ProjectPattern>,
+}
+
+static QUIET: OnceLock<Quiet> = OnceLock::new();
+
+/// "HH:MM" を0時からの分にする
+fn parse_time(time: &str) -> Option<u32> {
+    let (hour, minute) = time.trim().split_once(':')?;
+    let (hour, minute): (u32, u32) = (hour.parse().ok()?, minute.parse().ok()?);
+    (hour < 24 && minute < 60).then_some(hour * 60 + minute)
+}
+
+/// 起動時に [quiet] の設定を反映する（以降は変更しない）
+pub fn init_quiet(config: &QuietConfig) -> Result<()> {
+    let hours = config
+        .hours
+        .as_deref()
+        .map(|hours| {
+            hours
+                .split_once('-')
+                .and_then(|(start, end)| Some((parse_time(start)?, parse_time(end)?)))
+                .ok_or_else(|| anyhow!("[quiet] hours の形式が不正です: {}（\"22:00-07:00\" の形式で指定）", hours))
+        })
+        .transpose()?;
+
+    let muted = config
+        .muted_projects
+        .iter()
+        .map(|project| ProjectPattern::parse(project))
+        .collect::<Result<Vec<_>>>()?;
+
+    let _ = QUIET.set(Quiet { hours, muted });
+    Ok(())
+}
+
+/// いまが通知しない時間帯か
+fn in_quiet_hours(quiet: &Quiet) -> bool {
+    let Some((start, end)) = quiet.hours else {
+        return false;
+    };
+    let now = chrono::Local::now();
+    let minute = now.hour() * 60 + now.minute();
+    if start <= end {
+        start <= minute && minute < end
+    } else {
+        minute >= start || minute < end
+    }
+}
+
+/// 通知を止めたプロジェクトのセッションか
+fn is_muted(quiet: &Quiet, session: &Session) -> bool {
+    quiet.muted.iter().any(|pattern| pattern.matches(&session.cwd))
+}
+
+/// いまが [quiet] hours の時間帯か（セッションによらない通知向け）
+pub fn is_quiet_time() -> bool {
+    QUIET.get().is_some_and(in_quiet_hours)
+}
+
+/// このセッションについて通知してよいか
+pub fn should_notify(session: &Session) -> bool {
+    let Some(quiet) = QUIET.get() else {
+        return true;
+    };
+    let notify = !in_quiet_hours(quiet) && !is_muted(quiet, session);
+    if !notify {
+        tracing::debug!(session_id = %session.session_id, "通知しない時間帯かプロジェクトのため通知しない");
+    }
+    notify
+}
@@ -0,0 +1,111 @@
+use anyhow::{anyhow, Result};
+
+use crate::display::truncate_text;
+
+/// 承認待ちになった理由（Notificationフックのメッセージと種類から判定）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WaitingReason {
+    /// "Claude needs your permission to use Bash"
+    ToolPermission { tool: String },
+    /// "Claude Code needs your approval for the plan"
+    PlanApproval,
+    /// AskUserQuestion による質問
+    Question,
+    /// "Claude is waiting for your input"（一定時間操作がない）
+    Idle,
+    /// 知らないメッセージ（そのまま持つ）
+    Other(String),
+}
+
+impl WaitingReason {
+    pub fn parse(message: &str, notification_type: Option<&str>) -> WaitingReason {
+        if notification_type == Some("idle_prompt") || message.contains("waiting for your input") {
+            return WaitingReason::Idle;
+        }
+        if let Some((_, tool)) = message.split_once("permission to use ") {
+            let tool = tool.trim();
+            if tool == "AskUserQuestion" {
+                return WaitingReason::Question;
+            }
+            return WaitingReason::ToolPermission { tool: tool.to_string() };
+        }
+        if message.contains("approval for the plan") {
+            return WaitingReason::PlanApproval;
+        }
+        if message.to_lowercase().contains("question") {
+            return WaitingReason::Question;
+        }
+        WaitingReason::Other(message.to_string())
+    }
+
+    /// 絞り込み・JSON出力での名前
+    pub fn name(&self) -> &'static str {
+        match self {
+            WaitingReason::ToolPermission { .. } => "tool",
+            WaitingReason::PlanApproval => "plan",
+            WaitingReason::Question => "question",
+            WaitingReason::Idle => "idle",
+            WaitingReason::Other(_) => "other",
+        }
+    }
+
+    /// `--reason` の値が正しいか確かめる
+    pub fn validate_name(name: &str) -> Result<()> {
+        match name {
+            "tool" | "plan" | "question" | "idle" | "other" => Ok(()),
+            _ => Err(anyhow!(
+                "不明な理由: {} (tool, plan, question, idle, other のいずれかを指定)",
+                name
+            )),
+        }
+    }
+
+    /// 許可を求めているツール
+    pub fn tool(&self) -> Option<&str> {
+        match self {
+            WaitingReason::ToolPermission { tool } => Some(tool),
+            _ => None,
+        }
+    }
+
+    /// "Bash許可待ち" のような短い表示
+    pub fn label(&self) -> String {
+        match self {
+            WaitingReason::ToolPermission { tool } => format!("{}許可待ち", tool),
+            WaitingReason::PlanApproval => "プラン承認待ち".to_string(),
+            WaitingReason::Question => "質問への回答待ち".to_string(),
+            WaitingReason::Idle => "入力待ち".to_string(),
+            WaitingReason::Other(message) => truncate_text(message, 40),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn waiting_reason_from_notification_message() {
+        let reason = WaitingReason::parse("Claude needs your permission to use Bash", None);
+        assert_eq!(reason, WaitingReason::ToolPermission { tool: "Bash".to_string() });
+        assert_eq!(reason.label(), "Bash許可待ち");
+        assert_eq!(
+            WaitingReason::parse("Claude needs your permission to use AskUserQuestion", None),
+            WaitingReason::Question
+        );
+        assert_eq!(
+            WaitingReason::parse("Claude Code needs your approval for the plan", None),
+            WaitingReason::PlanApproval
+        );
+        assert_eq!(WaitingReason::parse("Claude is waiting for your input", None), WaitingReason::Idle);
+        assert_eq!(WaitingReason::parse("", Some("idle_prompt")), WaitingReason::Idle);
+    }
+
+    #[test]
+    fn waiting_reason_keeps_unknown_message() {
+        let message = "Something unexpected happened while running a very long command";
+        let reason = WaitingReason::parse(message, None);
+        assert_eq!(reason, WaitingReason::Other(message.to_string()));
+        assert_eq!(reason.label(), truncate_text(message, 40));
+    }
+}
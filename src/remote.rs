@@ -0,0 +1,256 @@
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crate::alerts::{expand_placeholders, shell_command};
+use crate::config::RemoteConfig;
+use crate::git::GitStatus;
+use crate::session::{PaneConflict, Session, StatusSource};
+use crate::status::SessionStatus;
+use crate::transcript::{Subagent, TokenUsage};
+
+/// リモートの `claude-watch list --json` が出力するセッション（Session::to_json の形式）
+#[derive(Debug, Deserialize)]
+struct RemoteSession {
+    session_id: String,
+    host: Option<String>,
+    pane_id: String,
+    cwd: String,
+    status: SessionStatus,
+    notification_message: Option<String>,
+    notification_type: Option<String>,
+    current_tool: Option<String>,
+    last_message: Option<String>,
+    updated: u64,
+    summary: Option<String>,
+    first_prompt: Option<String>,
+    message_count: Option<u32>,
+    git_branch: Option<String>,
+    git_dirty: Option<usize>,
+    git_ahead: Option<u32>,
+    git_behind: Option<u32>,
+    modified: Option<String>,
+    memory_usage_kb: Option<u64>,
+    input_tokens: Option<u64>,
+    output_tokens: Option<u64>,
+    cost_usd: Option<f64>,
+    /// 古いバージョンの出力には含まれない
+    model: Option<String>,
+    /// 古いバージョンの出力には含まれない
+    context_tokens: Option<u64>,
+    #[serde(default)]
+    subagents: Vec<Subagent>,
+    /// 古いバージョンの出力には含まれない
+    #[serde(default)]
+    pane_conflicts: Vec<PaneConflict>,
+    /// 古いバージョンの出力には含まれない
+    #[serde(default)]
+    idle: bool,
+    /// 古いバージョンの出力には含まれない
+    #[serde(default)]
+    orphaned: bool,
+    status_source: Option<String>,
+    #[serde(default)]
+    snoozed: bool,
+    /// デーモンのソケット経由のときだけ含まれる
+    path: Option<PathBuf>,
+}
+
+impl RemoteSession {
+    fn into_session(self) -> Session {
+        let token_usage = match (self.input_tokens, self.output_tokens) {
+            (Some(input_tokens), Some(output_tokens)) => Some(TokenUsage {
+                input_tokens,
+                output_tokens,
+                cost_usd: self.cost_usd.unwrap_or(0.0),
+                unpriced: self.cost_usd.is_none(),
+                ..TokenUsage::default()
+            }),
+            _ => None,
+        };
+        let mut session = Session {
+            session_id: self.session_id,
+            pane_id: self.pane_id,
+            cwd: self.cwd,
+            status: self.status,
+            notification_message: self.notification_message,
+            notification_type: self.notification_type,
+            current_tool: self.current_tool,
+            last_message: self.last_message,
+            waiting_reason: None,
+            updated: self.updated,
+            summary: self.summary,
+            first_prompt: self.first_prompt,
+            message_count: self.message_count,
+            git_branch: self.git_branch,
+            git_status: self.git_dirty.map(|dirty| GitStatus {
+                dirty,
+                ahead: self.git_ahead.unwrap_or(0),
+                behind: self.git_behind.unwrap_or(0),
+            }),
+            modified: self.modified,
+            memory_usage_kb: self.memory_usage_kb,
+            path: self.path.unwrap_or_default(),
+            token_usage,
+            model: self.model,
+            context_tokens: self.context_tokens,
+            subagents: self.subagents,
+            pane_conflicts: self.pane_conflicts,
+            host: self.host,
+            idle: self.idle,
+            orphaned: self.orphaned,
+            status_source: self.status_source.as_deref().map(StatusSource::parse).unwrap_or_default(),
+            snoozed: self.snoozed,
+        };
+        session.waiting_reason = session.parse_waiting_reason();
+        session
+    }
+}
+
+/// sshでリモートのセッション一覧を取得する
+pub fn fetch_remote_sessions(remote: &RemoteConfig) -> Result<Vec<Session>> {
+    tracing::debug!(host = %remote.host, command = %remote.command, "リモートのセッションを取得");
+    let output = Command::new("ssh")
+        // パスワードを聞かれて止まらないようにする
+        .args(["-o", "BatchMode=yes", "-o", "ConnectTimeout=5"])
+        .arg(&remote.host)
+        .arg(&remote.command)
+        .output()
+        .context("sshの実行に失敗")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!("{}: {}", remote.name, stderr.trim()));
+    }
+    let sessions: Vec<RemoteSession> = serde_json::from_slice(&output.stdout)
+        .with_context(|| format!("{}: セッション一覧のJSONを解析できません", remote.name))?;
+    Ok(sessions
+        .into_iter()
+        .map(|s| Session {
+            host: Some(remote.name.clone()),
+            ..s.into_session()
+        })
+        .collect())
+}
+
+/// Session::to_json の形式のJSONからセッションを復元する
+pub fn session_from_json(value: serde_json::Value) -> Result<Session> {
+    let session: RemoteSession = serde_json::from_value(value).context("セッションのJSONを解析できません")?;
+    Ok(session.into_session())
+}
+
+/// 全リモートのセッションを並行して取得する（取得できなかったホストはエラーとして返す）
+pub fn load_remote_sessions(remotes: &[RemoteConfig]) -> (Vec<Session>, Vec<anyhow::Error>) {
+    thread::scope(|scope| {
+        let handles: Vec<_> = remotes
+            .iter()
+            .map(|remote| scope.spawn(move || fetch_remote_sessions(remote)))
+            .collect();
+
+        let mut sessions = Vec::new();
+        let mut errors = Vec::new();
+        for handle in handles {
+            match handle.join().expect("リモート取得スレッドがパニック") {
+                Ok(remote_sessions) => sessions.extend(remote_sessions),
+                Err(e) => errors.push(e),
+            }
+        }
+        (sessions, errors)
+    })
+}
+
+/// リモートのセッションのペインへ [[remote]] の jump コマンドでジャンプする
+pub fn jump_to_remote_pane(remotes: &[RemoteConfig], session: &Session) -> Result<()> {
+    let host = session.host.as_deref().unwrap_or_default();
+    let remote = remotes
+        .iter()
+        .find(|r| r.name == host)
+        .ok_or_else(|| anyhow!("[[remote]] {} が設定にありません", host))?;
+    let template = remote
+        .jump
+        .as_deref()
+        .ok_or_else(|| anyhow!("[[remote]] {} に jump が設定されていません", host))?;
+    let command = expand_placeholders(template, session);
+    tracing::debug!(%command, "リモートのペインへジャンプ");
+    let output = shell_command(&command)
+        .stdin(Stdio::null())
+        .output()
+        .context("ジャンプコマンドの実行に失敗")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!("ジャンプコマンドが失敗しました: {}", stderr.trim()));
+    }
+    Ok(())
+}
+
+/// ホストごとにまとめる（ローカルが先、同じホスト内の順序は保つ）
+pub fn group_by_host(sessions: &mut [Session]) {
+    sessions.sort_by(|a, b| a.host.cmp(&b.host));
+}
+
+/// ホストごとのセッション数（ローカルは "local"、ローカル・ホスト名の順）
+pub fn count_by_host(sessions: &[Session]) -> Vec<(&str, usize)> {
+    let mut counts: BTreeMap<Option<&str>, usize> = BTreeMap::new();
+    for session in sessions {
+        *counts.entry(session.host.as_deref()).or_default() += 1;
+    }
+    counts
+        .into_iter()
+        .map(|(host, count)| (host.unwrap_or("local"), count))
+        .collect()
+}
+
+/// リモートのセッションをバックグラウンドで定期的に取得する（TUI・デーモン用）
+///
+/// sshは遅いので画面の更新とは別のスレッドで取得し、最後に取得できた一覧を返す。
+pub struct RemotePoller {
+    sessions: Arc<Mutex<BTreeMap<String, Vec<Session>>>>,
+}
+
+impl RemotePoller {
+    pub fn start(remotes: &[RemoteConfig]) -> Self {
+        let sessions = Arc::new(Mutex::new(BTreeMap::new()));
+        for remote in remotes {
+            let remote = remote.clone();
+            let sessions = Arc::clone(&sessions);
+            thread::spawn(move || {
+                loop {
+                    match fetch_remote_sessions(&remote) {
+                        Ok(fetched) => {
+                            sessions
+                                .lock()
+                                .unwrap_or_else(|e| e.into_inner())
+                                .insert(remote.name.clone(), fetched);
+                        }
+                        // 一時的な失敗では直前の一覧を残す
+                        Err(e) => tracing::warn!("リモートのセッションを取得できません: {:#}", e),
+                    }
+                    thread::sleep(Duration::from_secs(remote.interval.max(1)));
+                }
+            });
+        }
+        Self { sessions }
+    }
+
+    /// 最後に取得できたリモートのセッション
+    pub fn sessions(&self) -> Vec<Session> {
+        self.sessions
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .flatten()
+            .cloned()
+            .collect()
+    }
+
+    /// ローカルのセッションにリモートのものを加えて新しい順に並べる
+    pub fn merge(&self, mut sessions: Vec<Session>) -> Vec<Session> {
+        sessions.extend(self.sessions());
+        sessions.sort_by_key(|s| std::cmp::Reverse(s.updated));
+        sessions
+    }
+}
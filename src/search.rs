@@ -0,0 +1,225 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::config::SearchConfig;
+use crate::search_index::{SearchIndex, MIN_QUERY_CHARS};
+use crate::session::get_projects_dir;
+use crate::transcript::{load_conversation, Role};
+
+// `claude-watch search <query>`: すべてのプロジェクトのトランスクリプトから会話を全文検索する。
+// [search] index が有効なら索引（search_index.rs）を引き、そうでなければ
+// ファイル全体に語が含まれるものだけ会話として読み直す（どちらも大文字小文字は区別しない）。
+
+/// 1セッションで保持する一致の数（件数はすべて数える）
+pub const MAX_MATCHES_PER_SESSION: usize = 3;
+/// 一致した語の前後に出す文字数
+const SNIPPET_BEFORE: usize = 40;
+const SNIPPET_AFTER: usize = 60;
+pub const SNIPPET_CHARS: usize = SNIPPET_BEFORE + SNIPPET_AFTER;
+/// デーモンが索引に追記分を取り込む間隔
+const INDEX_REFRESH: Duration = Duration::from_secs(30);
+
+/// 一致した発言
+#[derive(Debug, Clone)]
+pub struct SearchMatch {
+    pub role: Role,
+    pub timestamp: Option<String>,
+    /// 一致した語の前後（空白と改行は1つの空白にまとめる）
+    pub snippet: String,
+}
+
+/// 一致したセッション
+#[derive(Debug, Clone)]
+pub struct SearchResult {
+    pub session_id: String,
+    /// トランスクリプトに記録されたcwd（なければプロジェクトのディレクトリ名）
+    pub cwd: String,
+    pub path: PathBuf,
+    pub modified: SystemTime,
+    pub matches: Vec<SearchMatch>,
+    /// 一致した発言の数
+    pub match_count: usize,
+}
+
+impl SearchResult {
+    pub fn to_json(&self) -> serde_json::Value {
+        let modified = chrono::DateTime::<chrono::Utc>::from(self.modified).to_rfc3339();
+        serde_json::json!({
+            "session_id": self.session_id,
+            "cwd": self.cwd,
+            "path": self.path,
+            "modified": modified,
+            "match_count": self.match_count,
+            "matches": self.matches.iter().map(|m| serde_json::json!({
+                "role": m.role.name(),
+                "timestamp": m.timestamp,
+                "snippet": m.snippet,
+            })).collect::<Vec<_>>(),
+        })
+    }
+}
+
+/// 大文字小文字を区別しない比較のため1文字ずつ小文字にする（位置を元の文字列とそろえる）
+pub fn fold(text: &str) -> Vec<char> {
+    text.chars().map(|c| c.to_lowercase().next().unwrap_or(c)).collect()
+}
+
+/// textの中でqueryが最初に現れる文字位置
+fn find_folded(text: &[char], query: &[char]) -> Option<usize> {
+    if query.is_empty() || query.len() > text.len() {
+        return None;
+    }
+    (0..=text.len() - query.len()).find(|&i| text[i..i + query.len()] == *query)
+}
+
+/// 一致した位置の前後を切り出す
+pub fn snippet(text: &str, query: &[char]) -> Option<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let start = find_folded(&fold(text), query)?;
+    let from = start.saturating_sub(SNIPPET_BEFORE);
+    let to = (start + query.len() + SNIPPET_AFTER).min(chars.len());
+    let body: String = chars[from..to].iter().collect();
+    let body = body.split_whitespace().collect::<Vec<_>>().join(" ");
+    Some(format!(
+        "{}{}{}",
+        if from > 0 { "…" } else { "" },
+        body,
+        if to < chars.len() { "…" } else { "" }
+    ))
+}
+
+/// トランスクリプトで最初に記録されたcwd
+pub fn transcript_cwd(content: &str) -> Option<String> {
+    content.lines().take(50).find_map(|line| {
+        let entry: serde_json::Value = serde_json::from_str(line).ok()?;
+        entry["cwd"].as_str().map(str::to_string)
+    })
+}
+
+/// 1つのトランスクリプトを検索する（一致しなければNone）
+fn search_file(path: &Path, query: &[char]) -> Result<Option<SearchResult>> {
+    let content = fs::read_to_string(path).with_context(|| format!("トランスクリプト読み込みエラー: {:?}", path))?;
+    // JSONのままでも含まれていなければ会話として読むまでもない
+    if find_folded(&fold(&content), query).is_none() {
+        return Ok(None);
+    }
+
+    let mut matches = Vec::new();
+    let mut match_count = 0;
+    for message in load_conversation(path)? {
+        let Some(snippet) = snippet(&message.text, query) else {
+            continue;
+        };
+        match_count += 1;
+        if matches.len() < MAX_MATCHES_PER_SESSION {
+            matches.push(SearchMatch {
+                role: message.role,
+                timestamp: message.timestamp,
+                snippet,
+            });
+        }
+    }
+    if match_count == 0 {
+        return Ok(None);
+    }
+
+    let session_id = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+    let cwd = transcript_cwd(&content).unwrap_or_else(|| {
+        let project = path.parent().and_then(Path::file_name).unwrap_or_default();
+        project.to_string_lossy().to_string()
+    });
+    Ok(Some(SearchResult {
+        session_id,
+        cwd,
+        path: path.to_path_buf(),
+        modified: fs::metadata(path)?.modified()?,
+        matches,
+        match_count,
+    }))
+}
+
+static SEARCH: OnceLock<SearchConfig> = OnceLock::new();
+
+/// 起動時に [search] の設定を反映する（以降は変更しない）
+pub fn init_search(config: &SearchConfig) {
+    let _ = SEARCH.set(config.clone());
+}
+
+fn index_enabled() -> bool {
+    SEARCH.get().is_some_and(|c| c.index)
+}
+
+/// すべてのプロジェクトのトランスクリプト（~/.claude/projects/<project>/<id>.jsonl）
+pub fn transcript_files() -> Result<Vec<PathBuf>> {
+    let projects_dir = get_projects_dir()?;
+    if !projects_dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut files = Vec::new();
+    for entry in fs::read_dir(&projects_dir).context("プロジェクトディレクトリの読み込みに失敗")? {
+        let project_dir = entry?.path();
+        if !project_dir.is_dir() {
+            continue;
+        }
+        for file in fs::read_dir(&project_dir)? {
+            let path = file?.path();
+            if path.extension().and_then(|s| s.to_str()) == Some("jsonl") {
+                files.push(path);
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// 索引に追記分を取り込む（索引が無効か、前回から INDEX_REFRESH たっていなければ何もしない）
+pub fn refresh_search_index() {
+    static LAST: Mutex<Option<Instant>> = Mutex::new(None);
+    if !index_enabled() {
+        return;
+    }
+    let mut last = LAST.lock().unwrap_or_else(|e| e.into_inner());
+    if last.is_some_and(|at| at.elapsed() < INDEX_REFRESH) {
+        return;
+    }
+    *last = Some(Instant::now());
+    match SearchIndex::open().and_then(|mut index| index.update()) {
+        Ok(added) if added > 0 => tracing::debug!(added, "検索の索引を更新しました"),
+        Ok(_) => {}
+        Err(e) => tracing::warn!("検索の索引を更新できません: {:#}", e),
+    }
+}
+
+/// 索引を最新にしてから引く
+fn search_index(query: &str, limit: usize) -> Result<Vec<SearchResult>> {
+    let mut index = SearchIndex::open()?;
+    index.update()?;
+    index.search(query, limit)
+}
+
+/// すべてのプロジェクトのトランスクリプトを検索し、新しく更新されたセッションから最大limit件を返す
+pub fn search_transcripts(query: &str, limit: usize) -> Result<Vec<SearchResult>> {
+    if index_enabled() && query.trim().chars().count() >= MIN_QUERY_CHARS {
+        match search_index(query, limit) {
+            Ok(results) => return Ok(results),
+            // 索引が壊れていても検索はできるようにする
+            Err(e) => tracing::warn!("検索の索引を使えないためトランスクリプトを読み直します: {:#}", e),
+        }
+    }
+
+    let query = fold(query.trim());
+    let mut results = Vec::new();
+    for path in transcript_files()? {
+        match search_file(&path, &query) {
+            Ok(Some(result)) => results.push(result),
+            Ok(None) => {}
+            Err(e) => tracing::debug!("{:#}", e),
+        }
+    }
+
+    results.sort_by_key(|r| std::cmp::Reverse(r.modified));
+    results.truncate(limit);
+    Ok(results)
+}
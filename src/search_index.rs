@@ -0,0 +1,207 @@
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection, OptionalExtension, TransactionBehavior};
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::display::truncate_text;
+use crate::search::{
+    fold, snippet, transcript_cwd, transcript_files, SearchMatch, SearchResult, MAX_MATCHES_PER_SESSION, SNIPPET_CHARS,
+};
+use crate::state::get_state_dir;
+use crate::transcript::{conversation_messages, Role};
+
+/// 会話ログの全文検索の索引（$XDG_STATE_HOME/claude-watch/search.db）
+pub struct SearchIndex {
+    conn: Connection,
+}
+
+// trigram なら日本語も部分一致で引ける（3文字未満の語は引けない）
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS files (
+    path TEXT PRIMARY KEY,
+    session_id TEXT NOT NULL,
+    cwd TEXT,
+    -- 取り込んだ位置（バイト）
+    offset INTEGER NOT NULL,
+    modified INTEGER NOT NULL
+);
+CREATE VIRTUAL TABLE IF NOT EXISTS messages USING fts5(
+    text,
+    path UNINDEXED,
+    role UNINDEXED,
+    timestamp UNINDEXED,
+    tokenize = 'trigram'
+);
+";
+
+/// 索引で引ける語の最短の長さ（文字数）
+pub const MIN_QUERY_CHARS: usize = 3;
+
+pub fn get_index_path() -> Result<PathBuf> {
+    Ok(get_state_dir()?.join("search.db"))
+}
+
+fn unix_secs(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs() as i64)
+}
+
+impl SearchIndex {
+    pub fn open() -> Result<SearchIndex> {
+        let path = get_index_path()?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).with_context(|| format!("ディレクトリの作成に失敗: {:?}", dir))?;
+        }
+        let conn = Connection::open(&path)
+            .with_context(|| format!("検索の索引を開けません: {:?}", path))?;
+        // デーモンと `search` が同時に取り込むことがある
+        conn.busy_timeout(Duration::from_secs(5))?;
+        conn.execute_batch(SCHEMA)
+            .with_context(|| format!("検索の索引の初期化に失敗: {:?}", path))?;
+        Ok(SearchIndex { conn })
+    }
+
+    /// 新しいトランスクリプトと追記分を取り込み、消えたトランスクリプトを除く（取り込んだ発言数を返す）
+    pub fn update(&mut self) -> Result<usize> {
+        let mut added = 0;
+        let mut seen = HashSet::new();
+        for path in transcript_files()? {
+            let key = path.to_string_lossy().to_string();
+            match self.index_file(&path, &key) {
+                Ok(n) => added += n,
+                Err(e) => tracing::debug!("{:#}", e),
+            }
+            seen.insert(key);
+        }
+
+        let known: Vec<String> = self
+            .conn
+            .prepare("SELECT path FROM files")?
+            .query_map([], |row| row.get(0))?
+            .collect::<rusqlite::Result<_>>()?;
+        for path in known.iter().filter(|p| !seen.contains(*p)) {
+            let tx = self.conn.transaction()?;
+            tx.execute("DELETE FROM messages WHERE path = ?1", params![path])?;
+            tx.execute("DELETE FROM files WHERE path = ?1", params![path])?;
+            tx.commit()?;
+        }
+        Ok(added)
+    }
+
+    /// 1つのトランスクリプトの続きを取り込む（追記されるだけなので、読み込んだ位置から先だけ読む）
+    fn index_file(&mut self, path: &Path, key: &str) -> Result<usize> {
+        let metadata = fs::metadata(path)?;
+        let size = metadata.len();
+        let offset: Option<u64> = self
+            .conn
+            .query_row("SELECT offset FROM files WHERE path = ?1", params![key], |row| row.get(0))
+            .optional()?;
+        if offset == Some(size) {
+            return Ok(0);
+        }
+
+        // 同じファイルを二重に取り込まないよう、位置を読み直すところから書き込みの排他をとる
+        let tx = self.conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+        let known: Option<(u64, Option<String>)> = tx
+            .query_row("SELECT offset, cwd FROM files WHERE path = ?1", params![key], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .optional()?;
+        let (offset, cwd) = match known {
+            Some((offset, _)) if offset == size => return Ok(0),
+            Some((offset, cwd)) if offset < size => (offset, cwd),
+            // 縮んだら書き直されたものとして最初から取り込む
+            Some(_) => {
+                tx.execute("DELETE FROM messages WHERE path = ?1", params![key])?;
+                (0, None)
+            }
+            None => (0, None),
+        };
+
+        let mut file = File::open(path).with_context(|| format!("トランスクリプト読み込みエラー: {:?}", path))?;
+        file.seek(SeekFrom::Start(offset))?;
+        let mut buf = Vec::new();
+        file.take(size - offset).read_to_end(&mut buf)?;
+        // 書きかけの最後の行は次の機会に取り込む
+        let end = buf.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+        let chunk = String::from_utf8_lossy(&buf[..end]);
+
+        let mut added = 0;
+        {
+            let mut stmt = tx.prepare_cached(
+                "INSERT INTO messages (text, path, role, timestamp) VALUES (?1, ?2, ?3, ?4)",
+            )?;
+            for message in chunk.lines().flat_map(conversation_messages) {
+                stmt.execute(params![message.text, key, message.role.name(), message.timestamp])?;
+                added += 1;
+            }
+        }
+        let session_id = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+        tx.execute(
+            "INSERT OR REPLACE INTO files (path, session_id, cwd, offset, modified) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                key,
+                session_id,
+                cwd.or_else(|| transcript_cwd(&chunk)),
+                (offset + end as u64) as i64,
+                unix_secs(metadata.modified()?),
+            ],
+        )?;
+        tx.commit()?;
+        Ok(added)
+    }
+
+    /// 語を含む発言のあるセッションを新しく更新された順に最大limit件返す
+    pub fn search(&self, query: &str, limit: usize) -> Result<Vec<SearchResult>> {
+        // 語全体を1つのフレーズとして引く
+        let phrase = format!("\"{}\"", query.trim().replace('"', "\"\""));
+        let folded = fold(query.trim());
+        let mut stmt = self.conn.prepare(
+            "SELECT files.path, files.session_id, files.cwd, files.modified,
+                    messages.role, messages.timestamp, messages.text
+             FROM messages JOIN files ON files.path = messages.path
+             WHERE messages MATCH ?1
+             ORDER BY files.modified DESC, files.path, messages.rowid",
+        )?;
+        let mut rows = stmt.query(params![phrase])?;
+
+        let mut results: Vec<SearchResult> = Vec::new();
+        while let Some(row) = rows.next()? {
+            let path: String = row.get(0)?;
+            if results.last().is_none_or(|r| r.path != Path::new(&path)) {
+                if results.len() == limit {
+                    break;
+                }
+                let session_id: String = row.get(1)?;
+                let cwd: Option<String> = row.get(2)?;
+                let project = Path::new(&path).parent().and_then(Path::file_name).unwrap_or_default();
+                results.push(SearchResult {
+                    session_id,
+                    cwd: cwd.unwrap_or_else(|| project.to_string_lossy().to_string()),
+                    path: PathBuf::from(&path),
+                    modified: UNIX_EPOCH + Duration::from_secs(row.get::<_, i64>(3)?.max(0) as u64),
+                    matches: Vec::new(),
+                    match_count: 0,
+                });
+            }
+            let Some(result) = results.last_mut() else {
+                continue;
+            };
+            result.match_count += 1;
+            if result.matches.len() < MAX_MATCHES_PER_SESSION {
+                let text: String = row.get(6)?;
+                let Some(role) = Role::from_name(&row.get::<_, String>(4)?) else {
+                    continue;
+                };
+                result.matches.push(SearchMatch {
+                    role,
+                    timestamp: row.get(5)?,
+                    snippet: snippet(&text, &folded).unwrap_or_else(|| truncate_text(&text, SNIPPET_CHARS)),
+                });
+            }
+        }
+        Ok(results)
+    }
+}
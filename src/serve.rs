@@ -0,0 +1,299 @@
+use anyhow::{anyhow, Context, Result};
+use serde_json::Value;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::backend::jump_to_pane;
+use crate::config::{Config, RemoteConfig};
+use crate::display::glyph;
+use crate::remote::{jump_to_remote_pane, RemotePoller};
+use crate::session::{find_session_by_id, load_active_sessions, Session};
+use crate::store::StoredTransition;
+use crate::transitions::StatusTracker;
+use crate::watcher::SessionWatcher;
+
+// `claude-watch serve`: セッションの状態をHTTPで公開する（Webのダッシュボードやエディタの拡張向け）。
+//
+// - GET /sessions  セッション一覧（list --json と同じ形式）
+// - GET /events    ステータス変化のServer-Sent Events（data は events --json と同じ形式）
+//
+// --web では加えて次を提供する（ジャンプできるのは --web のときだけ）。
+//
+// - GET /                        ダッシュボード（バイナリに埋め込んだHTML）
+// - POST /sessions/<id>/jump     セッションのペインにジャンプ
+//
+// ダッシュボードのHTML以外には X-Claude-Watch-Token ヘッダーか ?token= でトークン（[serve] token、未指定なら
+// 起動時に生成して表示）が必要で、別のオリジンのページからは受け付けない。DNSリバインディングで読まれないよう、
+// Host が 127.0.0.1 / localhost（と待ち受けているアドレス）でないリクエストはすべて拒否する。
+
+/// 接続が切れたクライアントを見つけるためのコメントを送る間隔
+const KEEPALIVE: Duration = Duration::from_secs(15);
+
+const DASHBOARD: &str = include_str!("dashboard.html");
+
+#[derive(Default)]
+struct Shared {
+    sessions: Vec<Session>,
+    snapshot: Vec<Value>,
+    subscribers: Vec<TcpStream>,
+    /// SSEのイベントID（通し番号）
+    next_id: u64,
+}
+
+impl Shared {
+    /// 購読中のクライアントへ送る（書き込めなくなったクライアントは外す）
+    fn broadcast(&mut self, message: &str) {
+        self.subscribers
+            .retain_mut(|stream| stream.write_all(message.as_bytes()).and_then(|_| stream.flush()).is_ok());
+    }
+}
+
+fn respond(stream: &mut TcpStream, status: &str, content_type: &str, body: &str) -> Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )?;
+    Ok(())
+}
+
+/// リクエスト行と、使うヘッダー（本文は使わない）
+#[derive(Default)]
+struct Request {
+    method: String,
+    path: String,
+    host: Option<String>,
+    origin: Option<String>,
+    token: Option<String>,
+}
+
+/// 受け付けるリクエストの条件
+struct Guard {
+    token: String,
+    /// 受け付ける Host ヘッダー
+    hosts: Vec<String>,
+}
+
+impl Guard {
+    fn new(address: SocketAddr, token: String) -> Self {
+        let port = address.port();
+        let mut hosts = vec![
+            format!("127.0.0.1:{}", port),
+            format!("localhost:{}", port),
+            format!("[::1]:{}", port),
+        ];
+        if !hosts.contains(&address.to_string()) {
+            hosts.push(address.to_string());
+        }
+        Self { token, hosts }
+    }
+
+    /// Host がこのサーバーを指しているか（別の名前で解決させたページからではない）
+    fn allows_host(&self, request: &Request) -> bool {
+        request.host.as_ref().is_some_and(|host| self.hosts.contains(host))
+    }
+
+    /// セッションを読んだりジャンプしたりしてよいか（トークンが一致し、別のオリジンのページからではない）
+    fn allows(&self, request: &Request) -> bool {
+        let same_origin = match (&request.origin, &request.host) {
+            (None, _) => true,
+            (Some(origin), Some(host)) => *origin == format!("http://{}", host),
+            (Some(_), None) => false,
+        };
+        self.allows_host(request) && same_origin && request.token.as_deref() == Some(self.token.as_str())
+    }
+}
+
+fn read_request(stream: &TcpStream) -> Result<Request> {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let mut parts = line.split_whitespace();
+    let mut request = Request {
+        method: parts.next().unwrap_or_default().to_string(),
+        path: parts.next().unwrap_or_default().to_string(),
+        ..Request::default()
+    };
+    // EventSource はヘッダーを付けられないのでクエリでも受け取る
+    request.token = request
+        .path
+        .split_once('?')
+        .and_then(|(_, query)| query.split('&').find_map(|pair| pair.strip_prefix("token=")))
+        .map(str::to_string);
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+        let Some((name, value)) = header.split_once(':') else { continue };
+        let value = Some(value.trim().to_string());
+        match name.trim().to_ascii_lowercase().as_str() {
+            "host" => request.host = value,
+            "origin" => request.origin = value,
+            "x-claude-watch-token" => request.token = value,
+            _ => {}
+        }
+    }
+    Ok(request)
+}
+
+/// アクセス用のトークン（128ビット、OSの乱数で初期化される RandomState から作る）
+fn generate_token() -> String {
+    (0..2)
+        .map(|_| format!("{:016x}", RandomState::new().build_hasher().finish()))
+        .collect()
+}
+
+/// セッションのペインにジャンプする（リモートならそのホストで）
+fn jump(shared: &Mutex<Shared>, remotes: &[RemoteConfig], session_id: &str) -> Result<()> {
+    let session = find_session_by_id(&shared.lock().unwrap_or_else(|e| e.into_inner()).sessions, session_id)
+        .cloned()
+        .ok_or_else(|| anyhow!("セッションID {} が見つかりません", session_id))?;
+    match session.host {
+        Some(_) => jump_to_remote_pane(remotes, &session),
+        None => jump_to_pane(&session.pane_id),
+    }
+}
+
+fn handle_client(
+    mut stream: TcpStream,
+    shared: &Mutex<Shared>,
+    web: bool,
+    guard: &Guard,
+    remotes: &[RemoteConfig],
+) -> Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(10)))?;
+    let request = read_request(&stream)?;
+    // クエリは無視する
+    let path = request.path.split('?').next().unwrap_or_default();
+    tracing::debug!(method = %request.method, path, "HTTPリクエスト");
+
+    // ダッシュボードのHTMLはセッションの情報を含まないのでトークンなしで返す（Hostだけ確かめる）
+    let public = web && request.method == "GET" && path == "/";
+    let allowed = if public { guard.allows_host(&request) } else { guard.allows(&request) };
+    if !allowed {
+        tracing::debug!(host = ?request.host, origin = ?request.origin, "トークンかHostかオリジンが違うリクエストを拒否しました");
+        return respond(&mut stream, "403 Forbidden", "text/plain; charset=utf-8", "forbidden\n");
+    }
+
+    match (request.method.as_str(), path) {
+        ("GET", "/") if web => respond(&mut stream, "200 OK", "text/html; charset=utf-8", DASHBOARD),
+        ("GET", "/sessions") => {
+            let body = serde_json::to_string(&shared.lock().unwrap_or_else(|e| e.into_inner()).snapshot)?;
+            respond(&mut stream, "200 OK", "application/json", &body)
+        }
+        ("POST", path)
+            if web && let Some(session_id) = path.strip_prefix("/sessions/").and_then(|p| p.strip_suffix("/jump")) =>
+        {
+            match jump(shared, remotes, session_id) {
+                Ok(()) => respond(&mut stream, "200 OK", "application/json", "true"),
+                Err(e) => {
+                    let message = format!("{:#}\n", e);
+                    respond(&mut stream, "500 Internal Server Error", "text/plain; charset=utf-8", &message)
+                }
+            }
+        }
+        ("GET", "/events") => {
+            // 受け取らないクライアントで監視が止まらないようにする
+            stream.set_write_timeout(Some(Duration::from_secs(5)))?;
+            stream.write_all(
+                b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\n\
+                  Connection: keep-alive\r\n\r\nretry: 3000\n\n",
+            )?;
+            shared.lock().unwrap_or_else(|e| e.into_inner()).subscribers.push(stream);
+            Ok(())
+        }
+        ("GET" | "HEAD", _) => respond(&mut stream, "404 Not Found", "text/plain; charset=utf-8", "not found\n"),
+        _ => respond(&mut stream, "405 Method Not Allowed", "text/plain; charset=utf-8", "method not allowed\n"),
+    }
+}
+
+/// to_json に表示用の値を加える（独自ステータスもダッシュボードで同じように表示できるように）
+fn session_to_json(session: &Session) -> Value {
+    let mut value = session.to_json();
+    value["icon"] = session.status_icon().into();
+    value["label"] = session.status_label().into();
+    value["waiting_reason_label"] = session.waiting_reason.as_ref().map(|r| r.label()).into();
+    value
+}
+
+/// 最新の一覧を反映し、ステータス変化をSSEで送る
+fn publish(shared: &Mutex<Shared>, sessions: &[Session], tracker: &mut StatusTracker) {
+    let mut shared = shared.lock().unwrap_or_else(|e| e.into_inner());
+    shared.snapshot = sessions.iter().map(session_to_json).collect();
+    shared.sessions = sessions.to_vec();
+    for transition in tracker.update(sessions) {
+        let event = StoredTransition::from_transition(&transition);
+        let data = event.to_json();
+        shared.next_id += 1;
+        let message = format!(
+            "id: {}\nevent: {}\ndata: {}\n\n",
+            shared.next_id,
+            data["type"].as_str().unwrap_or("status_change"),
+            data
+        );
+        shared.broadcast(&message);
+    }
+}
+
+/// HTTPで待ち受け、セッションを監視し続ける（Ctrl-Cで終了）
+pub fn run_server(config: &Config, listen: &str, web: bool) -> Result<()> {
+    let listener = TcpListener::bind(listen).with_context(|| format!("{} で待ち受けできません", listen))?;
+    let address = listener.local_addr()?;
+    let token = config.serve.token.clone().unwrap_or_else(generate_token);
+    if web {
+        eprintln!("{}claude-watch serve: http://{}/?token={}（ダッシュボード）", glyph("🌐 ", ""), address, token);
+    } else {
+        eprintln!("{}claude-watch serve: http://{}（/sessions, /events、トークン: {}）", glyph("🌐 ", ""), address, token);
+    }
+    let guard = Arc::new(Guard::new(address, token));
+
+    let shared = Arc::new(Mutex::new(Shared::default()));
+    {
+        let shared = Arc::clone(&shared);
+        let remotes = config.remote.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(stream) = stream else { continue };
+                let shared = Arc::clone(&shared);
+                let remotes = remotes.clone();
+                let guard = Arc::clone(&guard);
+                thread::spawn(move || {
+                    if let Err(e) = handle_client(stream, &shared, web, &guard, &remotes) {
+                        tracing::debug!("HTTPの接続を終了: {:#}", e);
+                    }
+                });
+            }
+        });
+    }
+
+    let remotes = RemotePoller::start(&config.remote);
+    let watcher = SessionWatcher::start()
+        .inspect_err(|e| eprintln!("{}ファイルの監視を開始できません: {:#}", glyph("⚠ ", "! "), e))
+        .ok();
+    let mut tracker = StatusTracker::new();
+    let interval = Duration::from_secs(2);
+    let mut last_keepalive = Instant::now();
+    loop {
+        match load_active_sessions() {
+            Ok(sessions) => publish(&shared, &remotes.merge(sessions), &mut tracker),
+            Err(e) => eprintln!("{}セッションの取得に失敗: {}", glyph("⚠ ", "! "), e),
+        }
+        if last_keepalive.elapsed() >= KEEPALIVE {
+            shared.lock().unwrap_or_else(|e| e.into_inner()).broadcast(": keepalive\n\n");
+            last_keepalive = Instant::now();
+        }
+        match watcher {
+            Some(ref watcher) => watcher.wait(interval),
+            None => thread::sleep(interval),
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use anyhow::{anyhow, Context, Result};
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+use crate::config::{env_dir, home_dir};
+use crate::display::glyph;
+
+/// systemdのユニット名
+const UNIT_NAME: &str = "claude-watch.service";
+/// launchdのラベル
+const LAUNCHD_LABEL: &str = "com.github.aya-215.claude-watch";
+
+/// ~/.config/systemd/user/claude-watch.service
+fn systemd_unit_path() -> Result<PathBuf> {
+    let config_dir = match env_dir("XDG_CONFIG_HOME") {
+        Some(dir) => dir,
+        None => home_dir()?.join(".config"),
+    };
+    Ok(config_dir.join("systemd/user").join(UNIT_NAME))
+}
+
+/// ~/Library/LaunchAgents/com.github.aya-215.claude-watch.plist
+fn launchd_plist_path() -> Result<PathBuf> {
+    Ok(home_dir()?
+        .join("Library/LaunchAgents")
+        .join(format!("{}.plist", LAUNCHD_LABEL)))
+}
+
+/// コマンドを実行し、失敗したら標準エラー出力を含めてエラーにする
+fn run(program: &str, args: &[&str]) -> Result<()> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .with_context(|| format!("{}の実行に失敗", program))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!("{} {} が失敗しました: {}", program, args.join(" "), stderr.trim()));
+    }
+    Ok(())
+}
+
+/// Windowsではサービスの登録に対応していない
+fn ensure_supported() -> Result<()> {
+    if cfg!(windows) {
+        return Err(anyhow!(
+            "Windowsではデーモンの登録に対応していません（タスクスケジューラでログオン時に claude-watch daemon を起動してください）"
+        ));
+    }
+    Ok(())
+}
+
+fn systemd_unit(exe: &str, interval: u64, path_env: &str) -> String {
+    format!(
+        "[Unit]\n\
+         Description=claude-watch daemon (Claude Codeのセッション監視)\n\
+         \n\
+         [Service]\n\
+         ExecStart=\"{}\" daemon --interval {}\n\
+         Environment=\"PATH={}\"\n\
+         Restart=on-failure\n\
+         RestartSec=5\n\
+         \n\
+         [Install]\n\
+         WantedBy=default.target\n",
+        exe, interval, path_env
+    )
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+fn launchd_plist(exe: &str, interval: u64, path_env: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{}</string>
+    <key>ProgramArguments</key>
+    <array>
+        <string>{}</string>
+        <string>daemon</string>
+        <string>--interval</string>
+        <string>{}</string>
+    </array>
+    <key>EnvironmentVariables</key>
+    <dict>
+        <key>PATH</key>
+        <string>{}</string>
+    </dict>
+    <key>RunAtLoad</key>
+    <true/>
+    <key>KeepAlive</key>
+    <true/>
+</dict>
+</plist>
+"#,
+        LAUNCHD_LABEL,
+        xml_escape(exe),
+        interval,
+        xml_escape(path_env)
+    )
+}
+
+/// ログイン時にデーモンを起動するよう登録して、すぐに起動する
+/// （Linux: systemdのユーザーユニット、macOS: launchdのLaunchAgent）
+pub fn install_service(interval: u64) -> Result<()> {
+    ensure_supported()?;
+    let exe = std::env::current_exe().context("実行ファイルのパスを取得できません")?;
+    let exe = exe.to_string_lossy();
+    // ログインシェルのPATHを引き継ぐ（wezterm.exe などを見つけられるように）
+    let path_env = std::env::var("PATH").unwrap_or_default();
+
+    let (path, content) = if cfg!(target_os = "macos") {
+        (launchd_plist_path()?, launchd_plist(&exe, interval, &path_env))
+    } else {
+        (systemd_unit_path()?, systemd_unit(&exe, interval, &path_env))
+    };
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("ディレクトリ作成エラー: {:?}", dir))?;
+    }
+    fs::write(&path, content).with_context(|| format!("書き込みエラー: {:?}", path))?;
+    println!("{}{} を書き込みました", glyph("📝 ", ""), path.display());
+
+    if cfg!(target_os = "macos") {
+        let path = path.to_string_lossy();
+        // 再インストール時は古い設定を外してから読み込む
+        let _ = run("launchctl", &["unload", &path]);
+        run("launchctl", &["load", "-w", &path])?;
+    } else {
+        run("systemctl", &["--user", "daemon-reload"])?;
+        run("systemctl", &["--user", "enable", UNIT_NAME])?;
+        // 再インストール時も新しい内容で起動し直す
+        run("systemctl", &["--user", "restart", UNIT_NAME])?;
+    }
+    println!("{}デーモンを登録して起動しました（次回以降はログイン時に起動）", glyph("✅ ", ""));
+    Ok(())
+}
+
+/// 登録したデーモンの状態を表示する
+pub fn service_status() -> Result<()> {
+    ensure_supported()?;
+    let (path, mut command) = if cfg!(target_os = "macos") {
+        let mut command = Command::new("launchctl");
+        command.args(["list", LAUNCHD_LABEL]);
+        (launchd_plist_path()?, command)
+    } else {
+        let mut command = Command::new("systemctl");
+        command.args(["--user", "status", "--no-pager", UNIT_NAME]);
+        (systemd_unit_path()?, command)
+    };
+    if !path.exists() {
+        println!("デーモンは登録されていません（claude-watch daemon install で登録）");
+        return Ok(());
+    }
+    println!("{}{}", glyph("📄 ", ""), path.display());
+    // 停止中は終了コードが0以外になるが、出力はそのまま見せる
+    command.status().context("状態の取得に失敗")?;
+    Ok(())
+}
+
+/// デーモンを停止して登録を解除する
+pub fn uninstall_service() -> Result<()> {
+    ensure_supported()?;
+    let path = if cfg!(target_os = "macos") {
+        launchd_plist_path()?
+    } else {
+        systemd_unit_path()?
+    };
+    if !path.exists() {
+        println!("デーモンは登録されていません");
+        return Ok(());
+    }
+
+    if cfg!(target_os = "macos") {
+        run("launchctl", &["unload", "-w", &path.to_string_lossy()])?;
+    } else {
+        run("systemctl", &["--user", "disable", "--now", UNIT_NAME])?;
+    }
+    fs::remove_file(&path).with_context(|| format!("削除エラー: {:?}", path))?;
+    if !cfg!(target_os = "macos") {
+        run("systemctl", &["--user", "daemon-reload"])?;
+    }
+    println!("{}{} を削除し、デーモンを停止しました", glyph("🗑 ", ""), path.display());
+    Ok(())
+}
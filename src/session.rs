@@ -1,19 +1,45 @@
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
+use ratatui::style::Color;
 use serde::Deserialize;
-use std::collections::{HashMap, HashSet};
+use serde_json::value::RawValue;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::backend::list_panes_or_degrade;
+use crate::config::{env_dir, home_dir};
+use crate::display::glyph;
+use crate::git::GitStatus;
+use crate::reason::WaitingReason;
+use crate::status::SessionStatus;
+use crate::theme::theme;
+use crate::process::{find_session_process, is_session_alive, list_claude_processes};
+use crate::projects::is_ignored;
+use crate::transcript::{
+    context_window, load_token_usage, model_family, pending_tool, last_assistant_text, transcript_path, transcript_state, Subagent, TokenUsage, TranscriptState,
+};
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct Session {
     pub session_id: String,
     pub pane_id: String,
     pub cwd: String,
-    pub status: String,
+    pub status: SessionStatus,
     pub notification_message: Option<String>,
-    #[allow(dead_code)]
     pub notification_type: Option<String>,
+    /// 実行中のツール（"Bash: cargo test"、PreToolUse フックか会話ログの末尾から）
+    pub current_tool: Option<String>,
+    /// アシスタントの最後の発言（実行中のときだけ会話ログの末尾から取得）
+    #[serde(skip)]
+    pub last_message: Option<String>,
+    /// 承認待ちの理由（notification_message から判定）
+    #[serde(skip)]
+    pub waiting_reason: Option<WaitingReason>,
     pub updated: u64,
     #[serde(skip)]
     pub summary: Option<String>,
@@ -23,10 +49,203 @@ pub struct Session {
     pub message_count: Option<u32>,
     #[serde(skip)]
     pub git_branch: Option<String>,
+    /// 作業ツリーの変更・upstreamとの差（TUIと一覧表示でのみ取得）
+    #[serde(skip)]
+    pub git_status: Option<GitStatus>,
     #[serde(skip)]
     pub modified: Option<String>,
     #[serde(skip)]
     pub memory_usage_kb: Option<u64>,
+    #[serde(skip)]
+    pub path: PathBuf,
+    #[serde(skip)]
+    pub token_usage: Option<TokenUsage>,
+    /// Claudeのモデル名（トランスクリプトの最新の応答、なければセッションファイルの値）
+    pub model: Option<String>,
+    /// 次の応答に渡る文脈のトークン数（トランスクリプトの最後の応答から推定）
+    #[serde(skip)]
+    pub context_tokens: Option<u64>,
+    /// 実行中のサブエージェント（Taskツール）
+    #[serde(skip)]
+    pub subagents: Vec<Subagent>,
+    /// 同じペインを指していたため一覧から外した古いセッションファイル（新しい順）
+    #[serde(skip)]
+    pub pane_conflicts: Vec<PaneConflict>,
+    /// リモートのセッションならそのホスト名（[[remote]] の name）
+    #[serde(skip)]
+    pub host: Option<String>,
+    /// 実行中のまま長く更新がない（Stopフックを取りこぼした疑い）
+    #[serde(skip)]
+    pub idle: bool,
+    /// ペインがなくなった（`list --orphaned` やTUIの表示切り替えで出す）
+    #[serde(skip)]
+    pub orphaned: bool,
+    /// ステータスをどこから判断したか
+    #[serde(skip)]
+    pub status_source: StatusSource,
+    /// 承認待ちの通知を一時的に止めている（状態ファイルのスヌーズ）
+    #[serde(skip)]
+    pub snoozed: bool,
+}
+
+/// 同じpane_idを書いていた別のセッションファイル
+#[derive(Debug, Clone, Deserialize)]
+pub struct PaneConflict {
+    pub session_id: String,
+    pub cwd: String,
+    pub status: SessionStatus,
+    pub updated: u64,
+    pub path: PathBuf,
+}
+
+impl PaneConflict {
+    fn from_session(session: Session) -> PaneConflict {
+        PaneConflict {
+            session_id: session.session_id,
+            cwd: session.cwd,
+            status: session.status,
+            updated: session.updated,
+            path: session.path,
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "session_id": self.session_id,
+            "cwd": self.cwd,
+            "status": self.status.as_str(),
+            "updated": self.updated,
+            "path": self.path,
+        })
+    }
+}
+
+/// ステータスの出どころ
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StatusSource {
+    /// フックが書き込んだセッションファイル
+    #[default]
+    Hook,
+    /// 会話ログの末尾から推定して補正した
+    Transcript,
+    /// Claude Codeのプロセスが終了していた
+    Process,
+}
+
+impl StatusSource {
+    pub fn parse(source: &str) -> StatusSource {
+        match source {
+            "transcript" => StatusSource::Transcript,
+            "process" => StatusSource::Process,
+            _ => StatusSource::Hook,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            StatusSource::Hook => "hook",
+            StatusSource::Transcript => "transcript",
+            StatusSource::Process => "process",
+        }
+    }
+
+    /// 詳細表示での説明（フックならNone）
+    pub fn label(self) -> Option<&'static str> {
+        match self {
+            StatusSource::Hook => None,
+            StatusSource::Transcript => Some("会話ログから推定"),
+            StatusSource::Process => Some("プロセスの終了から判定"),
+        }
+    }
+}
+
+impl Session {
+    /// 承認待ちなら通知メッセージから理由を判定する
+    pub fn parse_waiting_reason(&self) -> Option<WaitingReason> {
+        if self.status != SessionStatus::Waiting {
+            return None;
+        }
+        let message = self.notification_message.as_deref()?;
+        Some(WaitingReason::parse(message, self.notification_type.as_deref()))
+    }
+
+    /// 表示用のアイコン（アイドルの疑いがあれば💤）
+    pub fn status_icon(&self) -> &'static str {
+        if self.orphaned {
+            return glyph("👻", "[O]");
+        }
+        if self.idle {
+            return glyph("💤", "[I]");
+        }
+        self.status.icon()
+    }
+
+    pub fn status_label(&self) -> &str {
+        if self.orphaned {
+            return "孤立";
+        }
+        if self.idle {
+            return "idle?";
+        }
+        self.status.label()
+    }
+
+    /// コンテキストウィンドウの使用率（%）
+    pub fn context_percent(&self) -> Option<u64> {
+        let tokens = self.context_tokens?;
+        Some((tokens * 100 / context_window(self.model.as_deref())).min(100))
+    }
+
+    /// 一覧に出すモデルの系統（opus / sonnet / haiku）
+    pub fn model_family(&self) -> Option<&str> {
+        self.model.as_deref().map(model_family)
+    }
+
+    pub fn status_color(&self) -> Color {
+        if self.orphaned || self.idle {
+            return theme().muted;
+        }
+        self.status.color()
+    }
+
+    /// エクスポート・機械可読出力用のJSON表現
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "session_id": self.session_id,
+            "host": self.host,
+            "pane_id": self.pane_id,
+            "cwd": self.cwd,
+            "status": self.status.as_str(),
+            "notification_message": self.notification_message,
+            "notification_type": self.notification_type,
+            "current_tool": self.current_tool,
+            "last_message": self.last_message,
+            "waiting_reason": self.waiting_reason.as_ref().map(WaitingReason::name),
+            "waiting_tool": self.waiting_reason.as_ref().and_then(WaitingReason::tool),
+            "updated": self.updated,
+            "summary": self.summary,
+            "first_prompt": self.first_prompt,
+            "message_count": self.message_count,
+            "git_branch": self.git_branch,
+            "git_dirty": self.git_status.map(|g| g.dirty),
+            "git_ahead": self.git_status.map(|g| g.ahead),
+            "git_behind": self.git_status.map(|g| g.behind),
+            "modified": self.modified,
+            "memory_usage_kb": self.memory_usage_kb,
+            "input_tokens": self.token_usage.map(|u| u.total_input()),
+            "output_tokens": self.token_usage.map(|u| u.output_tokens),
+            "cost_usd": self.token_usage.filter(|u| !u.unpriced).map(|u| u.cost_usd),
+            "model": self.model,
+            "context_tokens": self.context_tokens,
+            "context_percent": self.context_percent(),
+            "subagents": self.subagents.iter().map(Subagent::to_json).collect::<Vec<_>>(),
+            "pane_conflicts": self.pane_conflicts.iter().map(PaneConflict::to_json).collect::<Vec<_>>(),
+            "orphaned": self.orphaned,
+            "idle": self.idle,
+            "status_source": self.status_source.as_str(),
+            "snoozed": self.snoozed,
+        })
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -34,6 +253,52 @@ struct SessionsIndex {
     entries: Vec<SessionIndexEntry>,
 }
 
+/// 文字列を確保せずに読んだsessions-index.json（必要なIDのエントリだけ後で文字列にする）
+#[derive(Deserialize)]
+struct SessionsIndexRef<'a> {
+    #[serde(borrow)]
+    entries: Vec<SessionIndexEntryRef<'a>>,
+}
+
+/// 文字列のフィールドをJSONのまま借用したエントリ
+#[derive(Deserialize)]
+struct SessionIndexEntryRef<'a> {
+    #[serde(rename = "sessionId", borrow)]
+    session_id: Cow<'a, str>,
+    #[serde(borrow)]
+    summary: Option<&'a RawValue>,
+    #[serde(rename = "firstPrompt", borrow)]
+    first_prompt: Option<&'a RawValue>,
+    #[serde(rename = "messageCount")]
+    message_count: Option<u32>,
+    #[serde(rename = "gitBranch", borrow)]
+    git_branch: Option<&'a RawValue>,
+    #[serde(borrow)]
+    created: Option<&'a RawValue>,
+    #[serde(borrow)]
+    modified: Option<&'a RawValue>,
+    #[serde(rename = "projectPath", borrow)]
+    project_path: Option<&'a RawValue>,
+}
+
+impl SessionIndexEntryRef<'_> {
+    fn into_owned(self) -> Result<SessionIndexEntry> {
+        fn text(raw: Option<&RawValue>) -> Result<Option<String>> {
+            raw.map_or(Ok(None), |raw| Ok(serde_json::from_str(raw.get())?))
+        }
+        Ok(SessionIndexEntry {
+            session_id: self.session_id.into_owned(),
+            summary: text(self.summary)?,
+            first_prompt: text(self.first_prompt)?,
+            message_count: self.message_count,
+            git_branch: text(self.git_branch)?,
+            created: text(self.created)?,
+            modified: text(self.modified)?,
+            project_path: text(self.project_path)?,
+        })
+    }
+}
+
 #[derive(Debug, Deserialize)]
 struct SessionIndexEntry {
     #[serde(rename = "sessionId")]
@@ -45,215 +310,945 @@ struct SessionIndexEntry {
     message_count: Option<u32>,
     #[serde(rename = "gitBranch")]
     git_branch: Option<String>,
+    created: Option<String>,
     modified: Option<String>,
-    #[allow(dead_code)]
     #[serde(rename = "projectPath")]
     project_path: Option<String>,
 }
 
-fn get_sessions_dir() -> Result<PathBuf> {
-    let home = std::env::var("HOME").context("HOME環境変数が見つかりません")?;
-    Ok(PathBuf::from(home).join(".claude/sessions"))
+/// sessions-index.jsonに記録された過去のセッション
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    pub session_id: String,
+    pub project_path: String,
+    pub summary: Option<String>,
+    pub first_prompt: Option<String>,
+    pub message_count: Option<u32>,
+    pub git_branch: Option<String>,
+    pub created: Option<String>,
+    pub modified: Option<String>,
+}
+
+impl HistoryEntry {
+    /// 開始から最終更新までの秒数
+    pub fn duration_secs(&self) -> Option<u64> {
+        let created = chrono::DateTime::parse_from_rfc3339(self.created.as_deref()?).ok()?;
+        let modified = chrono::DateTime::parse_from_rfc3339(self.modified.as_deref()?).ok()?;
+        u64::try_from((modified - created).num_seconds()).ok()
+    }
+}
+
+/// プロジェクトで保持する最近のセッションの数
+const RECENT_PER_PROJECT: usize = 10;
+
+/// ~/.claude/projects のプロジェクトごとの過去のセッション
+#[derive(Debug, Clone)]
+pub struct ProjectSummary {
+    pub path: String,
+    /// 記録されたセッションの数
+    pub session_count: usize,
+    /// 最近のセッション（新しい順に最大 RECENT_PER_PROJECT 件）
+    pub recent: Vec<HistoryEntry>,
+}
+
+impl ProjectSummary {
+    pub fn latest(&self) -> Option<&HistoryEntry> {
+        self.recent.first()
+    }
+}
+
+static SESSIONS_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// セッションファイルの置き場所を変える（`--sessions-dir` / 設定ファイルの sessions_dir）
+pub fn init_sessions_dir(dir: &Path) -> Result<()> {
+    let dir = match dir.strip_prefix("~") {
+        Ok(rest) => home_dir()?.join(rest),
+        Err(_) => dir.to_path_buf(),
+    };
+    let _ = SESSIONS_DIR.set(dir);
+    Ok(())
+}
+
+/// `--sessions-dir` / sessions_dir で指定された置き場所
+pub fn sessions_dir_override() -> Option<&'static Path> {
+    SESSIONS_DIR.get().map(PathBuf::as_path)
+}
+
+/// Claude Codeの設定ディレクトリ
+///
+/// CLAUDE_CONFIG_DIR があればそれ、なければ ~/.claude（Windowsでは %USERPROFILE%\.claude）。
+/// ~/.claude が無く $XDG_CONFIG_HOME/claude（~/.config/claude）があればそちらを使う。
+pub fn get_claude_dir() -> Result<PathBuf> {
+    if let Some(dir) = env_dir("CLAUDE_CONFIG_DIR") {
+        return Ok(dir);
+    }
+    let home = home_dir()?;
+    let default_dir = home.join(".claude");
+    if default_dir.exists() {
+        return Ok(default_dir);
+    }
+    let xdg_dir = match env_dir("XDG_CONFIG_HOME") {
+        Some(dir) => dir.join("claude"),
+        None => home.join(".config/claude"),
+    };
+    Ok(if xdg_dir.exists() { xdg_dir } else { default_dir })
+}
+
+pub fn get_sessions_dir() -> Result<PathBuf> {
+    if let Some(dir) = SESSIONS_DIR.get() {
+        return Ok(dir.clone());
+    }
+    Ok(get_claude_dir()?.join("sessions"))
+}
+
+/// Claude Codeの設定ファイル（フックの登録先）
+pub fn get_claude_settings_path() -> Result<PathBuf> {
+    Ok(get_claude_dir()?.join("settings.json"))
+}
+
+pub fn load_session_file(path: PathBuf) -> Result<Session> {
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("ファイル読み込みエラー: {:?}", path))?;
+    let mut session: Session = serde_json::from_str(&content)
+        .with_context(|| format!("JSONパースエラー: {:?}", path))?;
+    session.path = path;
+    session.waiting_reason = session.parse_waiting_reason();
+    Ok(session)
+}
+
+/// 読み込めなかったセッションファイル
+#[derive(Debug, Clone)]
+pub struct LoadError {
+    pub path: PathBuf,
+    pub message: String,
+}
+
+static STRICT: AtomicBool = AtomicBool::new(false);
+static CANONICALIZE: AtomicBool = AtomicBool::new(true);
+static IDLE_AFTER: AtomicU64 = AtomicU64::new(0);
+static RECONCILE: AtomicBool = AtomicBool::new(true);
+static LOAD_ERRORS: Mutex<Vec<LoadError>> = Mutex::new(Vec::new());
+
+/// 壊れたセッションファイルがあればエラーにする（`--strict`）
+pub fn set_strict_loading(strict: bool) {
+    STRICT.store(strict, Ordering::Relaxed);
+}
+
+/// cwdのシンボリックリンクを解決して照合する（設定ファイルの canonicalize_paths）
+pub fn set_canonicalize_paths(enabled: bool) {
+    CANONICALIZE.store(enabled, Ordering::Relaxed);
+}
+
+/// 会話ログの末尾からステータスを補正する（設定ファイルの reconcile_status）
+pub fn set_reconcile_status(enabled: bool) {
+    RECONCILE.store(enabled, Ordering::Relaxed);
+}
+
+/// 会話ログの最後の書き込みからこの秒数たつまでは、応答の途中とみなして完了扱いにしない
+const RECONCILE_SETTLE_SECS: u64 = 10;
+
+/// フックを取りこぼして古くなったステータスを会話ログの末尾から補正する
+///
+/// 最後のフックより後に会話ログが書き込まれているときだけ見る。
+/// - 完了・承認待ちの後にユーザーの発言やツールの結果が続いていれば実行中
+/// - 承認待ちのまま中断されていれば完了
+/// - 実行中のままアシスタントの発言で終わり、しばらく書き込みがなければ完了
+fn reconcile_with_transcript(sessions: &mut [Session]) {
+    if !RECONCILE.load(Ordering::Relaxed) {
+        return;
+    }
+    let now = SystemTime::now();
+    for session in sessions.iter_mut().filter(|s| s.status_source == StatusSource::Hook) {
+        let Some((state, modified)) = transcript_path(&session.cwd, &session.session_id)
+            .ok()
+            .and_then(|path| transcript_state(&path))
+        else {
+            continue;
+        };
+        let modified_secs = modified.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+        if modified_secs <= session.updated {
+            continue;
+        }
+        let settled = now.duration_since(modified).is_ok_and(|d| d.as_secs() >= RECONCILE_SETTLE_SECS);
+        let corrected = match (&session.status, state) {
+            (SessionStatus::Stopped | SessionStatus::Waiting, TranscriptState::Working) => SessionStatus::Active,
+            (SessionStatus::Stopped, TranscriptState::ToolPending) => SessionStatus::Active,
+            (SessionStatus::Waiting, TranscriptState::Finished) => SessionStatus::Stopped,
+            (SessionStatus::Active, TranscriptState::Finished) if settled => SessionStatus::Stopped,
+            _ => continue,
+        };
+        tracing::debug!(
+            session_id = %session.session_id,
+            from = %session.status,
+            to = %corrected,
+            ?state,
+            "会話ログからステータスを補正"
+        );
+        session.status = corrected;
+        session.status_source = StatusSource::Transcript;
+        session.updated = modified_secs;
+        if session.status != SessionStatus::Waiting {
+            session.notification_message = None;
+            session.waiting_reason = None;
+        }
+    }
 }
 
+/// 実行中のセッションのツールとアシスタントの最後の発言を決める
+///
+/// 最後のフックより後に会話ログが書き込まれていれば、会話ログの末尾で結果を待っているツールにする
+/// （ツールが終わって応答を作っている間は無し）。
+fn detect_current_tool(sessions: &mut [Session]) {
+    for session in sessions.iter_mut() {
+        if session.status != SessionStatus::Active {
+            session.current_tool = None;
+            continue;
+        }
+        let Ok(path) = transcript_path(&session.cwd, &session.session_id) else {
+            continue;
+        };
+        session.last_message = last_assistant_text(&path);
+        let Some((tool, modified)) = pending_tool(&path) else {
+            continue;
+        };
+        let modified_secs = modified.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+        if modified_secs > session.updated || session.current_tool.is_none() {
+            session.current_tool = tool;
+        }
+    }
+}
+
+/// 実行中のまま更新のないセッションを「idle?」とみなすまでの秒数（0で無効、[timer] idle_after）
+pub fn set_idle_after(secs: u64) {
+    IDLE_AFTER.store(secs, Ordering::Relaxed);
+}
+
+/// 実行中のままセッションファイルも会話ログも idle_after 秒以上更新されていないセッションに印を付ける
+fn detect_idle(sessions: &mut [Session]) {
+    let idle_after = IDLE_AFTER.load(Ordering::Relaxed);
+    if idle_after == 0 {
+        return;
+    }
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    for session in sessions.iter_mut().filter(|s| s.status == SessionStatus::Active) {
+        if now.saturating_sub(session.updated) < idle_after {
+            continue;
+        }
+        // ツールの実行中などはフックが走らないので、会話ログの更新も見る
+        let transcript_modified = transcript_path(&session.cwd, &session.session_id)
+            .and_then(|path| Ok(fs::metadata(path)?.modified()?))
+            .ok()
+            .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |d| d.as_secs());
+        session.idle = now.saturating_sub(transcript_modified) >= idle_after;
+        if session.idle {
+            tracing::debug!(session_id = %session.session_id, "更新がないためアイドルの疑い");
+        }
+    }
+}
+
+/// セッションファイルのステータスを書き換える（Stopフックを取りこぼしたセッションを完了扱いにするなど）
+pub fn set_session_status(path: &Path, status: &SessionStatus) -> Result<()> {
+    let content = fs::read_to_string(path).with_context(|| format!("ファイル読み込みエラー: {:?}", path))?;
+    let mut session: serde_json::Value =
+        serde_json::from_str(&content).with_context(|| format!("JSONパースエラー: {:?}", path))?;
+    let object = session
+        .as_object_mut()
+        .ok_or_else(|| anyhow!("セッションファイルの形式が不正です: {:?}", path))?;
+    object.insert("status".to_string(), status.as_str().into());
+    object.insert("notification_message".to_string(), serde_json::Value::Null);
+    object.insert("notification_type".to_string(), serde_json::Value::Null);
+
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, session.to_string())
+        .with_context(|| format!("セッションファイルの書き込みに失敗: {:?}", tmp_path))?;
+    fs::rename(&tmp_path, path).with_context(|| format!("セッションファイルの書き込みに失敗: {:?}", path))?;
+    Ok(())
+}
+
+/// シンボリックリンクを解決したcwd（無効時や解決できないときはそのまま）
+///
+/// `~/work` → `/data/work` のように別の経路で開いたセッションを同じディレクトリとして扱う。
+pub fn canonical_cwd(cwd: &str) -> String {
+    if !CANONICALIZE.load(Ordering::Relaxed) {
+        return cwd.to_string();
+    }
+    fs::canonicalize(cwd)
+        .ok()
+        .and_then(|path| path.to_str().map(str::to_string))
+        .unwrap_or_else(|| cwd.to_string())
+}
+
+/// 直前の load_sessions で読み飛ばしたファイル
+pub fn last_load_errors() -> Vec<LoadError> {
+    LOAD_ERRORS.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// 全セッションを読み込む
+///
+/// 壊れたファイルは読み飛ばして last_load_errors() で参照できるようにする（`--strict` 時はエラー）。
 pub fn load_sessions() -> Result<Vec<Session>> {
+    let (sessions, errors) = load_sessions_lenient()?;
+
+    if STRICT.load(Ordering::Relaxed)
+        && let Some(error) = errors.first()
+    {
+        return Err(anyhow!("{}", error.message));
+    }
+    for error in &errors {
+        tracing::debug!(path = ?error.path, "セッションファイルを読み飛ばしました: {}", error.message);
+    }
+
+    *LOAD_ERRORS.lock().unwrap_or_else(|e| e.into_inner()) = errors;
+    Ok(sessions)
+}
+
+/// 全セッションを読み込み、読み込めたものと読み込めなかったファイルを分けて返す
+pub fn load_sessions_lenient() -> Result<(Vec<Session>, Vec<LoadError>)> {
     let sessions_dir = get_sessions_dir()?;
-    let mut sessions = Vec::new();
 
     if !sessions_dir.exists() {
-        return Ok(sessions);
+        tracing::debug!(dir = ?sessions_dir, "セッションディレクトリが存在しません");
+        return Ok((Vec::new(), Vec::new()));
     }
 
+    let mut paths = Vec::new();
     for entry in fs::read_dir(&sessions_dir)
         .context("セッションディレクトリの読み込みに失敗")?
     {
-        let entry = entry?;
-        let path = entry.path();
-
+        let path = entry?.path();
         if path.extension().and_then(|s| s.to_str()) == Some("json") {
-            let content = fs::read_to_string(&path)
-                .with_context(|| format!("ファイル読み込みエラー: {:?}", path))?;
-            let session: Session = serde_json::from_str(&content)
-                .with_context(|| format!("JSONパースエラー: {:?}", path))?;
-            sessions.push(session);
+            paths.push(path);
         }
     }
 
-    Ok(sessions)
-}
+    tracing::debug!(dir = ?sessions_dir, count = paths.len(), "セッションファイルを検出");
 
-/// /proc/[pid]/statusからメモリ使用量（VmRSS）を取得
-fn get_memory_from_proc(pid: u32) -> Option<u64> {
-    let status_path = format!("/proc/{}/status", pid);
-    let content = fs::read_to_string(&status_path).ok()?;
+    // ファイル数が多い場合はスレッドに分けて並列に読み込む
+    let workers = thread::available_parallelism().map_or(4, |n| n.get());
+    let chunk_size = paths.len().div_ceil(workers).max(16);
 
-    for line in content.lines() {
-        if line.starts_with("VmRSS:") {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() >= 2 {
-                return parts[1].parse().ok();
+    thread::scope(|scope| {
+        let handles: Vec<_> = paths
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|path| {
+                            load_session_file(path.clone()).map_err(|e| LoadError {
+                                path: path.clone(),
+                                message: format!("{:#}", e),
+                            })
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        let mut sessions = Vec::new();
+        let mut errors = Vec::new();
+        for handle in handles {
+            for result in handle.join().expect("セッション読み込みスレッドがパニック") {
+                match result {
+                    Ok(session) => sessions.push(session),
+                    Err(error) => errors.push(error),
+                }
             }
         }
-    }
-    None
+        errors.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok((sessions, errors))
+    })
 }
 
-/// 全ペインのpane_id → PIDマッピングを取得
-fn get_all_pane_pids() -> Result<HashMap<String, u32>> {
-    let wezterm = "/mnt/c/Program Files/WezTerm/wezterm.exe";
-    let output = Command::new(wezterm)
-        .args(["cli", "list", "--format", "json"])
-        .output()
-        .context("WezTermのペイン一覧取得に失敗")?;
+/// 全セッションを読み込み、アクティブなものだけをindex情報付きで返す
+///
+/// セッションファイルの読み込みとペイン一覧の取得は並行して行う。
+pub fn load_active_sessions() -> Result<Vec<Session>> {
+    let (sessions, panes) = thread::scope(|scope| {
+        let panes = scope.spawn(list_panes_or_degrade);
+        let sessions = load_sessions();
+        (sessions, panes.join().expect("ペイン一覧取得スレッドがパニック"))
+    });
 
-    if !output.status.success() {
-        return Err(anyhow::anyhow!("WezTerm cliコマンドが失敗しました"));
-    }
+    let active_pane_ids: Option<HashSet<String>> =
+        panes.as_ref().map(|panes| panes.keys().cloned().collect());
+    let mut sessions = sessions?;
+    sessions.retain(|s| {
+        let ignored = is_ignored(&s.cwd);
+        if ignored {
+            tracing::debug!(session_id = %s.session_id, cwd = %s.cwd, "ignore に一致するため除外");
+        }
+        !ignored
+    });
 
-    let json_str = String::from_utf8(output.stdout)
-        .context("WezTerm出力のUTF-8変換に失敗")?;
+    // Claude Codeのプロセスが終了しているセッションを検出する
+    let processes = list_claude_processes();
+    if let Some(ref processes) = processes
+        && active_pane_ids.is_none()
+    {
+        // ペインで絞り込めないときはプロセスの生存で代用する
+        sessions.retain(|s| {
+            // 分からないものは残す
+            let alive = is_session_alive(s, processes) != Some(false);
+            if !alive {
+                tracing::debug!(session_id = %s.session_id, "Claude Codeのプロセスがないため除外");
+            }
+            alive
+        });
+    }
 
-    let panes: Vec<serde_json::Value> = serde_json::from_str(&json_str)
-        .context("WezTerm JSON解析に失敗")?;
+    let mut sessions = filter_active_sessions(sessions, active_pane_ids.as_ref());
 
-    let mut map = HashMap::new();
-    for pane in panes {
-        if let (Some(pane_id), Some(pid)) = (
-            pane["pane_id"].as_u64(),
-            pane["foreground_process_id"].as_u64(),
-        ) {
-            map.insert(pane_id.to_string(), pid as u32);
+    // ペインが残っていてもプロセスが終了していれば完了扱いにする
+    if let Some(ref processes) = processes {
+        for session in sessions.iter_mut() {
+            if session.status != SessionStatus::Stopped && is_session_alive(session, processes) == Some(false) {
+                tracing::debug!(session_id = %session.session_id, "プロセスが終了しているため完了扱い");
+                session.status = SessionStatus::Stopped;
+                session.status_source = StatusSource::Process;
+                session.notification_message = None;
+                session.waiting_reason = None;
+            }
         }
     }
-    Ok(map)
-}
 
-fn get_active_pane_ids() -> Result<HashSet<String>> {
-    let wezterm = "/mnt/c/Program Files/WezTerm/wezterm.exe";
+    reconcile_with_transcript(&mut sessions);
+    detect_current_tool(&mut sessions);
+    detect_idle(&mut sessions);
 
-    let output = Command::new(wezterm)
-        .args(["cli", "list", "--format", "json"])
-        .output()
-        .context("WezTermのペイン一覧取得に失敗")?;
+    tracing::debug!(count = sessions.len(), "アクティブなセッション");
+    enrich_sessions_with_index(&mut sessions, &panes.unwrap_or_default());
 
-    if !output.status.success() {
-        return Err(anyhow::anyhow!("WezTerm cliコマンドが失敗しました"));
+    // ペインからPIDが取れなかったセッションはClaude Codeプロセスのメモリを使う
+    if let Some(ref processes) = processes {
+        for session in sessions.iter_mut().filter(|s| s.memory_usage_kb.is_none()) {
+            if let Some(process) = find_session_process(session, processes) {
+                session.memory_usage_kb = get_memory_from_proc(process.pid);
+            }
+        }
     }
 
-    let json_str = String::from_utf8(output.stdout)
-        .context("WezTerm出力のUTF-8変換に失敗")?;
+    Ok(sessions)
+}
 
-    let panes: Vec<serde_json::Value> = serde_json::from_str(&json_str)
-        .context("WezTerm JSON解析に失敗")?;
+/// これより前に更新された孤立したセッションは `clean` に任せて出さない
+const ORPHAN_MAX_AGE: u64 = 7 * 86400;
 
-    let pane_ids: HashSet<String> = panes
-        .iter()
-        .filter_map(|p| p["pane_id"].as_u64())
-        .map(|id| id.to_string())
-        .collect();
+/// ペインがなくなったセッション（ペインもプロセスも調べられなければ空）
+///
+/// load_active_sessions が黙って外すものを、最近更新されたものに限って新しい順に返す。
+pub fn load_orphaned_sessions() -> Result<Vec<Session>> {
+    let panes = list_panes_or_degrade();
+    let processes = list_claude_processes();
+    if panes.is_none() && processes.is_none() {
+        return Ok(Vec::new());
+    }
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let mut sessions = load_sessions()?;
+    sessions.retain(|s| {
+        let gone = match (&panes, &processes) {
+            (Some(panes), _) => !panes.contains_key(&s.pane_id),
+            (None, Some(processes)) => is_session_alive(s, processes) == Some(false),
+            (None, None) => false,
+        };
+        gone && now.saturating_sub(s.updated) <= ORPHAN_MAX_AGE && !is_ignored(&s.cwd)
+    });
+
+    // 同じペインのものは新しい方だけにする
+    let mut sessions = filter_active_sessions(sessions, None);
+    for session in sessions.iter_mut() {
+        session.orphaned = true;
+    }
+    enrich_sessions_with_index(&mut sessions, &HashMap::new());
+    Ok(sessions)
+}
+
+/// /proc/[pid]/statusからメモリ使用量（VmRSS）を取得
+fn get_memory_from_proc(pid: u32) -> Option<u64> {
+    let status_path = format!("/proc/{}/status", pid);
+    let content = fs::read_to_string(&status_path).ok()?;
 
-    Ok(pane_ids)
+    for line in content.lines() {
+        if line.starts_with("VmRSS:") {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() >= 2 {
+                return parts[1].parse().ok();
+            }
+        }
+    }
+    None
 }
 
-pub fn filter_active_sessions(sessions: Vec<Session>) -> Result<Vec<Session>> {
-    let active_pane_ids = get_active_pane_ids()?;
+/// 生存しているpane_idの一覧（バックエンドがペインを列挙できなければNone）
+pub fn get_active_pane_ids() -> Option<HashSet<String>> {
+    list_panes_or_degrade().map(|panes| panes.into_keys().collect())
+}
 
+/// 生存しているペインのセッションだけを残す（active_pane_idsがNoneならペインでは絞り込まない）
+///
+/// 同じペインに複数のセッションファイルがあれば新しい方だけを残し、外したものを pane_conflicts に記録する。
+pub fn filter_active_sessions(
+    sessions: Vec<Session>,
+    active_pane_ids: Option<&HashSet<String>>,
+) -> Vec<Session> {
     // pane_idごとに最新のセッションだけを保持
     let mut pane_to_session: HashMap<String, Session> = HashMap::new();
+    let mut displaced: HashMap<String, Vec<Session>> = HashMap::new();
 
     for session in sessions {
-        if !active_pane_ids.contains(&session.pane_id) {
+        if active_pane_ids.is_some_and(|ids| !ids.contains(&session.pane_id)) {
+            tracing::debug!(
+                session_id = %session.session_id,
+                pane_id = %session.pane_id,
+                "ペインが存在しないため除外"
+            );
             continue;
         }
 
         // 既存のセッションより新しければ更新
         if let Some(existing) = pane_to_session.get(&session.pane_id) {
-            if session.updated > existing.updated {
-                pane_to_session.insert(session.pane_id.clone(), session);
-            }
+            tracing::debug!(
+                pane_id = %session.pane_id,
+                a = %session.session_id,
+                b = %existing.session_id,
+                "同じペインに複数のセッションがあるため新しい方だけを残す"
+            );
+            let pane_id = session.pane_id.clone();
+            let stale = if session.updated > existing.updated {
+                pane_to_session.insert(pane_id.clone(), session)
+            } else {
+                Some(session)
+            };
+            displaced.entry(pane_id).or_default().extend(stale);
         } else {
             pane_to_session.insert(session.pane_id.clone(), session);
         }
     }
 
+    for (pane_id, mut stale) in displaced {
+        if let Some(kept) = pane_to_session.get_mut(&pane_id) {
+            stale.sort_by_key(|s| std::cmp::Reverse(s.updated));
+            kept.pane_conflicts = stale.into_iter().map(PaneConflict::from_session).collect();
+        }
+    }
+
     let mut filtered: Vec<Session> = pane_to_session.into_values().collect();
 
     // タイムスタンプでソート（新しい順）
-    filtered.sort_by(|a, b| b.updated.cmp(&a.updated));
+    filtered.sort_by_key(|s| std::cmp::Reverse(s.updated));
 
-    Ok(filtered)
+    filtered
 }
 
 pub fn find_session_by_id<'a>(sessions: &'a [Session], session_id: &str) -> Option<&'a Session> {
     sessions.iter().find(|s| s.session_id == session_id)
 }
 
+pub fn get_projects_dir() -> Result<PathBuf> {
+    Ok(get_claude_dir()?.join("projects"))
+}
+
+/// cwdに対応する ~/.claude/projects/<project> ディレクトリ
+pub fn project_dir_for_cwd(cwd: &str) -> Result<PathBuf> {
+    Ok(get_projects_dir()?.join(cwd_to_project_path(cwd)))
+}
+
 fn cwd_to_project_path(cwd: &str) -> String {
-    // cwdから.claude/projectsのディレクトリ名を生成
+    // Claude Codeと同じく英数字以外をすべて '-' に置き換える
     // 例: "/home/aya/.dotfiles" -> "-home-aya--dotfiles"
-    // '/' と '.' の両方を '-' に置き換える
-    cwd.replace('/', "-").replace('.', "-")
+    //     "/home/aya/my_app v2" -> "-home-aya-my-app-v2"
+    cwd.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect()
 }
 
-fn load_sessions_index(cwd: &str) -> Result<HashMap<String, SessionIndexEntry>> {
-    let home = std::env::var("HOME").context("HOME環境変数が見つかりません")?;
-    let project_dir_name = cwd_to_project_path(cwd);
-    let index_path = Path::new(&home)
-        .join(".claude/projects")
-        .join(&project_dir_name)
-        .join("sessions-index.json");
+// セッションID → cwdから求めた名前と違う場所で見つかったプロジェクトのディレクトリ
+static FOUND_PROJECT_DIRS: Mutex<BTreeMap<String, PathBuf>> = Mutex::new(BTreeMap::new());
+
+// セッションID → 全プロジェクトを探しても見つからなかったときの projects ディレクトリの更新時刻と探した時刻
+static MISSED_PROJECT_DIRS: Mutex<BTreeMap<String, (Option<SystemTime>, Instant)>> = Mutex::new(BTreeMap::new());
+
+// 見つからなかったセッションを探し直すまでの間隔（既存のプロジェクトに後から書かれる場合に備える）
+const MISSED_PROJECT_DIR_TTL: Duration = Duration::from_secs(30);
+
+/// セッションのトランスクリプトがある ~/.claude/projects/<project> ディレクトリ
+///
+/// cwd（とシンボリックリンクを解決したcwd）から求めた名前のディレクトリに無ければ（長すぎるパスなどで
+/// 名前が一致しない）、全プロジェクトからセッションIDのトランスクリプトを探す。どこにも無ければcwdから求めたもの。
+pub fn project_dir_for_session(cwd: &str, session_id: &str) -> Result<PathBuf> {
+    let project_dir = project_dir_for_cwd(cwd)?;
+    let file_name = format!("{}.jsonl", session_id);
+    if project_dir.join(&file_name).exists() {
+        return Ok(project_dir);
+    }
+    let canonical = canonical_cwd(cwd);
+    if canonical != cwd {
+        let canonical_dir = project_dir_for_cwd(&canonical)?;
+        if canonical_dir.join(&file_name).exists() {
+            return Ok(canonical_dir);
+        }
+    }
+
+    if let Some(dir) = FOUND_PROJECT_DIRS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(session_id)
+        && dir.join(&file_name).exists()
+    {
+        return Ok(dir.clone());
+    }
+
+    // 前回見つからず projects ディレクトリも変わっていなければ探し直さない
+    let projects_dir = get_projects_dir()?;
+    let projects_mtime = fs::metadata(&projects_dir).and_then(|m| m.modified()).ok();
+    if let Some((mtime, searched_at)) = MISSED_PROJECT_DIRS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(session_id)
+        && *mtime == projects_mtime
+        && searched_at.elapsed() < MISSED_PROJECT_DIR_TTL
+    {
+        return Ok(project_dir);
+    }
 
-    if !index_path.exists() {
-        return Ok(HashMap::new());
+    let Ok(entries) = fs::read_dir(&projects_dir) else {
+        return Ok(project_dir);
+    };
+    let found = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .find(|dir| dir.join(&file_name).exists());
+    match found {
+        Some(dir) => {
+            tracing::debug!(session_id, ?dir, "cwdと名前の違うプロジェクトのディレクトリで見つかりました");
+            FOUND_PROJECT_DIRS
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(session_id.to_string(), dir.clone());
+            MISSED_PROJECT_DIRS.lock().unwrap_or_else(|e| e.into_inner()).remove(session_id);
+            Ok(dir)
+        }
+        None => {
+            MISSED_PROJECT_DIRS
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(session_id.to_string(), (projects_mtime, Instant::now()));
+            Ok(project_dir)
+        }
     }
+}
 
-    let content = fs::read_to_string(&index_path)
+fn read_index_file(index_path: &Path) -> Result<SessionsIndex> {
+    let content = fs::read_to_string(index_path)
         .with_context(|| format!("sessions-index.json読み込みエラー: {:?}", index_path))?;
 
-    let index: SessionsIndex = serde_json::from_str(&content)
+    serde_json::from_str(&content)
+        .with_context(|| format!("sessions-index.json解析エラー: {:?}", index_path))
+}
+
+/// セッションID → sessions-index.jsonのエントリ
+type IndexEntries = Arc<HashMap<String, SessionIndexEntry>>;
+
+/// 解析済みのsessions-index.json（更新日時とサイズが変わるまで使い回す）
+struct CachedIndex {
+    modified: SystemTime,
+    len: u64,
+    /// 解析したときに探したセッションID
+    wanted: HashSet<String>,
+    entries: IndexEntries,
+}
+
+// sessions-index.jsonのパス → 解析結果
+static INDEX_CACHE: Mutex<BTreeMap<PathBuf, CachedIndex>> = Mutex::new(BTreeMap::new());
+
+/// sessions-index.jsonから指定したIDのエントリだけを取り出す
+///
+/// エントリが数千件あっても、IDが一致しないものは文字列を確保せずに読み飛ばす。
+fn read_index_entries(index_path: &Path, wanted: &HashSet<&str>) -> Result<HashMap<String, SessionIndexEntry>> {
+    let content = fs::read_to_string(index_path)
+        .with_context(|| format!("sessions-index.json読み込みエラー: {:?}", index_path))?;
+    let index: SessionsIndexRef = serde_json::from_str(&content)
         .with_context(|| format!("sessions-index.json解析エラー: {:?}", index_path))?;
 
-    let map: HashMap<String, SessionIndexEntry> = index
-        .entries
-        .into_iter()
-        .map(|entry| (entry.session_id.clone(), entry))
-        .collect();
+    let mut entries = HashMap::new();
+    for entry in index.entries {
+        if !wanted.contains(entry.session_id.as_ref()) {
+            continue;
+        }
+        let entry = entry
+            .into_owned()
+            .with_context(|| format!("sessions-index.json解析エラー: {:?}", index_path))?;
+        entries.insert(entry.session_id.clone(), entry);
+    }
+    Ok(entries)
+}
+
+/// プロジェクトのsessions-index.jsonから指定したIDのエントリを読み込む
+///
+/// ファイルが変わっておらず、前回すでに探したIDだけならキャッシュを返す（2つ目はキャッシュを使ったか）。
+fn load_sessions_index(project_dir: &Path, wanted: &HashSet<&str>) -> Result<(IndexEntries, bool)> {
+    let index_path = project_dir.join("sessions-index.json");
 
-    Ok(map)
+    let Ok(metadata) = fs::metadata(&index_path) else {
+        return Ok((Arc::default(), false));
+    };
+    let modified = metadata
+        .modified()
+        .with_context(|| format!("更新日時を取得できません: {:?}", index_path))?;
+    let len = metadata.len();
+
+    if let Some(cached) = INDEX_CACHE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&index_path)
+        && cached.modified == modified
+        && cached.len == len
+        && wanted.iter().all(|id| cached.wanted.contains(*id))
+    {
+        return Ok((Arc::clone(&cached.entries), true));
+    }
+
+    let entries: IndexEntries = Arc::new(read_index_entries(&index_path, wanted)?);
+
+    INDEX_CACHE.lock().unwrap_or_else(|e| e.into_inner()).insert(
+        index_path,
+        CachedIndex {
+            modified,
+            len,
+            wanted: wanted.iter().map(|id| id.to_string()).collect(),
+            entries: Arc::clone(&entries),
+        },
+    );
+    Ok((entries, false))
 }
 
-pub fn enrich_sessions_with_index(sessions: &mut [Session]) -> Result<()> {
-    // cwdごとにsessions-index.jsonを読み込む
-    let mut cwd_to_index: HashMap<String, HashMap<String, SessionIndexEntry>> = HashMap::new();
+fn enrich_sessions_with_index(sessions: &mut [Session], pane_pids: &HashMap<String, Option<u32>>) {
+    // セッションごとのプロジェクトのディレクトリ（cwdから求めた名前と違うこともある）
+    let project_dirs: Vec<Option<PathBuf>> = sessions
+        .iter()
+        .map(|s| project_dir_for_session(&s.cwd, &s.session_id).ok())
+        .collect();
 
-    for session in sessions.iter() {
-        if !cwd_to_index.contains_key(&session.cwd) {
-            let index = load_sessions_index(&session.cwd).unwrap_or_default();
-            cwd_to_index.insert(session.cwd.clone(), index);
+    // プロジェクトごとにsessions-index.jsonを並列に読み込む
+    let mut dir_to_ids: HashMap<&Path, HashSet<&str>> = HashMap::new();
+    for (session, dir) in sessions.iter().zip(&project_dirs) {
+        if let Some(dir) = dir {
+            dir_to_ids
+                .entry(dir.as_path())
+                .or_default()
+                .insert(session.session_id.as_str());
         }
     }
+    let results: Vec<(&Path, IndexEntries, bool)> = thread::scope(|scope| {
+        let handles: Vec<_> = dir_to_ids
+            .iter()
+            .map(|(&dir, ids)| {
+                scope.spawn(move || {
+                    let (index, hit) = load_sessions_index(dir, ids).unwrap_or_else(|e| {
+                        tracing::warn!(?dir, "sessions-index.jsonを読み込めません: {:#}", e);
+                        (Arc::default(), false)
+                    });
+                    (dir, index, hit)
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .filter_map(|handle| handle.join().ok())
+            .collect()
+    });
+    let hits = results.iter().filter(|(_, _, hit)| *hit).count();
+    tracing::debug!(hits, misses = results.len() - hits, "sessions-index.jsonのキャッシュ");
+    let dir_to_index: HashMap<&Path, IndexEntries> = results
+        .into_iter()
+        .map(|(dir, index, _)| (dir, index))
+        .collect();
 
     // 各セッションにsummary、first_prompt、その他の情報を追加
-    for session in sessions.iter_mut() {
-        if let Some(index) = cwd_to_index.get(&session.cwd) {
-            if let Some(entry) = index.get(&session.session_id) {
-                session.summary = entry.summary.clone();
-                session.first_prompt = entry.first_prompt.clone();
-                session.message_count = entry.message_count;
-                session.git_branch = entry.git_branch.clone();
-                session.modified = entry.modified.clone();
-            }
+    for (session, dir) in sessions.iter_mut().zip(&project_dirs) {
+        if let Some(entry) = dir
+            .as_deref()
+            .and_then(|dir| dir_to_index.get(dir))
+            .and_then(|index| index.get(&session.session_id))
+        {
+            session.summary = entry.summary.clone();
+            session.first_prompt = entry.first_prompt.clone();
+            session.message_count = entry.message_count;
+            session.git_branch = entry.git_branch.clone();
+            session.modified = entry.modified.clone();
         }
     }
 
-    // メモリ使用量を取得
-    let pane_pids = get_all_pane_pids().unwrap_or_default();
+    // トランスクリプトからトークン使用量とモデルを集計（スレッド数はCPU数までに抑える）
+    let workers = thread::available_parallelism().map_or(4, |n| n.get());
+    let chunk_size = sessions.len().div_ceil(workers).max(1);
+    thread::scope(|scope| {
+        for chunk in sessions.chunks_mut(chunk_size) {
+            scope.spawn(move || chunk.iter_mut().for_each(apply_token_usage));
+        }
+    });
 
+    // メモリ使用量を取得
     for session in sessions.iter_mut() {
-        if let Some(&pid) = pane_pids.get(&session.pane_id) {
+        if let Some(&Some(pid)) = pane_pids.get(&session.pane_id) {
             session.memory_usage_kb = get_memory_from_proc(pid);
         }
     }
+}
 
-    Ok(())
+/// トランスクリプトのトークン使用量・モデル・サブエージェントをセッションに反映する
+fn apply_token_usage(session: &mut Session) {
+    let Some(transcript) = transcript_path(&session.cwd, &session.session_id)
+        .ok()
+        .and_then(|path| load_token_usage(&path))
+    else {
+        return;
+    };
+    session.token_usage = Some(transcript.usage);
+    session.context_tokens = transcript.context_tokens;
+    // 完了したセッションに結果のない呼び出しが残っていても実行中とは見なさない
+    if session.status != SessionStatus::Stopped {
+        session.subagents = transcript.subagents;
+    }
+    if transcript.model.is_some() {
+        session.model = transcript.model;
+    }
+}
+
+/// 全プロジェクトのsessions-index.jsonから過去のセッションを新しい順に取得
+pub fn load_history(exclude_ids: &HashSet<String>) -> Result<Vec<HistoryEntry>> {
+    let projects_dir = get_projects_dir()?;
+    if !projects_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut history = Vec::new();
+    for entry in fs::read_dir(&projects_dir).context("プロジェクトディレクトリの読み込みに失敗")? {
+        let project_dir = entry?.path();
+        let index_path = project_dir.join("sessions-index.json");
+        if !index_path.exists() {
+            continue;
+        }
+        let index = match read_index_file(&index_path) {
+            Ok(index) => index,
+            Err(e) => {
+                tracing::warn!("{:#}", e);
+                continue;
+            }
+        };
+
+        let dir_name = project_dir
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        for entry in index.entries {
+            if exclude_ids.contains(&entry.session_id) {
+                continue;
+            }
+            let project_path = entry.project_path.unwrap_or_else(|| dir_name.clone());
+            if is_ignored(&project_path) {
+                continue;
+            }
+            history.push(HistoryEntry {
+                session_id: entry.session_id,
+                project_path,
+                summary: entry.summary,
+                first_prompt: entry.first_prompt,
+                message_count: entry.message_count,
+                git_branch: entry.git_branch,
+                created: entry.created,
+                modified: entry.modified,
+            });
+        }
+    }
+
+    // RFC3339の文字列比較で新しい順に並べる
+    history.sort_by(|a, b| b.modified.cmp(&a.modified));
+    Ok(history)
+}
+
+/// 過去のセッションをプロジェクトごとにまとめ、最近使った順に返す
+pub fn load_projects() -> Result<Vec<ProjectSummary>> {
+    let mut projects: Vec<ProjectSummary> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    // 履歴は新しい順なので、最初に現れた順がそのまま最近使った順になる
+    for entry in load_history(&HashSet::new())? {
+        let i = *index.entry(entry.project_path.clone()).or_insert_with(|| {
+            projects.push(ProjectSummary {
+                path: entry.project_path.clone(),
+                session_count: 0,
+                recent: Vec::new(),
+            });
+            projects.len() - 1
+        });
+        let project = &mut projects[i];
+        project.session_count += 1;
+        if project.recent.len() < RECENT_PER_PROJECT {
+            project.recent.push(entry);
+        }
+    }
+    Ok(projects)
+}
+
+/// 全プロジェクトのsessions-index.jsonを解析し、解析できた数と失敗したファイルを返す
+pub fn check_index_files() -> Result<(usize, Vec<LoadError>)> {
+    let projects_dir = get_projects_dir()?;
+    if !projects_dir.exists() {
+        return Ok((0, Vec::new()));
+    }
+
+    let mut parsed = 0;
+    let mut errors = Vec::new();
+    for entry in fs::read_dir(&projects_dir).context("プロジェクトディレクトリの読み込みに失敗")? {
+        let index_path = entry?.path().join("sessions-index.json");
+        if !index_path.exists() {
+            continue;
+        }
+        match read_index_file(&index_path) {
+            Ok(_) => parsed += 1,
+            Err(e) => errors.push(LoadError {
+                path: index_path,
+                message: format!("{:#}", e),
+            }),
+        }
+    }
+    errors.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok((parsed, errors))
+}
+
+/// 過去のセッションをIDで検索
+pub fn find_history_entry(session_id: &str) -> Result<Option<HistoryEntry>> {
+    Ok(load_history(&HashSet::new())?
+        .into_iter()
+        .find(|entry| entry.session_id == session_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cwd_to_project_path_matches_documented_examples() {
+        assert_eq!(cwd_to_project_path("/home/aya/.dotfiles"), "-home-aya--dotfiles");
+        assert_eq!(cwd_to_project_path("/home/aya/my_app v2"), "-home-aya-my-app-v2");
+    }
+
+    #[test]
+    fn cwd_to_project_path_keeps_uppercase() {
+        assert_eq!(cwd_to_project_path("/Users/Aya/MyApp"), "-Users-Aya-MyApp");
+    }
+
+    #[test]
+    fn cwd_to_project_path_replaces_spaces_and_underscores() {
+        assert_eq!(cwd_to_project_path("/tmp/my project"), "-tmp-my-project");
+        assert_eq!(cwd_to_project_path("/tmp/snake_case_dir"), "-tmp-snake-case-dir");
+    }
+
+    #[test]
+    fn cwd_to_project_path_replaces_each_non_ascii_character() {
+        assert_eq!(cwd_to_project_path("/home/aya/作業"), "-home-aya---");
+        assert_eq!(cwd_to_project_path("/home/aya/café"), "-home-aya-caf-");
+    }
 }
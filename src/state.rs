@@ -0,0 +1,286 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::{env_dir, home_dir};
+use crate::projects::is_pinned_by_project;
+use crate::session::Session;
+
+/// セッションに付けたメモとタグ
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SessionNote {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl SessionNote {
+    fn is_empty(&self) -> bool {
+        self.note.is_none() && self.tags.is_empty()
+    }
+
+    /// "メモ #tag1 #tag2" 形式（一覧表示とTUIの入力欄の初期値）
+    pub fn to_input(&self) -> String {
+        let mut parts: Vec<String> = self.note.iter().cloned().collect();
+        parts.extend(self.tags.iter().map(|t| format!("#{}", t)));
+        parts.join(" ")
+    }
+
+    /// "メモ #tag1 #tag2" 形式の入力から作る（#で始まる語がタグ、残りがメモ）
+    pub fn from_input(input: &str) -> SessionNote {
+        let mut tags = Vec::new();
+        let mut words = Vec::new();
+        for word in input.split_whitespace() {
+            match word.strip_prefix('#').filter(|t| !t.is_empty()) {
+                Some(tag) => add_tag(&mut tags, tag),
+                None => words.push(word),
+            }
+        }
+        SessionNote {
+            note: (!words.is_empty()).then(|| words.join(" ")),
+            tags,
+        }
+    }
+}
+
+fn add_tag(tags: &mut Vec<String>, tag: &str) {
+    let tag = tag.trim_start_matches('#');
+    if !tag.is_empty() && !tags.iter().any(|t| t == tag) {
+        tags.push(tag.to_string());
+    }
+}
+
+/// 承認待ちの通知を一時的に止めたセッション
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snooze {
+    /// この時刻（UNIX秒）まで止める
+    pub until: u64,
+    /// スヌーズしたときのセッションの updated（ステータスが変わると変わるので解除する）
+    pub updated: u64,
+}
+
+/// claude-watch自身が保存する状態（セッションIDをキーにする）
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct State {
+    #[serde(default)]
+    pub notes: BTreeMap<String, SessionNote>,
+    /// 一覧の先頭に固定するセッション
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub pinned: BTreeSet<String>,
+    /// スヌーズ中のセッション
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub snoozed: BTreeMap<String, Snooze>,
+}
+
+impl State {
+    pub fn note(&self, session_id: &str) -> Option<&SessionNote> {
+        self.notes.get(session_id)
+    }
+
+    pub fn set_note(&mut self, session_id: &str, note: SessionNote) {
+        if note.is_empty() {
+            self.notes.remove(session_id);
+        } else {
+            self.notes.insert(session_id.to_string(), note);
+        }
+    }
+
+    pub fn add_tags(&mut self, session_id: &str, tags: &[String]) {
+        let mut note = self.notes.get(session_id).cloned().unwrap_or_default();
+        for tag in tags {
+            add_tag(&mut note.tags, tag);
+        }
+        self.set_note(session_id, note);
+    }
+
+    pub fn remove_tags(&mut self, session_id: &str, tags: &[String]) {
+        let mut note = self.notes.get(session_id).cloned().unwrap_or_default();
+        note.tags
+            .retain(|t| !tags.iter().any(|r| r.trim_start_matches('#') == t));
+        self.set_note(session_id, note);
+    }
+
+    pub fn has_tag(&self, session_id: &str, tag: &str) -> bool {
+        self.note(session_id)
+            .is_some_and(|n| n.tags.iter().any(|t| t == tag.trim_start_matches('#')))
+    }
+
+    pub fn is_pinned(&self, session_id: &str) -> bool {
+        self.pinned.contains(session_id)
+    }
+
+    /// ピン留めを切り替える（ピン留めした場合はtrue）
+    pub fn toggle_pin(&mut self, session_id: &str) -> bool {
+        if self.pinned.remove(session_id) {
+            false
+        } else {
+            self.pinned.insert(session_id.to_string());
+            true
+        }
+    }
+
+    /// ピン留めしたセッション（[[projects]] の pin を含む）を先頭に移す（それぞれの中の順序は保つ）
+    pub fn sort_pinned_first(&self, sessions: &mut [Session]) {
+        sessions.sort_by_key(|s| !(self.is_pinned(&s.session_id) || is_pinned_by_project(s)));
+    }
+
+    /// 期限内で、スヌーズしてからステータスが変わっていないか
+    pub fn is_snoozed(&self, session: &Session) -> bool {
+        self.snoozed
+            .get(&session.session_id)
+            .is_some_and(|s| s.updated == session.updated && now_secs() < s.until)
+    }
+
+    /// スヌーズを切り替える（スヌーズした場合はtrue）。期限切れの記録はここで捨てる
+    pub fn toggle_snooze(&mut self, session: &Session, secs: u64) -> bool {
+        let now = now_secs();
+        self.snoozed.retain(|_, s| now < s.until);
+        if self.is_snoozed(session) {
+            self.snoozed.remove(&session.session_id);
+            false
+        } else {
+            let snooze = Snooze {
+                until: now + secs,
+                updated: session.updated,
+            };
+            self.snoozed.insert(session.session_id.clone(), snooze);
+            true
+        }
+    }
+
+    /// 各セッションのスヌーズ中の印を付け直す
+    pub fn apply_snoozes(&self, sessions: &mut [Session]) {
+        for session in sessions {
+            session.snoozed = self.is_snoozed(session);
+        }
+    }
+
+    /// 使われている全タグ（名前順）
+    pub fn all_tags(&self) -> Vec<String> {
+        let mut tags: Vec<String> = self
+            .notes
+            .values()
+            .flat_map(|n| n.tags.iter().cloned())
+            .collect();
+        tags.sort();
+        tags.dedup();
+        tags
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+/// $XDG_STATE_HOME/claude-watch（未設定なら ~/.local/state/claude-watch）
+pub fn get_state_dir() -> Result<PathBuf> {
+    if let Some(dir) = env_dir("XDG_STATE_HOME") {
+        return Ok(dir.join("claude-watch"));
+    }
+    // Windowsでは %LOCALAPPDATA%\claude-watch
+    if cfg!(windows)
+        && let Some(dir) = env_dir("LOCALAPPDATA")
+    {
+        return Ok(dir.join("claude-watch"));
+    }
+    Ok(home_dir()?.join(".local/state/claude-watch"))
+}
+
+fn get_state_path() -> Result<PathBuf> {
+    Ok(get_state_dir()?.join("state.json"))
+}
+
+/// 状態ファイルを読み込む（存在しなければ空）
+pub fn load_state() -> Result<State> {
+    load_state_from(&get_state_path()?)
+}
+
+fn load_state_from(path: &Path) -> Result<State> {
+    if !path.exists() {
+        return Ok(State::default());
+    }
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("状態ファイル読み込みエラー: {:?}", path))?;
+    serde_json::from_str(&content).with_context(|| format!("状態ファイル解析エラー: {:?}", path))
+}
+
+/// 最新の状態ファイルを読み直して変更を加え、保存した状態を返す
+///
+/// TUIとCLIが別々に書き込むので、手元の古い状態をそのまま保存すると
+/// その間に他方が付けたピン留めやスヌーズ、メモを消してしまう
+pub fn update_state<T>(f: impl FnOnce(&mut State) -> T) -> Result<(State, T)> {
+    update_state_at(&get_state_path()?, f)
+}
+
+fn update_state_at<T>(path: &Path, f: impl FnOnce(&mut State) -> T) -> Result<(State, T)> {
+    let mut state = load_state_from(path)?;
+    let result = f(&mut state);
+    save_state_to(path, &state)?;
+    Ok((state, result))
+}
+
+/// 一時ファイルに書いてから置き換える（読み込み側が書きかけのファイルを見ないように）
+fn save_state_to(path: &Path, state: &State) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("ディレクトリの作成に失敗: {:?}", dir))?;
+    }
+    let json = serde_json::to_string_pretty(state)?;
+    let tmp_path = path.with_extension(format!("json.{}.tmp", std::process::id()));
+    fs::write(&tmp_path, json)
+        .with_context(|| format!("状態ファイルの書き込みに失敗: {:?}", tmp_path))?;
+    fs::rename(&tmp_path, path).with_context(|| format!("状態ファイルの書き込みに失敗: {:?}", path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_state_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("claude-watch-test-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir.join("state.json")
+    }
+
+    #[test]
+    fn update_keeps_snooze_written_by_another_process() {
+        let path = temp_state_path("snooze");
+        // TUIが起動時に読み込んだ状態
+        let stale = load_state_from(&path).unwrap();
+        // TUIの起動中に別のプロセスがスヌーズを書き込む
+        update_state_at(&path, |state| {
+            let snooze = Snooze {
+                until: now_secs() + 600,
+                updated: 1,
+            };
+            state.snoozed.insert("other".to_string(), snooze);
+        })
+        .unwrap();
+        // TUIでピン留めしても、手元の古い状態で上書きしない
+        assert!(!stale.is_pinned("mine"));
+        let (state, ()) = update_state_at(&path, |state| {
+            state.toggle_pin("mine");
+        })
+        .unwrap();
+        assert!(state.is_pinned("mine"));
+        assert!(state.snoozed.contains_key("other"));
+
+        let reloaded = load_state_from(&path).unwrap();
+        assert!(reloaded.is_pinned("mine"));
+        assert!(reloaded.snoozed.contains_key("other"));
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn save_leaves_no_temp_file() {
+        let path = temp_state_path("atomic");
+        update_state_at(&path, |state| state.set_note("a", SessionNote::from_input("メモ #tag"))).unwrap();
+        let dir = path.parent().unwrap();
+        let files: Vec<_> = fs::read_dir(dir).unwrap().flatten().map(|e| e.file_name()).collect();
+        assert_eq!(files, vec!["state.json"]);
+        let _ = fs::remove_dir_all(dir);
+    }
+}
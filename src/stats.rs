@@ -0,0 +1,125 @@
+use anyhow::Result;
+use chrono::{Local, NaiveDate};
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::display::elapsed_since;
+use crate::export::sessions_since;
+use crate::latency::average_latency_today;
+use crate::store::EventStore;
+use crate::session::{HistoryEntry, Session};
+use crate::status::SessionStatus;
+
+/// 表示するプロジェクト・長いセッションの件数
+const TOP_N: usize = 5;
+
+pub struct ProjectStats {
+    pub project: String,
+    pub sessions: usize,
+    pub total_secs: u64,
+}
+
+/// `claude-watch stats` の集計結果
+pub struct Stats {
+    /// 日ごとのセッション数（古い日から、セッションの無い日も含む）
+    pub per_day: Vec<(NaiveDate, usize)>,
+    /// セッション数の多いプロジェクト
+    pub projects: Vec<ProjectStats>,
+    /// 開始から最終更新までが長いセッション
+    pub longest: Vec<HistoryEntry>,
+    /// 現在のセッションのステータス内訳（多い順）
+    pub statuses: Vec<(SessionStatus, usize)>,
+    /// 現在承認待ちのセッションの平均待ち時間（秒）
+    pub waiting_avg: Option<u64>,
+    /// 今日承認されるまでにかかった平均時間（秒）と件数
+    pub approval_today: Option<(u64, usize)>,
+    /// 今日の平均より長く承認待ちになっているセッション
+    pub waiting_over_avg: Vec<Session>,
+    pub total_sessions: usize,
+}
+
+fn local_date(timestamp: &str) -> Option<NaiveDate> {
+    let time = chrono::DateTime::parse_from_rfc3339(timestamp).ok()?;
+    Some(time.with_timezone(&Local).date_naive())
+}
+
+/// 直近 `days` 日の履歴と現在のセッションを集計する
+pub fn collect_stats(days: u64, active: &[Session]) -> Result<Stats> {
+    let history = sessions_since(Some(Duration::from_secs(days * 86400)))?;
+
+    let today = Local::now().date_naive();
+    let mut per_day: Vec<(NaiveDate, usize)> = (0..days)
+        .rev()
+        .filter_map(|offset| today.checked_sub_days(chrono::Days::new(offset)))
+        .map(|date| (date, 0))
+        .collect();
+    for entry in &history {
+        if let Some(date) = entry.modified.as_deref().and_then(local_date)
+            && let Some(day) = per_day.iter_mut().find(|(d, _)| *d == date)
+        {
+            day.1 += 1;
+        }
+    }
+
+    let mut by_project: HashMap<&str, ProjectStats> = HashMap::new();
+    for entry in &history {
+        let stats = by_project.entry(&entry.project_path).or_insert_with(|| ProjectStats {
+            project: entry.project_path.clone(),
+            sessions: 0,
+            total_secs: 0,
+        });
+        stats.sessions += 1;
+        stats.total_secs += entry.duration_secs().unwrap_or(0);
+    }
+    let mut projects: Vec<ProjectStats> = by_project.into_values().collect();
+    projects.sort_by(|a, b| b.sessions.cmp(&a.sessions).then(b.total_secs.cmp(&a.total_secs)));
+    projects.truncate(TOP_N);
+
+    let mut longest: Vec<HistoryEntry> = history
+        .iter()
+        .filter(|e| e.duration_secs().is_some())
+        .cloned()
+        .collect();
+    longest.sort_by_key(|e| std::cmp::Reverse(e.duration_secs()));
+    longest.truncate(TOP_N);
+
+    let mut statuses: Vec<(SessionStatus, usize)> = Vec::new();
+    for session in active {
+        match statuses.iter_mut().find(|(status, _)| *status == session.status) {
+            Some((_, count)) => *count += 1,
+            None => statuses.push((session.status.clone(), 1)),
+        }
+    }
+    statuses.sort_by(|a, b| b.1.cmp(&a.1).then(b.0.severity().cmp(&a.0.severity())));
+
+    let waits: Vec<u64> = active
+        .iter()
+        .filter(|s| s.status == SessionStatus::Waiting)
+        .map(|s| elapsed_since(s.updated))
+        .collect();
+    let waiting_avg = (!waits.is_empty()).then(|| waits.iter().sum::<u64>() / waits.len() as u64);
+
+    // イベントストアが開けなければ承認までの時間は出さない
+    let approval_today = EventStore::open()
+        .ok()
+        .and_then(|store| average_latency_today(&store));
+    let waiting_over_avg = match approval_today {
+        Some((avg, _)) => active
+            .iter()
+            .filter(|s| s.status == SessionStatus::Waiting && elapsed_since(s.updated) > avg)
+            .cloned()
+            .collect(),
+        None => Vec::new(),
+    };
+
+    Ok(Stats {
+        per_day,
+        projects,
+        longest,
+        statuses,
+        waiting_avg,
+        approval_today,
+        waiting_over_avg,
+        total_sessions: history.len(),
+    })
+}
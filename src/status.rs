@@ -0,0 +1,206 @@
+use anyhow::{anyhow, Result};
+use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use crate::config::StatusConfig;
+use crate::display::glyph;
+use crate::theme::theme;
+
+/// 設定ファイルで表示を定めたステータス
+struct CustomStatus {
+    icon: Option<String>,
+    ascii_icon: String,
+    label: Option<String>,
+    color: Option<Color>,
+    priority: Option<u8>,
+    running: Option<bool>,
+}
+
+static CUSTOM_STATUSES: OnceLock<HashMap<String, CustomStatus>> = OnceLock::new();
+
+/// 設定ファイルの [statuses.<name>] を読み込む（起動時に1回だけ）
+pub fn init_custom_statuses(statuses: &BTreeMap<String, StatusConfig>) -> Result<()> {
+    let mut custom = HashMap::new();
+    for (name, config) in statuses {
+        let color = config
+            .color
+            .as_deref()
+            .map(|c| Color::from_str(c).map_err(|_| anyhow!("statuses.{} の色が不正です: {}", name, c)))
+            .transpose()?;
+        let ascii_icon = config.ascii_icon.clone().unwrap_or_else(|| {
+            let initial = name.chars().next().map(|c| c.to_ascii_uppercase()).unwrap_or('?');
+            format!("[{}]", initial)
+        });
+        custom.insert(
+            name.clone(),
+            CustomStatus {
+                icon: config.icon.clone(),
+                ascii_icon,
+                label: config.label.clone(),
+                color,
+                priority: config.priority,
+                running: config.running,
+            },
+        );
+    }
+    let _ = CUSTOM_STATUSES.set(custom);
+    Ok(())
+}
+
+/// 設定ファイルで追加したステータスの一覧（名前順、組み込みのステータスは除く）
+pub fn custom_statuses() -> Vec<SessionStatus> {
+    let mut statuses: Vec<SessionStatus> = CUSTOM_STATUSES
+        .get()
+        .into_iter()
+        .flat_map(|c| c.keys())
+        .map(|name| SessionStatus::parse(name))
+        .filter(|s| matches!(s, SessionStatus::Other(_)))
+        .collect();
+    statuses.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+    statuses
+}
+
+/// セッションのステータス（フックがセッションファイルに書き込む文字列）
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum SessionStatus {
+    Active,
+    Waiting,
+    Stopped,
+    /// 知らないステータス（書き込まれた文字列をそのまま持つ）
+    Other(String),
+}
+
+impl SessionStatus {
+    pub fn parse(status: &str) -> SessionStatus {
+        match status {
+            "active" => SessionStatus::Active,
+            "waiting" => SessionStatus::Waiting,
+            "stopped" => SessionStatus::Stopped,
+            other => SessionStatus::Other(other.to_string()),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            SessionStatus::Active => "active",
+            SessionStatus::Waiting => "waiting",
+            SessionStatus::Stopped => "stopped",
+            SessionStatus::Other(status) => status,
+        }
+    }
+
+    /// 設定ファイルでの表示の指定
+    fn custom(&self) -> Option<&'static CustomStatus> {
+        CUSTOM_STATUSES.get()?.get(self.as_str())
+    }
+
+    pub fn icon(&self) -> &'static str {
+        if let Some(custom) = self.custom() {
+            let icon = custom.icon.as_deref().unwrap_or_else(|| self.builtin_icon());
+            return glyph(icon, &custom.ascii_icon);
+        }
+        self.builtin_icon()
+    }
+
+    fn builtin_icon(&self) -> &'static str {
+        match self {
+            SessionStatus::Active => glyph("🟢", "[A]"),
+            SessionStatus::Waiting => glyph("🟡", "[W]"),
+            SessionStatus::Stopped => glyph("⚪", "[S]"),
+            SessionStatus::Other(_) => glyph("❓", "[?]"),
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        if let Some(label) = self.custom().and_then(|c| c.label.as_deref()) {
+            return label;
+        }
+        match self {
+            SessionStatus::Active => "実行中",
+            SessionStatus::Waiting => "承認待ち",
+            SessionStatus::Stopped => "完了",
+            // 表示の指定がなければ書き込まれた文字列をそのまま出す
+            SessionStatus::Other(status) if !status.is_empty() => status,
+            SessionStatus::Other(_) => "不明",
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        self.custom()
+            .and_then(|c| c.color)
+            .unwrap_or_else(|| theme().status_color(self))
+    }
+
+    /// 深刻度（大きいほど注意が必要）
+    pub fn severity(&self) -> u8 {
+        if let Some(priority) = self.custom().and_then(|c| c.priority) {
+            return priority;
+        }
+        match self {
+            SessionStatus::Waiting => 3,
+            SessionStatus::Active => 2,
+            SessionStatus::Stopped => 1,
+            SessionStatus::Other(_) => 0,
+        }
+    }
+
+    /// 経過時間を表示するステータスか（実行中・承認待ち）
+    pub fn is_running(&self) -> bool {
+        if let Some(running) = self.custom().and_then(|c| c.running) {
+            return running;
+        }
+        matches!(self, SessionStatus::Active | SessionStatus::Waiting)
+    }
+}
+
+impl From<String> for SessionStatus {
+    fn from(status: String) -> Self {
+        SessionStatus::parse(&status)
+    }
+}
+
+impl From<SessionStatus> for String {
+    fn from(status: SessionStatus) -> Self {
+        status.as_str().to_string()
+    }
+}
+
+impl fmt::Display for SessionStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_icon_label_color() {
+        let cases = [
+            (SessionStatus::Active, "🟢", "実行中"),
+            (SessionStatus::Waiting, "🟡", "承認待ち"),
+            (SessionStatus::Stopped, "⚪", "完了"),
+        ];
+        for (status, icon, label) in cases {
+            assert_eq!(status.icon(), icon);
+            assert_eq!(status.label(), label);
+            assert_eq!(status.color(), theme().status_color(&status));
+        }
+        assert_ne!(SessionStatus::Active.color(), SessionStatus::Waiting.color());
+    }
+
+    #[test]
+    fn unknown_status_shows_written_name() {
+        let status = SessionStatus::parse("compacting");
+        assert_eq!(status, SessionStatus::Other("compacting".to_string()));
+        assert_eq!(status.label(), "compacting");
+        assert_eq!(status.icon(), "❓");
+        assert_eq!(SessionStatus::parse("").label(), "不明");
+    }
+}
@@ -0,0 +1,187 @@
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::state::get_state_dir;
+use crate::transitions::Transition;
+
+/// ステータス変化を記録するSQLiteのイベントストア（$XDG_STATE_HOME/claude-watch/events.db）
+pub struct EventStore {
+    conn: Connection,
+}
+
+/// 記録されたステータス変化
+#[derive(Debug, Clone)]
+pub struct StoredTransition {
+    pub session_id: String,
+    pub cwd: String,
+    /// "active" などのステータス（出現時は "new"）
+    pub from: String,
+    /// "waiting" などのステータス（消滅時は "gone"）
+    pub to: String,
+    /// 変化前のステータスになった時刻（UNIX秒）
+    pub from_updated: Option<u64>,
+    /// 変化後のステータスになった時刻（UNIX秒）
+    pub updated: u64,
+    pub summary: Option<String>,
+    pub pane_id: String,
+}
+
+impl StoredTransition {
+    /// 監視中に検出した変化を記録と同じ形にする
+    pub fn from_transition(t: &Transition) -> StoredTransition {
+        let session = &t.session;
+        StoredTransition {
+            session_id: session.session_id.clone(),
+            cwd: session.cwd.clone(),
+            from: t.old_label().to_string(),
+            to: t.new_label().to_string(),
+            from_updated: t.from_updated,
+            // 消滅時はセッションファイルの時刻が変わらないため検出した時刻を使う
+            updated: if t.to.is_some() { session.updated } else { t.timestamp },
+            summary: session.summary.clone(),
+            pane_id: session.pane_id.clone(),
+        }
+    }
+
+    /// `events --json` の1行（type は "new" / "gone" / "status_change"）
+    pub fn to_json(&self) -> serde_json::Value {
+        let kind = match (self.from.as_str(), self.to.as_str()) {
+            ("new", _) => "new",
+            (_, "gone") => "gone",
+            _ => "status_change",
+        };
+        serde_json::json!({
+            "type": kind,
+            "session_id": self.session_id,
+            "cwd": self.cwd,
+            "pane_id": self.pane_id,
+            "from": self.from,
+            "to": self.to,
+            "from_updated": self.from_updated,
+            "timestamp": self.updated,
+            "summary": self.summary,
+        })
+    }
+}
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS transitions (
+    id INTEGER PRIMARY KEY,
+    session_id TEXT NOT NULL,
+    cwd TEXT NOT NULL,
+    pane_id TEXT NOT NULL,
+    from_status TEXT NOT NULL,
+    to_status TEXT NOT NULL,
+    from_updated INTEGER,
+    updated INTEGER NOT NULL,
+    recorded_at INTEGER NOT NULL,
+    summary TEXT,
+    -- TUIとデーモンが同時に記録しても1件にする
+    UNIQUE (session_id, from_status, to_status, updated)
+);
+CREATE INDEX IF NOT EXISTS transitions_updated ON transitions (updated);
+CREATE INDEX IF NOT EXISTS transitions_session ON transitions (session_id);
+";
+
+/// SELECTの列順（session_id, cwd, from_status, to_status, from_updated, updated, summary, pane_id）
+fn read_transition(row: &rusqlite::Row) -> rusqlite::Result<StoredTransition> {
+    Ok(StoredTransition {
+        session_id: row.get(0)?,
+        cwd: row.get(1)?,
+        from: row.get(2)?,
+        to: row.get(3)?,
+        from_updated: row.get::<_, Option<i64>>(4)?.map(|u| u as u64),
+        updated: row.get::<_, i64>(5)? as u64,
+        summary: row.get(6)?,
+        pane_id: row.get(7)?,
+    })
+}
+
+pub fn get_store_path() -> Result<PathBuf> {
+    Ok(get_state_dir()?.join("events.db"))
+}
+
+impl EventStore {
+    pub fn open() -> Result<EventStore> {
+        let path = get_store_path()?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).with_context(|| format!("ディレクトリの作成に失敗: {:?}", dir))?;
+        }
+        let conn = Connection::open(&path)
+            .with_context(|| format!("イベントストアを開けません: {:?}", path))?;
+        // TUIとデーモンが同時に書き込むことがある
+        conn.busy_timeout(Duration::from_secs(2))?;
+        conn.execute_batch(SCHEMA)
+            .with_context(|| format!("イベントストアの初期化に失敗: {:?}", path))?;
+        Ok(EventStore { conn })
+    }
+
+    /// ステータス変化を追記する（記録済みのものは無視）
+    pub fn record_transitions(&mut self, transitions: &[Transition]) -> Result<()> {
+        if transitions.is_empty() {
+            return Ok(());
+        }
+        let tx = self.conn.transaction()?;
+        {
+            let mut stmt = tx.prepare_cached(
+                "INSERT OR IGNORE INTO transitions
+                 (session_id, cwd, pane_id, from_status, to_status, from_updated, updated, recorded_at, summary)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            )?;
+            for t in transitions {
+                let event = StoredTransition::from_transition(t);
+                stmt.execute(params![
+                    event.session_id,
+                    event.cwd,
+                    event.pane_id,
+                    event.from,
+                    event.to,
+                    event.from_updated.map(|u| u as i64),
+                    event.updated as i64,
+                    t.timestamp as i64,
+                    event.summary,
+                ])?;
+            }
+        }
+        tx.commit().context("イベントの記録に失敗")?;
+        Ok(())
+    }
+
+    /// 条件に合うステータス変化を新しい順に返す
+    pub fn transitions(
+        &self,
+        session_id: Option<&str>,
+        since: Option<u64>,
+        limit: usize,
+    ) -> Result<Vec<StoredTransition>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT session_id, cwd, from_status, to_status, from_updated, updated, summary, pane_id
+             FROM transitions
+             WHERE (?1 IS NULL OR session_id = ?1) AND updated >= ?2
+             ORDER BY updated DESC, id DESC
+             LIMIT ?3",
+        )?;
+        let rows = stmt.query_map(
+            params![session_id, since.unwrap_or(0) as i64, limit as i64],
+            read_transition,
+        )?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+            .context("イベントの読み込みに失敗")
+    }
+
+    /// 指定したステータス間の変化（古い順）
+    pub fn transitions_between(&self, from: &str, to: &str, since: u64) -> Result<Vec<StoredTransition>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT session_id, cwd, from_status, to_status, from_updated, updated, summary, pane_id
+             FROM transitions
+             WHERE from_status = ?1 AND to_status = ?2 AND updated >= ?3
+             ORDER BY updated",
+        )?;
+        let rows = stmt.query_map(params![from, to, since as i64], read_transition)?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+            .context("イベントの読み込みに失敗")
+    }
+}
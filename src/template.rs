@@ -0,0 +1,143 @@
+use anyhow::{anyhow, Result};
+
+use crate::display::{elapsed_since, format_clock_time, format_cwd, format_dir_name, format_duration, truncate_text};
+use crate::session::Session;
+use crate::state::State;
+
+// `list --template` の書式。{名前} をセッションの値に置き換え、{名前:幅} で幅をそろえる。
+// {{ と }} は波かっこそのもの、\t と \n はタブと改行。
+
+/// 使えるプレースホルダ
+const FIELDS: &[&str] = &[
+    "icon", "status", "label", "cwd", "path", "dir", "id", "short_id", "pane", "host", "age", "updated", "reason",
+    "tool", "last_message", "summary", "branch", "messages", "context", "model", "subagents", "note", "tags",
+];
+
+#[derive(Debug, Clone)]
+enum Part {
+    Text(String),
+    /// プレースホルダ名と幅（文字数）
+    Field(&'static str, Option<usize>),
+}
+
+/// 解析済みのテンプレート
+#[derive(Debug, Clone)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+impl Template {
+    pub fn parse(template: &str) -> Result<Template> {
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        let mut chars = template.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '\\' if matches!(chars.peek(), Some('t' | 'n')) => {
+                    text.push(if chars.next() == Some('t') { '\t' } else { '\n' });
+                }
+                '{' => {
+                    let mut spec = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => spec.push(c),
+                            None => return Err(anyhow!("テンプレートの {{{} が閉じていません", spec)),
+                        }
+                    }
+                    if !text.is_empty() {
+                        parts.push(Part::Text(std::mem::take(&mut text)));
+                    }
+                    parts.push(parse_field(&spec)?);
+                }
+                '}' => return Err(anyhow!("テンプレートの }} に対応する {{ がありません（}} そのものは }}}} と書く）")),
+                c => text.push(c),
+            }
+        }
+        if !text.is_empty() {
+            parts.push(Part::Text(text));
+        }
+        Ok(Template { parts })
+    }
+
+    /// セッション1件分の文字列
+    pub fn render(&self, session: &Session, state: &State) -> String {
+        self.parts
+            .iter()
+            .map(|part| match part {
+                Part::Text(text) => text.clone(),
+                Part::Field(name, width) => {
+                    let value = field_value(name, session, state);
+                    match width {
+                        Some(width) => format!("{:<width$}", truncate_text(&value, *width), width = width),
+                        None => value,
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+/// "name" または "name:幅"
+fn parse_field(spec: &str) -> Result<Part> {
+    let (name, width) = match spec.split_once(':') {
+        Some((name, width)) => {
+            let width = width
+                .trim()
+                .parse()
+                .map_err(|_| anyhow!("テンプレートの幅は数値で指定してください: {{{}}}", spec))?;
+            (name.trim(), Some(width))
+        }
+        None => (spec.trim(), None),
+    };
+    let name = FIELDS.iter().copied().find(|field| *field == name).ok_or_else(|| {
+        anyhow!("テンプレートの不明なプレースホルダ: {{{}}}（{} のいずれか）", name, FIELDS.join(", "))
+    })?;
+    Ok(Part::Field(name, width))
+}
+
+fn field_value(name: &str, session: &Session, state: &State) -> String {
+    let note = state.note(&session.session_id);
+    match name {
+        "icon" => session.status_icon().to_string(),
+        "status" => session.status.as_str().to_string(),
+        "label" => session.status_label().to_string(),
+        "cwd" => format_cwd(&session.cwd),
+        "path" => session.cwd.clone(),
+        "dir" => format_dir_name(&session.cwd).to_string(),
+        "id" => session.session_id.clone(),
+        "short_id" => session.session_id.chars().take(8).collect(),
+        "pane" => session.pane_id.clone(),
+        "host" => session.host.clone().unwrap_or_default(),
+        "age" => format_duration(elapsed_since(session.updated)),
+        "updated" => format_clock_time(session.updated as i64),
+        "reason" => session.waiting_reason.as_ref().map(|r| r.label()).unwrap_or_default(),
+        "tool" => session.current_tool.clone().unwrap_or_default(),
+        "last_message" => session.last_message.clone().unwrap_or_default(),
+        "summary" => session
+            .summary
+            .as_ref()
+            .or(session.first_prompt.as_ref())
+            .cloned()
+            .unwrap_or_default(),
+        "branch" => session.git_branch.clone().unwrap_or_default(),
+        "messages" => session.message_count.map(|n| n.to_string()).unwrap_or_default(),
+        "context" => session.context_percent().map(|p| format!("{}%", p)).unwrap_or_default(),
+        "subagents" => match session.subagents.len() {
+            0 => String::new(),
+            n => n.to_string(),
+        },
+        "model" => session.model_family().unwrap_or_default().to_string(),
+        "note" => note.and_then(|n| n.note.clone()).unwrap_or_default(),
+        "tags" => note.map(|n| n.tags.join(" ")).unwrap_or_default(),
+        _ => String::new(),
+    }
+}
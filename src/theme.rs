@@ -0,0 +1,188 @@
+use anyhow::{anyhow, Result};
+use ratatui::style::{Color, Modifier, Style};
+use std::sync::OnceLock;
+
+use crate::status::SessionStatus;
+
+/// TUIと一覧表示で使う色
+pub struct Theme {
+    pub active: Color,
+    pub waiting: Color,
+    pub stopped: Color,
+    pub unknown: Color,
+    /// キー・選択中のタブ・タスク
+    pub accent: Color,
+    /// パスや要約などの本文
+    pub text: Color,
+    /// タブとフッター
+    pub subtle: Color,
+    /// メタ情報や見出し
+    pub muted: Color,
+    pub warning: Color,
+    /// しばらく承認待ちのままのセッション
+    pub escalated: Color,
+    pub danger: Color,
+    pub ok: Color,
+    /// マーク中のセッション
+    pub mark: Color,
+    /// 選択中の行
+    pub highlight: Style,
+    pub border: Color,
+    /// プロジェクトの色分け（空なら色分けしない）
+    pub project_colors: &'static [Color],
+}
+
+static THEME: OnceLock<Theme> = OnceLock::new();
+
+/// 起動時に使用するテーマを決める（以降は変更しない）
+pub fn init_theme(theme: Theme) {
+    let _ = THEME.set(theme);
+}
+
+pub fn theme() -> &'static Theme {
+    THEME.get_or_init(Theme::default_theme)
+}
+
+impl Theme {
+    pub fn parse(name: &str) -> Result<Theme> {
+        match name {
+            "default" => Ok(Theme::default_theme()),
+            "light" => Ok(Theme::light()),
+            "solarized" => Ok(Theme::solarized()),
+            "monochrome" => Ok(Theme::monochrome()),
+            _ => Err(anyhow!(
+                "不明なテーマ: {} (default, light, solarized, monochrome のいずれかを指定)",
+                name
+            )),
+        }
+    }
+
+    fn default_theme() -> Theme {
+        Theme {
+            active: Color::Green,
+            waiting: Color::Yellow,
+            stopped: Color::Gray,
+            unknown: Color::White,
+            accent: Color::Cyan,
+            text: Color::White,
+            subtle: Color::Gray,
+            muted: Color::DarkGray,
+            warning: Color::Yellow,
+            escalated: Color::Indexed(208),
+            danger: Color::Red,
+            ok: Color::Green,
+            mark: Color::Magenta,
+            highlight: Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD),
+            border: Color::Reset,
+            // ステータス色と紛らわしい緑・黄・赤は避ける
+            project_colors: &[
+                Color::Cyan,
+                Color::Magenta,
+                Color::LightBlue,
+                Color::LightMagenta,
+                Color::LightCyan,
+                Color::Indexed(208), // オレンジ
+                Color::Indexed(141), // 紫
+                Color::Indexed(73),  // 青緑
+                Color::Indexed(175), // ピンク
+                Color::Indexed(110), // 水色
+            ],
+        }
+    }
+
+    /// 明るい背景の端末向け
+    fn light() -> Theme {
+        Theme {
+            active: Color::Indexed(28),
+            waiting: Color::Indexed(130),
+            stopped: Color::Indexed(244),
+            unknown: Color::Black,
+            accent: Color::Blue,
+            text: Color::Black,
+            subtle: Color::Indexed(240),
+            muted: Color::Indexed(246),
+            warning: Color::Indexed(130),
+            escalated: Color::Indexed(166),
+            danger: Color::Indexed(160),
+            ok: Color::Indexed(28),
+            mark: Color::Indexed(127),
+            highlight: Style::default().bg(Color::Indexed(253)).add_modifier(Modifier::BOLD),
+            border: Color::Indexed(246),
+            project_colors: &[
+                Color::Indexed(25),
+                Color::Indexed(90),
+                Color::Indexed(30),
+                Color::Indexed(94),
+                Color::Indexed(61),
+                Color::Indexed(125),
+                Color::Indexed(24),
+                Color::Indexed(97),
+            ],
+        }
+    }
+
+    fn solarized() -> Theme {
+        const BASE02: Color = Color::Rgb(0x07, 0x36, 0x42);
+        const BASE01: Color = Color::Rgb(0x58, 0x6e, 0x75);
+        const BASE0: Color = Color::Rgb(0x83, 0x94, 0x96);
+        const BASE1: Color = Color::Rgb(0x93, 0xa1, 0xa1);
+        const YELLOW: Color = Color::Rgb(0xb5, 0x89, 0x00);
+        const ORANGE: Color = Color::Rgb(0xcb, 0x4b, 0x16);
+        const RED: Color = Color::Rgb(0xdc, 0x32, 0x2f);
+        const MAGENTA: Color = Color::Rgb(0xd3, 0x36, 0x82);
+        const VIOLET: Color = Color::Rgb(0x6c, 0x71, 0xc4);
+        const BLUE: Color = Color::Rgb(0x26, 0x8b, 0xd2);
+        const CYAN: Color = Color::Rgb(0x2a, 0xa1, 0x98);
+        const GREEN: Color = Color::Rgb(0x85, 0x99, 0x00);
+
+        Theme {
+            active: GREEN,
+            waiting: YELLOW,
+            stopped: BASE01,
+            unknown: BASE0,
+            accent: CYAN,
+            text: BASE1,
+            subtle: BASE0,
+            muted: BASE01,
+            warning: ORANGE,
+            escalated: ORANGE,
+            danger: RED,
+            ok: GREEN,
+            mark: MAGENTA,
+            highlight: Style::default().bg(BASE02).add_modifier(Modifier::BOLD),
+            border: BASE01,
+            project_colors: &[BLUE, VIOLET, MAGENTA, CYAN, ORANGE],
+        }
+    }
+
+    /// 色を使わず太字・反転で区別する
+    fn monochrome() -> Theme {
+        Theme {
+            active: Color::Reset,
+            waiting: Color::Reset,
+            stopped: Color::Reset,
+            unknown: Color::Reset,
+            accent: Color::Reset,
+            text: Color::Reset,
+            subtle: Color::Reset,
+            muted: Color::Reset,
+            warning: Color::Reset,
+            escalated: Color::Reset,
+            danger: Color::Reset,
+            ok: Color::Reset,
+            mark: Color::Reset,
+            highlight: Style::default().add_modifier(Modifier::REVERSED),
+            border: Color::Reset,
+            project_colors: &[],
+        }
+    }
+
+    pub fn status_color(&self, status: &SessionStatus) -> Color {
+        match status {
+            SessionStatus::Active => self.active,
+            SessionStatus::Waiting => self.waiting,
+            SessionStatus::Stopped => self.stopped,
+            SessionStatus::Other(_) => self.unknown,
+        }
+    }
+}
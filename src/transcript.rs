@@ -0,0 +1,849 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, NaiveDate};
+use serde::Deserialize;
+use serde_json::value::RawValue;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::SystemTime;
+
+use crate::config::ModelPricing;
+use crate::session::{get_projects_dir, project_dir_for_session};
+
+/// トランスクリプト（~/.claude/projects/<project>/<session_id>.jsonl）の1行
+#[derive(Debug, Deserialize)]
+struct TranscriptEntry {
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    subtype: Option<String>,
+    cwd: Option<String>,
+    timestamp: Option<String>,
+    message: Option<TranscriptMessage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TranscriptMessage {
+    id: Option<String>,
+    model: Option<String>,
+    usage: Option<UsageRecord>,
+    /// サブエージェントの追跡にだけ使うので、必要な行だけ後で解析する
+    content: Option<Box<RawValue>>,
+}
+
+/// 実行中のサブエージェント（Taskツールの呼び出しで、結果がまだないもの）
+#[derive(Debug, Clone, Deserialize)]
+pub struct Subagent {
+    pub description: String,
+    /// "general-purpose" や独自のエージェント名
+    pub subagent_type: Option<String>,
+    /// 呼び出した時刻（RFC 3339）
+    pub started: Option<String>,
+}
+
+impl Subagent {
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "description": self.description,
+            "subagent_type": self.subagent_type,
+            "started": self.started,
+        })
+    }
+}
+
+/// サブエージェントを起動するツール名（新しいバージョンでは Agent）
+const SUBAGENT_TOOLS: &[&str] = &["Task", "Agent"];
+
+/// 1行のcontentから実行中のサブエージェントを更新する（呼び出しで追加、結果で削除、中断ですべて削除）
+fn track_subagents(pending: &mut Vec<(String, Subagent)>, content: &RawValue, timestamp: Option<&str>) {
+    let raw = content.get();
+    if !raw.contains("tool_u") && !raw.contains("[Request interrupted") {
+        return;
+    }
+    let Ok(content) = serde_json::from_str::<serde_json::Value>(raw) else {
+        return;
+    };
+    let blocks = content.as_array().map(Vec::as_slice).unwrap_or_default();
+    for block in blocks {
+        match block["type"].as_str() {
+            Some("tool_use") if SUBAGENT_TOOLS.contains(&block["name"].as_str().unwrap_or_default()) => {
+                let Some(id) = block["id"].as_str() else {
+                    continue;
+                };
+                // ストリーミング中の同じ呼び出しは1回だけ数える
+                if pending.iter().any(|(pending_id, _)| pending_id == id) {
+                    continue;
+                }
+                let input = &block["input"];
+                pending.push((
+                    id.to_string(),
+                    Subagent {
+                        description: input["description"].as_str().unwrap_or("?").to_string(),
+                        subagent_type: input["subagent_type"].as_str().map(str::to_string),
+                        started: timestamp.map(str::to_string),
+                    },
+                ));
+            }
+            Some("tool_result") => {
+                let id = block["tool_use_id"].as_str().unwrap_or_default();
+                pending.retain(|(pending_id, _)| pending_id != id);
+            }
+            Some("text") if block["text"].as_str().is_some_and(|t| t.starts_with("[Request interrupted")) => {
+                pending.clear();
+            }
+            _ => {}
+        }
+    }
+    if content.as_str().is_some_and(|t| t.starts_with("[Request interrupted")) {
+        pending.clear();
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct UsageRecord {
+    #[serde(default)]
+    input_tokens: u64,
+    #[serde(default)]
+    output_tokens: u64,
+    #[serde(default)]
+    cache_creation_input_tokens: u64,
+    #[serde(default)]
+    cache_read_input_tokens: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokenUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    /// 推定コスト（USD、単価の分からないモデルの分は含まない）
+    pub cost_usd: f64,
+    /// 単価の分からないモデルのトークンを含む
+    pub unpriced: bool,
+}
+
+impl TokenUsage {
+    pub fn add(&mut self, other: &TokenUsage) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_creation_tokens += other.cache_creation_tokens;
+        self.cache_read_tokens += other.cache_read_tokens;
+        self.cost_usd += other.cost_usd;
+        self.unpriced |= other.unpriced;
+    }
+
+    /// キャッシュを含めた入力トークンの合計
+    pub fn total_input(&self) -> u64 {
+        self.input_tokens + self.cache_creation_tokens + self.cache_read_tokens
+    }
+}
+
+/// 単価（USD / 100万トークン）: 入力, 出力, キャッシュ書き込み, キャッシュ読み込み
+const fn price(input: f64, output: f64, cache_write: f64, cache_read: f64) -> ModelPricing {
+    ModelPricing {
+        input,
+        output,
+        cache_write,
+        cache_read,
+    }
+}
+
+/// 組み込みの単価（モデル名に含まれる文字列 → 単価）
+const BUILTIN_PRICING: &[(&str, ModelPricing)] = &[
+    ("claude-opus-4-5", price(5.0, 25.0, 6.25, 0.5)),
+    ("claude-opus-4-1", price(15.0, 75.0, 18.75, 1.5)),
+    ("claude-opus-4-2025", price(15.0, 75.0, 18.75, 1.5)),
+    ("claude-3-opus", price(15.0, 75.0, 18.75, 1.5)),
+    ("claude-sonnet-4-5", price(3.0, 15.0, 3.75, 0.3)),
+    ("claude-sonnet-4-2025", price(3.0, 15.0, 3.75, 0.3)),
+    ("claude-3-7-sonnet", price(3.0, 15.0, 3.75, 0.3)),
+    ("claude-3-5-sonnet", price(3.0, 15.0, 3.75, 0.3)),
+    ("claude-haiku-4-5", price(1.0, 5.0, 1.25, 0.1)),
+    ("claude-3-5-haiku", price(0.8, 4.0, 1.0, 0.08)),
+    ("claude-3-haiku", price(0.25, 1.25, 0.3, 0.03)),
+];
+
+static PRICING: OnceLock<BTreeMap<String, ModelPricing>> = OnceLock::new();
+
+/// 起動時に [pricing] の設定を反映する（以降は変更しない）
+pub fn init_pricing(pricing: &BTreeMap<String, ModelPricing>) {
+    let _ = PRICING.set(pricing.clone());
+}
+
+/// モデルの単価（[pricing] → 組み込みの順に、モデル名に含まれる一番長いキーのもの。分からなければNone）
+fn model_pricing(model: &str) -> Option<ModelPricing> {
+    let configured = PRICING
+        .get()
+        .and_then(|pricing| {
+            pricing
+                .iter()
+                .filter(|(key, _)| model.contains(key.as_str()))
+                .max_by_key(|(key, _)| key.len())
+        })
+        .map(|(_, pricing)| *pricing);
+    configured.or_else(|| {
+        BUILTIN_PRICING
+            .iter()
+            .filter(|(key, _)| model.contains(key))
+            .max_by_key(|(key, _)| key.len())
+            .map(|&(_, pricing)| pricing)
+    })
+}
+
+/// モデルのコンテキストウィンドウの大きさ（トークン）
+pub fn context_window(model: Option<&str>) -> u64 {
+    // "claude-sonnet-4-5[1m]" のように100万トークンの版は名前に付く
+    if model.is_some_and(|m| m.contains("[1m]")) {
+        1_000_000
+    } else {
+        200_000
+    }
+}
+
+/// 一覧に出すモデルの系統（opus / sonnet / haiku、どれでもなければモデル名のまま）
+pub fn model_family(model: &str) -> &str {
+    ["opus", "sonnet", "haiku"]
+        .into_iter()
+        .find(|family| model.contains(family))
+        .unwrap_or(model)
+}
+
+fn usage_from_record(record: &UsageRecord, model: &str) -> TokenUsage {
+    let usage = TokenUsage {
+        input_tokens: record.input_tokens,
+        output_tokens: record.output_tokens,
+        cache_creation_tokens: record.cache_creation_input_tokens,
+        cache_read_tokens: record.cache_read_input_tokens,
+        ..TokenUsage::default()
+    };
+    match model_pricing(model) {
+        Some(price) => TokenUsage {
+            cost_usd: (record.input_tokens as f64 * price.input
+                + record.output_tokens as f64 * price.output
+                + record.cache_creation_input_tokens as f64 * price.cache_write
+                + record.cache_read_input_tokens as f64 * price.cache_read)
+                / 1_000_000.0,
+            ..usage
+        },
+        // "<synthetic>" の応答のように使用量が0なら単価が分からなくても構わない
+        None => TokenUsage {
+            unpriced: usage.total_input() + usage.output_tokens > 0,
+            ..usage
+        },
+    }
+}
+
+/// 会話の発言者
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    User,
+    Assistant,
+    /// アシスタントのツール呼び出し
+    ToolUse,
+    /// ツールの実行結果（userメッセージとして記録される）
+    ToolResult,
+}
+
+impl Role {
+    /// JSONやMCPで使う名前
+    pub fn name(self) -> &'static str {
+        match self {
+            Role::User => "user",
+            Role::Assistant => "assistant",
+            Role::ToolUse => "tool_use",
+            Role::ToolResult => "tool_result",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Role> {
+        [Role::User, Role::Assistant, Role::ToolUse, Role::ToolResult]
+            .into_iter()
+            .find(|r| r.name() == name)
+    }
+
+    /// 画面に出す名前
+    pub fn label(self) -> &'static str {
+        match self {
+            Role::User => "ユーザー",
+            Role::Assistant => "Claude",
+            Role::ToolUse => "ツール",
+            Role::ToolResult => "結果",
+        }
+    }
+}
+
+/// トランスクリプトビューアで表示する1発言
+#[derive(Debug, Clone)]
+pub struct ConversationMessage {
+    pub role: Role,
+    pub text: String,
+    pub timestamp: Option<String>,
+}
+
+/// 会話の読み込み用（トークン集計とは別に本文も読む）
+#[derive(Debug, Deserialize)]
+struct ConversationEntry {
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    timestamp: Option<String>,
+    message: Option<ConversationRecord>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ConversationRecord {
+    #[serde(default)]
+    content: serde_json::Value,
+}
+
+/// ツール呼び出しの入力から1行の説明を作る（コマンドやパスなど代表的な値を優先）
+pub fn describe_tool_input(input: &serde_json::Value) -> String {
+    ["command", "file_path", "path", "pattern", "url", "description"]
+        .iter()
+        .find_map(|key| input[key].as_str())
+        .map(str::to_string)
+        .unwrap_or_else(|| input.to_string())
+}
+
+/// tool_resultのcontent（文字列またはtextブロックの配列）を文字列にする
+fn tool_result_text(content: &serde_json::Value) -> String {
+    match content {
+        serde_json::Value::String(text) => text.clone(),
+        serde_json::Value::Array(blocks) => blocks
+            .iter()
+            .filter_map(|b| b["text"].as_str())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+/// 1行分のcontentを発言に分解する（thinkingブロックは表示しない）
+fn split_content(kind: &str, content: &serde_json::Value) -> Vec<(Role, String)> {
+    let role = if kind == "assistant" { Role::Assistant } else { Role::User };
+    match content {
+        serde_json::Value::String(text) => vec![(role, text.clone())],
+        serde_json::Value::Array(blocks) => blocks
+            .iter()
+            .filter_map(|block| match block["type"].as_str()? {
+                "text" => Some((role, block["text"].as_str()?.to_string())),
+                "tool_use" => Some((
+                    Role::ToolUse,
+                    format!(
+                        "{}: {}",
+                        block["name"].as_str().unwrap_or("?"),
+                        describe_tool_input(&block["input"])
+                    ),
+                )),
+                "tool_result" => Some((Role::ToolResult, tool_result_text(&block["content"]))),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// トランスクリプトの1行を発言にする（会話以外の行は空）
+pub fn conversation_messages(line: &str) -> Vec<ConversationMessage> {
+    let Ok(entry) = serde_json::from_str::<ConversationEntry>(line) else {
+        return Vec::new();
+    };
+    let (Some(kind), Some(message)) = (entry.kind, entry.message) else {
+        return Vec::new();
+    };
+    if kind != "user" && kind != "assistant" {
+        return Vec::new();
+    }
+    split_content(&kind, &message.content)
+        .into_iter()
+        .filter(|(_, text)| !text.trim().is_empty())
+        .map(|(role, text)| ConversationMessage {
+            role,
+            text,
+            timestamp: entry.timestamp.clone(),
+        })
+        .collect()
+}
+
+/// トランスクリプトから会話（ユーザー・アシスタント・ツール）を読み込む
+pub fn load_conversation(path: &Path) -> Result<Vec<ConversationMessage>> {
+    let file = File::open(path).with_context(|| format!("トランスクリプト読み込みエラー: {:?}", path))?;
+
+    let mut messages = Vec::new();
+    for line in BufReader::new(file).lines() {
+        messages.extend(conversation_messages(&line?));
+    }
+    Ok(messages)
+}
+
+pub fn transcript_path(cwd: &str, session_id: &str) -> Result<PathBuf> {
+    Ok(project_dir_for_session(cwd, session_id)?.join(format!("{}.jsonl", session_id)))
+}
+
+/// 会話が始まった時刻（トランスクリプトで最初にタイムスタンプのある行）
+pub fn session_started_at(path: &Path) -> Option<SystemTime> {
+    let file = File::open(path).ok()?;
+    BufReader::new(file).lines().map_while(Result::ok).find_map(|line| {
+        let entry: ConversationEntry = serde_json::from_str(&line).ok()?;
+        let time = chrono::DateTime::parse_from_rfc3339(entry.timestamp.as_deref()?).ok()?;
+        Some(SystemTime::from(time))
+    })
+}
+
+/// 会話ログの末尾から推定したセッションの状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TranscriptState {
+    /// ユーザーの発言やツールの結果の後で、Claudeが応答を作っている
+    Working,
+    /// ツールの呼び出しに結果がまだない（実行中か承認待ち）
+    ToolPending,
+    /// アシスタントの発言で終わっている、または中断された
+    Finished,
+}
+
+/// 状態の推定に読む末尾のバイト数（大きなツール結果の行が収まる程度）
+const TAIL_BYTES: u64 = 256 * 1024;
+
+/// 実行中のツールの1行の説明（"Bash: cargo test"、複数行のコマンドは1行目だけ）
+pub fn describe_tool_use(name: &str, input: &serde_json::Value) -> String {
+    let description = describe_tool_input(input);
+    format!("{}: {}", name, description.lines().next().unwrap_or_default())
+}
+
+/// 結果を待っているツール呼び出し
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolCall {
+    pub name: String,
+    pub input: serde_json::Value,
+}
+
+impl ToolCall {
+    pub fn describe(&self) -> String {
+        describe_tool_use(&self.name, &self.input)
+    }
+}
+
+/// 結果がまだないツール呼び出し（同時に複数あれば最後のもの）
+fn pending_tool_of(entry: &ConversationEntry) -> Option<ToolCall> {
+    let blocks = entry.message.as_ref()?.content.as_array()?;
+    let block = blocks.iter().rev().find(|b| b["type"] == "tool_use")?;
+    Some(ToolCall {
+        name: block["name"].as_str().unwrap_or("?").to_string(),
+        input: block["input"].clone(),
+    })
+}
+
+/// アシスタントの発言の最後のテキスト（空白と改行は1つの空白にまとめる）
+fn assistant_text_of(entry: &ConversationEntry) -> Option<String> {
+    if entry.kind.as_deref() != Some("assistant") {
+        return None;
+    }
+    let blocks = entry.message.as_ref()?.content.as_array()?;
+    let text = blocks
+        .iter()
+        .rev()
+        .filter(|b| b["type"] == "text")
+        .filter_map(|b| b["text"].as_str())
+        .find(|text| !text.trim().is_empty())?;
+    Some(text.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+/// 会話ログの1行から状態を推定する（会話以外の行はNone）
+fn entry_state(entry: &ConversationEntry) -> Option<TranscriptState> {
+    let content = &entry.message.as_ref()?.content;
+    let blocks = content.as_array().map(Vec::as_slice).unwrap_or_default();
+    let has_block = |kind: &str| blocks.iter().any(|b| b["type"] == kind);
+    match entry.kind.as_deref()? {
+        "assistant" if has_block("tool_use") => Some(TranscriptState::ToolPending),
+        "assistant" => Some(TranscriptState::Finished),
+        "user" => {
+            let text = content.as_str().map(str::to_string).unwrap_or_else(|| tool_result_text(content));
+            if text.starts_with("[Request interrupted by user") {
+                Some(TranscriptState::Finished)
+            } else {
+                Some(TranscriptState::Working)
+            }
+        }
+        _ => None,
+    }
+}
+
+/// 会話ログの末尾から推定した状態と、結果を待っているツール
+#[derive(Debug, Clone)]
+struct TailState {
+    state: TranscriptState,
+    pending_call: Option<ToolCall>,
+    /// 末尾にあるアシスタントの最後のテキスト
+    last_text: Option<String>,
+}
+
+/// 会話ログの末尾の数行から状態を推定する
+fn read_transcript_state(path: &Path) -> Result<Option<TailState>> {
+    let mut file = File::open(path).with_context(|| format!("トランスクリプト読み込みエラー: {:?}", path))?;
+    let len = file.metadata()?.len();
+    let offset = len.saturating_sub(TAIL_BYTES);
+    file.seek(SeekFrom::Start(offset))?;
+    let mut tail = Vec::new();
+    file.read_to_end(&mut tail)?;
+
+    let tail = String::from_utf8_lossy(&tail);
+    let mut lines: Vec<&str> = tail.lines().collect();
+    // 途中から読んだときの先頭は行の途中なので捨てる
+    if offset > 0 && !lines.is_empty() {
+        lines.remove(0);
+    }
+    let mut found = None;
+    let mut last_text = None;
+    for entry in lines.iter().rev().filter_map(|line| serde_json::from_str::<ConversationEntry>(line).ok()) {
+        if found.is_none()
+            && let Some(state) = entry_state(&entry)
+        {
+            let pending_call = (state == TranscriptState::ToolPending).then(|| pending_tool_of(&entry)).flatten();
+            found = Some((state, pending_call));
+        }
+        if last_text.is_none() {
+            last_text = assistant_text_of(&entry);
+        }
+        if found.is_some() && last_text.is_some() {
+            break;
+        }
+    }
+    Ok(found.map(|(state, pending_call)| TailState {
+        state,
+        pending_call,
+        last_text,
+    }))
+}
+
+type StateCache = BTreeMap<PathBuf, (SystemTime, u64, Option<TailState>)>;
+
+static STATE_CACHE: Mutex<StateCache> = Mutex::new(BTreeMap::new());
+
+/// 会話ログの末尾の状態と最終更新時刻（mtimeとサイズが変わらなければキャッシュを返す）
+fn tail_state(path: &Path) -> Option<(TailState, SystemTime)> {
+    let metadata = fs::metadata(path).ok()?;
+    let mtime = metadata.modified().ok()?;
+    let size = metadata.len();
+
+    let mut cache = STATE_CACHE.lock().ok()?;
+    if let Some((cached_mtime, cached_size, state)) = cache.get(path)
+        && *cached_mtime == mtime
+        && *cached_size == size
+    {
+        return state.clone().map(|state| (state, mtime));
+    }
+
+    let state = read_transcript_state(path)
+        .inspect_err(|e| tracing::debug!("{:#}", e))
+        .ok()
+        .flatten();
+    cache.insert(path.to_path_buf(), (mtime, size, state.clone()));
+    state.map(|state| (state, mtime))
+}
+
+/// 会話ログから推定した状態と最終更新時刻
+pub fn transcript_state(path: &Path) -> Option<(TranscriptState, SystemTime)> {
+    tail_state(path).map(|(tail, mtime)| (tail.state, mtime))
+}
+
+/// 会話ログで結果を待っているツールの説明と最終更新時刻（ツールを待っていなければ説明はNone）
+pub fn pending_tool(path: &Path) -> Option<(Option<String>, SystemTime)> {
+    tail_state(path).map(|(tail, mtime)| (tail.pending_call.map(|call| call.describe()), mtime))
+}
+
+/// 会話ログの末尾で結果を待っているツール呼び出し（許可の確認に入力をそのまま見せる）
+pub fn pending_tool_call(path: &Path) -> Option<ToolCall> {
+    tail_state(path)?.0.pending_call
+}
+
+/// 会話ログの末尾にあるアシスタントの最後のテキスト
+pub fn last_assistant_text(path: &Path) -> Option<String> {
+    tail_state(path)?.0.last_text
+}
+
+/// トランスクリプトを集計した結果
+#[derive(Clone)]
+struct ParsedUsage {
+    usage: TokenUsage,
+    /// 最初に記録されたcwd
+    cwd: Option<String>,
+    /// 最後の応答のモデル（/model で切り替えられるので最新を使う）
+    model: Option<String>,
+    /// 日（ローカル時刻）ごとの推定コスト（USD）
+    daily_cost: BTreeMap<NaiveDate, f64>,
+    /// 次の応答に渡る文脈の大きさ（最後の応答の入力と出力、圧縮後は次の応答までNone）
+    context_tokens: Option<u64>,
+    /// 実行中のサブエージェント（呼び出し順）
+    subagents: Vec<Subagent>,
+}
+
+/// トランスクリプトの集計の途中経過（追記された分だけ読み足す）
+#[derive(Default)]
+struct UsageParser {
+    /// 読み込んだ位置（最後に読んだ行の終わり、バイト）
+    offset: u64,
+    usage: TokenUsage,
+    cwd: Option<String>,
+    last_model: Option<String>,
+    daily_cost: BTreeMap<NaiveDate, f64>,
+    context_tokens: Option<u64>,
+    subagents: Vec<(String, Subagent)>,
+    // ストリーミング中の同一メッセージが複数行に記録されるためidで重複を除く
+    seen_ids: HashSet<String>,
+}
+
+impl UsageParser {
+    /// offset から先の追記分を集計に加える（書きかけの最後の行は次の機会に読む）
+    fn read_appended(&mut self, path: &Path, size: u64) -> Result<()> {
+        let mut file = File::open(path).with_context(|| format!("トランスクリプト読み込みエラー: {:?}", path))?;
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut buf = Vec::new();
+        file.take(size.saturating_sub(self.offset)).read_to_end(&mut buf)?;
+        let end = buf.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+        for line in String::from_utf8_lossy(&buf[..end]).lines() {
+            self.add_line(line);
+        }
+        self.offset += end as u64;
+        Ok(())
+    }
+
+    fn add_line(&mut self, line: &str) {
+        let Ok(entry) = serde_json::from_str::<TranscriptEntry>(line) else {
+            return;
+        };
+
+        if self.cwd.is_none() {
+            self.cwd = entry.cwd;
+        }
+        if entry.kind.as_deref() == Some("system") && entry.subtype.as_deref() == Some("compact_boundary") {
+            self.context_tokens = None;
+        }
+
+        let Some(message) = entry.message else {
+            return;
+        };
+        if let Some(ref content) = message.content {
+            track_subagents(&mut self.subagents, content, entry.timestamp.as_deref());
+        }
+        let Some(record) = message.usage else {
+            return;
+        };
+        // ストリーミング中の行も含めて最後の記録を使う（"<synthetic>" の応答は使用量が0）
+        if !message.model.as_deref().is_some_and(|m| m.starts_with('<')) {
+            self.context_tokens = Some(
+                record.input_tokens
+                    + record.cache_creation_input_tokens
+                    + record.cache_read_input_tokens
+                    + record.output_tokens,
+            );
+        }
+        if let Some(id) = message.id
+            && !self.seen_ids.insert(id)
+        {
+            return;
+        }
+
+        let model = message.model.unwrap_or_default();
+        let record_usage = usage_from_record(&record, &model);
+        self.usage.add(&record_usage);
+        if let Some(time) = entry.timestamp.as_deref().and_then(|t| DateTime::parse_from_rfc3339(t).ok()) {
+            *self.daily_cost.entry(time.with_timezone(&Local).date_naive()).or_insert(0.0) += record_usage.cost_usd;
+        }
+        // API を通らない応答（エラーなど）は "<synthetic>" になる
+        if !model.is_empty() && !model.starts_with('<') {
+            self.last_model = Some(model);
+        }
+    }
+
+    fn result(&self) -> ParsedUsage {
+        ParsedUsage {
+            usage: self.usage,
+            cwd: self.cwd.clone(),
+            model: self.last_model.clone(),
+            daily_cost: self.daily_cost.clone(),
+            context_tokens: self.context_tokens,
+            subagents: self.subagents.iter().map(|(_, subagent)| subagent.clone()).collect(),
+        }
+    }
+}
+
+type UsageCache = HashMap<PathBuf, (SystemTime, u64, UsageParser)>;
+
+fn usage_cache() -> &'static Mutex<UsageCache> {
+    static CACHE: OnceLock<Mutex<UsageCache>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// トランスクリプトを集計する（mtimeとサイズが変わらなければキャッシュを返し、追記されたら続きだけ読む）
+fn load_parsed_usage(path: &Path) -> Option<ParsedUsage> {
+    let metadata = fs::metadata(path).ok()?;
+    let mtime = metadata.modified().ok()?;
+    let size = metadata.len();
+
+    // 読んでいる間もほかのセッションの集計を止めないよう、キャッシュから取り出してロックを離す
+    let cached = {
+        let mut cache = usage_cache().lock().ok()?;
+        if let Some((cached_mtime, cached_size, parser)) = cache.get(path)
+            && *cached_mtime == mtime
+            && *cached_size == size
+        {
+            return Some(parser.result());
+        }
+        cache.remove(path)
+    };
+    let mut parser = match cached {
+        Some((_, _, parser)) if parser.offset <= size => parser,
+        // 縮んだら書き直されたものとして最初から読む
+        _ => UsageParser::default(),
+    };
+    parser
+        .read_appended(path, size)
+        .inspect_err(|e| tracing::debug!("{:#}", e))
+        .ok()?;
+    let parsed = parser.result();
+    usage_cache()
+        .lock()
+        .ok()?
+        .insert(path.to_path_buf(), (mtime, size, parser));
+    Some(parsed)
+}
+
+/// トランスクリプトから分かるセッションの使用状況
+pub struct TranscriptUsage {
+    pub usage: TokenUsage,
+    pub model: Option<String>,
+    pub context_tokens: Option<u64>,
+    pub subagents: Vec<Subagent>,
+}
+
+/// トランスクリプトのトークン使用量・最新のモデル・文脈の大きさを取得
+pub fn load_token_usage(path: &Path) -> Option<TranscriptUsage> {
+    load_parsed_usage(path).map(|parsed| TranscriptUsage {
+        usage: parsed.usage,
+        model: parsed.model,
+        context_tokens: parsed.context_tokens,
+        subagents: parsed.subagents,
+    })
+}
+
+/// 今日（ローカル時刻）の全トランスクリプトの推定コスト（USD）
+pub fn cost_today() -> Result<f64> {
+    let projects_dir = get_projects_dir()?;
+    if !projects_dir.exists() {
+        return Ok(0.0);
+    }
+    let today = Local::now().date_naive();
+    let mut total = 0.0;
+    for entry in fs::read_dir(&projects_dir).context("プロジェクトディレクトリの読み込みに失敗")? {
+        let project_dir = entry?.path();
+        if !project_dir.is_dir() {
+            continue;
+        }
+        for file in fs::read_dir(&project_dir)? {
+            let path = file?.path();
+            if path.extension().and_then(|s| s.to_str()) != Some("jsonl") {
+                continue;
+            }
+            // 今日更新されていないファイルには今日の記録がない
+            let modified_today = fs::metadata(&path)
+                .and_then(|m| m.modified())
+                .is_ok_and(|mtime| DateTime::<Local>::from(mtime).date_naive() == today);
+            if !modified_today {
+                continue;
+            }
+            if let Some(cost) = load_parsed_usage(&path).and_then(|parsed| parsed.daily_cost.get(&today).copied()) {
+                total += cost;
+            }
+        }
+    }
+    Ok(total)
+}
+
+pub struct ProjectUsage {
+    pub project: String,
+    pub session_count: usize,
+    pub usage: TokenUsage,
+}
+
+/// ~/.claude/projects 配下の全トランスクリプトをプロジェクトごとに集計
+pub fn summarize_usage_by_project() -> Result<Vec<ProjectUsage>> {
+    let projects_dir = get_projects_dir()?;
+    if !projects_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut summaries = Vec::new();
+    for entry in fs::read_dir(&projects_dir).context("プロジェクトディレクトリの読み込みに失敗")? {
+        let project_dir = entry?.path();
+        if !project_dir.is_dir() {
+            continue;
+        }
+
+        let mut summary = ProjectUsage {
+            project: project_dir
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            session_count: 0,
+            usage: TokenUsage::default(),
+        };
+        let mut project_cwd = None;
+
+        for file in fs::read_dir(&project_dir)? {
+            let path = file?.path();
+            if path.extension().and_then(|s| s.to_str()) != Some("jsonl") {
+                continue;
+            }
+            let Some(parsed) = load_parsed_usage(&path) else {
+                continue;
+            };
+            summary.session_count += 1;
+            summary.usage.add(&parsed.usage);
+            if project_cwd.is_none() {
+                project_cwd = parsed.cwd;
+            }
+        }
+
+        if summary.session_count == 0 {
+            continue;
+        }
+        // トランスクリプトに記録されたcwdがあればそれをプロジェクト名にする
+        if let Some(cwd) = project_cwd {
+            summary.project = cwd;
+        }
+        summaries.push(summary);
+    }
+
+    summaries.sort_by(|a, b| b.usage.cost_usd.total_cmp(&a.usage.cost_usd));
+    Ok(summaries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(input_tokens: u64, output_tokens: u64) -> UsageRecord {
+        serde_json::from_value(serde_json::json!({"input_tokens": input_tokens, "output_tokens": output_tokens})).unwrap()
+    }
+
+    #[test]
+    fn builtin_pricing_matches_dated_model_names() {
+        assert_eq!(model_pricing("claude-sonnet-4-5-20250929"), Some(price(3.0, 15.0, 3.75, 0.3)));
+        assert_eq!(model_pricing("claude-opus-4-1-20250805"), Some(price(15.0, 75.0, 18.75, 1.5)));
+        assert_eq!(model_pricing("claude-opus-4-5-20251101"), Some(price(5.0, 25.0, 6.25, 0.5)));
+    }
+
+    #[test]
+    fn unknown_model_is_unpriced_instead_of_guessed() {
+        assert_eq!(model_pricing("claude-opus-9"), None);
+        let usage = usage_from_record(&record(1000, 100), "claude-opus-9");
+        assert!(usage.unpriced);
+        assert_eq!(usage.cost_usd, 0.0);
+    }
+
+    #[test]
+    fn synthetic_message_without_tokens_is_not_unpriced() {
+        assert!(!usage_from_record(&record(0, 0), "<synthetic>").unpriced);
+    }
+}
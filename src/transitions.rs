@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::session::Session;
+use crate::status::SessionStatus;
+
+/// セッションのステータス変化（from: None は新規、to: None は消滅）
+#[derive(Debug, Clone)]
+pub struct Transition {
+    pub session: Session,
+    pub from: Option<SessionStatus>,
+    pub to: Option<SessionStatus>,
+    /// 変化前のステータスになった時刻（フックが書き込んだupdated）
+    pub from_updated: Option<u64>,
+    pub timestamp: u64,
+}
+
+impl Transition {
+    pub fn old_label(&self) -> &str {
+        self.from.as_ref().map_or("new", SessionStatus::as_str)
+    }
+
+    pub fn new_label(&self) -> &str {
+        self.to.as_ref().map_or("gone", SessionStatus::as_str)
+    }
+
+    /// "active->waiting" 形式のパターンに一致するか（"*" は任意のステータス）
+    pub fn matches(&self, pattern: &str) -> bool {
+        let Some((from, to)) = pattern.split_once("->") else {
+            return false;
+        };
+        let (from, to) = (from.trim(), to.trim());
+        (from == "*" || from == self.old_label()) && (to == "*" || to == self.new_label())
+    }
+}
+
+/// 前回の一覧と比較してステータス変化を検出する
+#[derive(Default)]
+pub struct StatusTracker {
+    previous: Option<HashMap<String, Session>>,
+}
+
+impl StatusTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 最新の一覧を渡して変化を返す（初回は基準にするだけで何も返さない）
+    pub fn update(&mut self, sessions: &[Session]) -> Vec<Transition> {
+        let current: HashMap<String, Session> = sessions
+            .iter()
+            .map(|s| (s.session_id.clone(), s.clone()))
+            .collect();
+
+        let Some(previous) = self.previous.replace(current.clone()) else {
+            return Vec::new();
+        };
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        let mut transitions = Vec::new();
+        for session in sessions {
+            let before = previous.get(&session.session_id);
+            let from = before.map(|s| s.status.clone());
+            if from.as_ref() != Some(&session.status) {
+                transitions.push(Transition {
+                    session: session.clone(),
+                    from,
+                    to: Some(session.status.clone()),
+                    from_updated: before.map(|s| s.updated),
+                    timestamp,
+                });
+            }
+        }
+        for (session_id, session) in previous {
+            if !current.contains_key(&session_id) {
+                transitions.push(Transition {
+                    from: Some(session.status.clone()),
+                    from_updated: Some(session.updated),
+                    session,
+                    to: None,
+                    timestamp,
+                });
+            }
+        }
+
+        transitions
+    }
+}
@@ -0,0 +1,168 @@
+use anyhow::{anyhow, Result};
+use std::io::Read;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::OnceLock;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::alerts::{expand_placeholders, shell_command, shell_quote};
+use crate::config::{get_config_path, TriggersConfig};
+use crate::status::SessionStatus;
+use crate::transitions::Transition;
+
+// ステータス変化で利用者のコマンドを実行し、独自の連携を作れるようにする（[triggers]）。
+
+/// コマンドを実行するきっかけ
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Event {
+    /// このステータスになった
+    Status(SessionStatus),
+    /// セッションが現れた
+    New,
+    /// セッションが消えた
+    Gone,
+}
+
+impl Event {
+    fn parse(key: &str) -> Result<Event> {
+        let name = key
+            .strip_prefix("on_")
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| anyhow!("[triggers] の不明な設定: {}（on_<ステータス>、on_new、on_gone、timeout のいずれか）", key))?;
+        Ok(match name {
+            "new" => Event::New,
+            "gone" => Event::Gone,
+            status => Event::Status(SessionStatus::parse(status)),
+        })
+    }
+
+    fn matches(&self, transition: &Transition) -> bool {
+        match self {
+            Event::Status(status) => transition.to.as_ref() == Some(status),
+            Event::New => transition.from.is_none(),
+            Event::Gone => transition.to.is_none(),
+        }
+    }
+}
+
+struct Triggers {
+    commands: Vec<(Event, String)>,
+    timeout: Duration,
+    /// 相対パスのスクリプトを設定ファイルのディレクトリから探す
+    dir: Option<PathBuf>,
+}
+
+static TRIGGERS: OnceLock<Triggers> = OnceLock::new();
+
+/// 起動時に [triggers] の設定を反映する（以降は変更しない）
+pub fn init_triggers(config: &TriggersConfig) -> Result<()> {
+    let commands = config
+        .commands
+        .iter()
+        .map(|(key, command)| Ok((Event::parse(key)?, command.clone())))
+        .collect::<Result<Vec<_>>>()?;
+    let dir = get_config_path()
+        .ok()
+        .and_then(|path| path.parent().map(PathBuf::from))
+        .filter(|dir| dir.is_dir());
+    let _ = TRIGGERS.set(Triggers {
+        commands,
+        timeout: Duration::from_secs(config.timeout),
+        dir,
+    });
+    Ok(())
+}
+
+/// 出力を最後の数行だけにする（ログ用）
+fn tail(output: &str) -> String {
+    let lines: Vec<&str> = output.trim_end().lines().collect();
+    lines[lines.len().saturating_sub(5)..].join(" / ")
+}
+
+/// コマンドを実行し、終了かタイムアウトまで待ってログに残す
+fn run(command: &str, timeout: Duration, dir: Option<&PathBuf>) {
+    let mut cmd = shell_command(command);
+    if let Some(dir) = dir {
+        cmd.current_dir(dir);
+    }
+    let mut child = match cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => {
+            tracing::warn!(command, "トリガーのコマンドを実行できません: {}", e);
+            return;
+        }
+    };
+    // パイプが詰まらないよう出力は別スレッドで読む
+    let readers: Vec<_> = [
+        child.stdout.take().map(|r| Box::new(r) as Box<dyn Read + Send>),
+        child.stderr.take().map(|r| Box::new(r) as Box<dyn Read + Send>),
+    ]
+    .into_iter()
+    .flatten()
+    .map(|mut reader| {
+        thread::spawn(move || {
+            let mut output = String::new();
+            let _ = reader.read_to_string(&mut output);
+            output
+        })
+    })
+    .collect();
+
+    let started = Instant::now();
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Some(status),
+            Ok(None) if started.elapsed() >= timeout => {
+                let _ = child.kill();
+                let _ = child.wait();
+                break None;
+            }
+            Ok(None) => thread::sleep(Duration::from_millis(100)),
+            Err(e) => {
+                tracing::warn!(command, "トリガーのコマンドの終了を待てません: {}", e);
+                return;
+            }
+        }
+    };
+    let Some(status) = status else {
+        // 孫プロセスがパイプを持ったまま残ることがあるので出力は待たない
+        tracing::warn!(command, timeout_secs = timeout.as_secs(), "トリガーのコマンドがタイムアウトしたため終了させた");
+        return;
+    };
+    let output: Vec<String> = readers.into_iter().map(|r| r.join().unwrap_or_default()).collect();
+    let output = tail(&output.concat());
+    let elapsed_ms = started.elapsed().as_millis() as u64;
+
+    if status.success() {
+        tracing::info!(command, elapsed_ms, %output, "トリガーのコマンドが完了");
+    } else {
+        tracing::warn!(command, elapsed_ms, %status, %output, "トリガーのコマンドが失敗");
+    }
+}
+
+/// ステータス変化に一致するトリガーのコマンドをバックグラウンドで実行する
+pub fn run_triggers(transitions: &[Transition]) {
+    let Some(triggers) = TRIGGERS.get() else {
+        return;
+    };
+    for transition in transitions {
+        for (event, template) in &triggers.commands {
+            if !event.matches(transition) {
+                continue;
+            }
+            let command = expand_placeholders(template, &transition.session)
+                .replace("{from}", &shell_quote(transition.old_label()))
+                .replace("{to}", &shell_quote(transition.new_label()));
+            tracing::debug!(?event, %command, "トリガーのコマンドを実行");
+            let timeout = triggers.timeout;
+            let dir = triggers.dir.clone();
+            thread::spawn(move || run(&command, timeout, dir.as_ref()));
+        }
+    }
+}
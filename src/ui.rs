@@ -1,61 +1,790 @@
 use anyhow::Result;
 use crossterm::{
-    event::{self, Event, KeyCode, KeyEvent},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyModifiers,
+        MouseButton, MouseEvent, MouseEventKind,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{
     backend::CrosstermBackend,
-    layout::{Constraint, Direction, Layout},
+    layout::{Alignment, Constraint, Direction, Layout, Margin, Rect},
     style::{Color, Modifier, Style},
-    text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    symbols::border,
+    text::{Line, Span, Text},
+    widgets::{
+        Bar, BarChart, BarGroup, Block, Borders, Cell, Clear, Paragraph, Row, Scrollbar, ScrollbarOrientation,
+        ScrollbarState, Table, TableState, Tabs,
+    },
     Frame, Terminal,
 };
+use std::collections::HashSet;
+use std::fs;
 use std::io;
-use std::time::{Duration, Instant};
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime};
 
+use crate::alerts::{ring_on_new_waiting, AlertEngine};
+use crate::budget::{check_budgets, daily_budget, refresh_daily_cost, session_budget, BudgetLevel};
+use crate::clipboard::{copy_to_clipboard, CopyField};
+use crate::columns::{table_columns, Column};
+use crate::config::{Config, TimerConfig};
 use crate::display::{
-    format_cwd, format_relative_time, get_status_color, get_status_icon, get_status_label,
-    simplify_notification_message, truncate_text,
+    ascii_mode, elapsed_since, glyph, tree, format_cwd, format_datetime, format_duration, format_relative_time, format_absolute_time, format_clock_time, status_elapsed, format_dir_name, format_memory, format_message_time, format_token_usage, format_git_status, format_context_gauge, format_tokens,
+    truncate_text,
 };
-use crate::session::{enrich_sessions_with_index, filter_active_sessions, load_sessions, Session};
+use crate::keybindings::{Action, KeyBindings};
+use crate::latency::average_latency_today;
+use crate::status::{custom_statuses, SessionStatus};
+use crate::stats::Stats;
+use crate::editor::open_in_editor;
+use crate::email::dispatch_email;
+use crate::ipc::DaemonClient;
+use crate::git::{apply_cached_git_status, load_diff};
+use crate::projects::is_hidden_by_project;
+use crate::push::dispatch_push;
+use crate::search::{search_transcripts, SearchResult};
+use crate::remote::{count_by_host, group_by_host, jump_to_remote_pane, RemotePoller};
+use crate::store::EventStore;
+use crate::state::{load_state, update_state, SessionNote, State};
+use crate::theme::theme;
+use crate::transcript::{
+    describe_tool_input, load_conversation, pending_tool_call, session_started_at, transcript_path, ConversationMessage, Role,
+    ToolCall,
+};
+use crate::transitions::{StatusTracker, Transition};
+use crate::triggers::run_triggers;
+use crate::watcher::SessionWatcher;
+use crate::webhook::dispatch_webhooks;
+use crate::session::{
+    last_load_errors, load_active_sessions, load_history, load_session_file, load_orphaned_sessions, load_projects, set_session_status, HistoryEntry,
+    LoadError, PaneConflict, ProjectSummary, Session,
+};
+use crate::backend::{
+    answer_permission, backend_unavailable, can_control_panes, current_backend, interrupt_pane, new_session,
+    resolve_project_dir, resume_session, send_prompt,
+};
+
+/// 確認ダイアログで承認待ちの操作
+#[derive(Debug, Clone)]
+enum PendingAction {
+    /// (pane_id, cwd) の一覧
+    Interrupt(Vec<(String, String)>),
+    Clean(Vec<PathBuf>),
+    /// 承認待ちのペインへの許可（true）/ 拒否（false）
+    Answer(bool, Vec<AnswerTarget>),
+}
+
+/// 許可 / 拒否するセッション（確認ダイアログを開いたときの状態）
+#[derive(Debug, Clone)]
+struct AnswerTarget {
+    session_id: String,
+    pane_id: String,
+    cwd: String,
+    path: PathBuf,
+    updated: u64,
+    /// 確認ダイアログに見せたツール呼び出し（会話ログから読めなければNone）
+    call: Option<ToolCall>,
+}
+
+impl AnswerTarget {
+    fn new(session: &Session) -> Self {
+        Self {
+            session_id: session.session_id.clone(),
+            pane_id: session.pane_id.clone(),
+            cwd: session.cwd.clone(),
+            path: session.path.clone(),
+            updated: session.updated,
+            call: transcript_path(&session.cwd, &session.session_id)
+                .ok()
+                .and_then(|path| pending_tool_call(&path)),
+        }
+    }
+
+    /// まだ同じツール呼び出しで承認待ちか（別の確認に進んでいたら見ていないものを許可してしまう）
+    fn is_unchanged(&self) -> bool {
+        let Ok(session) = load_session_file(self.path.clone()) else {
+            return false;
+        };
+        let call = transcript_path(&self.cwd, &self.session_id)
+            .ok()
+            .and_then(|path| pending_tool_call(&path));
+        session.session_id == self.session_id
+            && session.status == SessionStatus::Waiting
+            && session.updated == self.updated
+            && call == self.call
+    }
+}
+
+impl PendingAction {
+    fn prompt(&self) -> String {
+        match self {
+            PendingAction::Interrupt(targets) => match targets.as_slice() {
+                [(pane_id, cwd)] => {
+                    format!("pane {} ({}) にCtrl-Cを送信しますか？", pane_id, format_cwd(cwd))
+                }
+                _ => format!("{}個のセッションにCtrl-Cを送信しますか？", targets.len()),
+            },
+            PendingAction::Clean(paths) => {
+                format!("{}個のセッションファイルを削除しますか？", paths.len())
+            }
+            PendingAction::Answer(approve, targets) => {
+                let verb = if *approve { "許可" } else { "拒否" };
+                match targets.as_slice() {
+                    [target] => format!(
+                        "pane {} ({}) のツール実行を{}しますか？",
+                        target.pane_id,
+                        format_cwd(&target.cwd),
+                        verb
+                    ),
+                    _ => format!("{}個のセッションのツール実行を{}しますか？", targets.len(), verb),
+                }
+            }
+        }
+    }
+}
+
+/// はい / いいえ を選ぶ確認ダイアログ
+///
+/// y / n で即決、←→ / Tab でフォーカスを移して Enter で決定する。
+/// 誤って Enter を押しても実行しないよう、フォーカスは「いいえ」から始まる。
+struct Confirm<T> {
+    prompt: String,
+    // 質問と選択肢の間に出す補足（許可するツール呼び出しの内容など）
+    detail: Vec<Line<'static>>,
+    value: T,
+    yes_focused: bool,
+}
+
+impl<T> Confirm<T> {
+    fn new(prompt: String, value: T) -> Self {
+        Self {
+            prompt,
+            detail: Vec::new(),
+            value,
+            yes_focused: false,
+        }
+    }
+
+    fn with_detail(mut self, detail: Vec<Line<'static>>) -> Self {
+        self.detail = detail;
+        self
+    }
+
+    /// キー入力を処理し、決まったら実行するか（true）キャンセルか（false）を返す
+    fn handle_key(&mut self, code: KeyCode) -> Option<bool> {
+        match code {
+            KeyCode::Char('y') | KeyCode::Char('Y') => Some(true),
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => Some(false),
+            KeyCode::Enter => Some(self.yes_focused),
+            KeyCode::Left | KeyCode::Right | KeyCode::Tab | KeyCode::BackTab | KeyCode::Char('h') | KeyCode::Char('l') => {
+                self.yes_focused = !self.yes_focused;
+                None
+            }
+            _ => None,
+        }
+    }
+
+    fn render(&self, f: &mut Frame) {
+        let button = |label: &'static str, focused: bool, color: Color| {
+            let style = if focused {
+                Style::default().fg(theme().text).bg(color).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(color)
+            };
+            Span::styled(label, style)
+        };
+        let mut lines = vec![Line::from(self.prompt.clone()), Line::from("")];
+        if !self.detail.is_empty() {
+            lines.extend(self.detail.iter().cloned());
+            lines.push(Line::from(""));
+        }
+        lines.extend([
+            Line::from(vec![
+                button(" はい (y) ", self.yes_focused, theme().ok),
+                Span::raw("   "),
+                button(" いいえ (n) ", !self.yes_focused, theme().danger),
+            ])
+            .alignment(Alignment::Center),
+            Line::from(""),
+            Line::styled("←→: 選択  Enter: 決定  Esc: キャンセル", Style::default().fg(theme().subtle)),
+        ]);
+
+        let width = if self.detail.is_empty() { 60 } else { 100 };
+        let area = centered_rect(width, lines.len() as u16 + 2, f.area());
+        let popup = Paragraph::new(lines).block(
+            block()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme().warning))
+                .title("確認"),
+        );
+        f.render_widget(Clear, area);
+        f.render_widget(popup, area);
+    }
+}
+
+/// 選択中のセッションの操作メニュー（`m`）
+struct ActionMenu {
+    items: Vec<Action>,
+    selected: usize,
+}
+
+impl ActionMenu {
+    fn new(tab: Tab) -> Self {
+        let items = match tab {
+            Tab::Sessions => vec![
+                Action::Jump,
+                Action::ViewTranscript,
+                Action::ViewDiff,
+                Action::Resume,
+                Action::Approve,
+                Action::Deny,
+                Action::SendText,
+                Action::Snooze,
+                Action::Interrupt,
+                Action::MarkStopped,
+                Action::CopyId,
+                Action::EditNote,
+                Action::CleanMarked,
+            ],
+            Tab::History => vec![
+                Action::Resume,
+                Action::ViewTranscript,
+                Action::CopyId,
+                Action::CopyResumeCommand,
+                Action::EditNote,
+            ],
+            Tab::Projects => vec![
+                Action::NewSession,
+                Action::Resume,
+                Action::ViewTranscript,
+                Action::OpenEditor,
+                Action::CopyCwd,
+            ],
+        };
+        Self { items, selected: 0 }
+    }
+}
+
+/// メニューでの項目名
+fn menu_label(action: Action) -> &'static str {
+    match action {
+        Action::Jump => "ジャンプ",
+        Action::ViewTranscript => "会話ログを表示",
+        Action::ViewDiff => "差分を表示",
+        Action::Resume => "新しいペインで再開",
+        Action::NewSession => "新しいセッションを起動",
+        Action::Approve => "ツール実行を許可",
+        Action::Deny => "ツール実行を拒否",
+        Action::SendText => "テキストを送信",
+        Action::Interrupt => "中断（Ctrl-C）",
+        Action::MarkStopped => "完了扱いにする",
+        Action::Snooze => "通知をスヌーズ / 再開",
+        Action::CopyId => "IDをコピー",
+        Action::CopyResumeCommand => "再開コマンドをコピー",
+        Action::EditNote => "メモ・タグを編集",
+        Action::CleanMarked => "セッションファイルを削除",
+        other => other.description(),
+    }
+}
+
+/// 自動ジャンプのカウントダウン中のセッション
+struct PendingJump {
+    session_id: String,
+    pane_id: String,
+    cwd: String,
+    deadline: Instant,
+}
+
+/// リモートのペインへはジャンプだけができる
+const REMOTE_UNSUPPORTED: &str = "リモートのセッションは中断できません";
+
+/// ツールの実行結果は長くなりがちなので先頭だけ表示する
+const MAX_TOOL_RESULT_LINES: usize = 8;
+/// ペインがなくなったセッションを読み直す間隔
+const ORPHAN_RELOAD: Duration = Duration::from_secs(5);
+/// 検索画面に出すセッションの最大数
+const SEARCH_LIMIT: usize = 30;
+/// モニターでペインの表示を取り直す間隔
+const MONITOR_REFRESH: Duration = Duration::from_secs(1);
+
+/// 全画面で表示する内容
+enum PagerContent {
+    Conversation(Vec<ConversationMessage>),
+    /// 作業ツリーの差分（sinceがあれば `s` でセッション開始以降の変更に絞れる）
+    Diff {
+        cwd: String,
+        since: Option<SystemTime>,
+        limited: bool,
+        lines: Vec<String>,
+    },
+}
+
+/// トランスクリプト・差分ビューアの状態
+struct PagerView {
+    title: String,
+    content: PagerContent,
+    // 先頭に表示する行（描画時に範囲内に収める）
+    scroll: usize,
+    // 表示幅で折り返した行と、検索用に小文字にしたその本文
+    rows: Vec<Line<'static>>,
+    row_texts: Vec<String>,
+    wrapped_width: u16,
+    page_height: usize,
+    query: String,
+    searching: bool,
+    current_match: Option<usize>,
+}
+
+impl PagerView {
+    fn new(title: String, content: PagerContent) -> Self {
+        // 会話は最新の発言から、差分は先頭から表示する
+        let scroll = match content {
+            PagerContent::Conversation(_) => usize::MAX,
+            PagerContent::Diff { .. } => 0,
+        };
+        Self {
+            title,
+            content,
+            scroll,
+            rows: Vec::new(),
+            row_texts: Vec::new(),
+            wrapped_width: 0,
+            page_height: 0,
+            query: String::new(),
+            searching: false,
+            current_match: None,
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self.content {
+            PagerContent::Conversation(_) => "Transcript",
+            PagerContent::Diff { limited: true, .. } => "Diff (セッション開始以降)",
+            PagerContent::Diff { .. } => "Diff",
+        }
+    }
+
+    /// 表示幅に合わせて行を作り直す（幅が変わったときだけ）
+    fn wrap(&mut self, width: u16) {
+        if width == self.wrapped_width {
+            return;
+        }
+        self.wrapped_width = width;
+        self.rows.clear();
+        self.row_texts.clear();
+
+        let content = std::mem::replace(&mut self.content, PagerContent::Conversation(Vec::new()));
+        match content {
+            PagerContent::Conversation(ref messages) => self.wrap_conversation(messages, width),
+            PagerContent::Diff { ref lines, .. } => self.wrap_diff(lines, width),
+        }
+        self.content = content;
+    }
+
+    fn wrap_conversation(&mut self, messages: &[ConversationMessage], width: u16) {
+        for message in messages {
+            let (icon, label, color) = role_style(message.role);
+            let mut header = vec![Span::styled(
+                format!("{}{}", icon, label),
+                Style::default().fg(color).add_modifier(Modifier::BOLD),
+            )];
+            if let Some(time) = message.timestamp.as_deref().and_then(format_message_time) {
+                header.push(Span::styled(format!("  {}", time), Style::default().fg(theme().muted)));
+            }
+            self.push_row(Line::from(header));
+
+            let mut lines: Vec<&str> = message.text.lines().collect();
+            let mut omitted = 0;
+            if message.role == Role::ToolResult && lines.len() > MAX_TOOL_RESULT_LINES {
+                omitted = lines.len() - MAX_TOOL_RESULT_LINES;
+                lines.truncate(MAX_TOOL_RESULT_LINES);
+            }
+            let style = match message.role {
+                Role::User | Role::Assistant => Style::default().fg(theme().text),
+                Role::ToolUse | Role::ToolResult => Style::default().fg(theme().muted),
+            };
+            for line in lines {
+                for row in wrap_text(&line.replace('\t', "    "), (width as usize).saturating_sub(2)) {
+                    self.push_row(Line::from(Span::styled(format!("  {}", row), style)));
+                }
+            }
+            if omitted > 0 {
+                self.push_row(Line::from(Span::styled(
+                    format!("  … ({}行省略)", omitted),
+                    Style::default().fg(theme().muted),
+                )));
+            }
+            self.push_row(Line::from(""));
+        }
+    }
+
+    fn wrap_diff(&mut self, lines: &[String], width: u16) {
+        if lines.is_empty() {
+            self.push_row(Line::from(Span::styled("変更はありません", Style::default().fg(theme().muted))));
+            return;
+        }
+        for line in lines {
+            let style = diff_line_style(line);
+            for row in wrap_text(&line.replace('\t', "    "), width as usize) {
+                self.push_row(Line::from(Span::styled(row, style)));
+            }
+        }
+    }
+
+    /// 差分の絞り込み（セッション開始以降 / すべて）を切り替えて読み込み直す
+    fn toggle_diff_limit(&mut self) {
+        let PagerContent::Diff { ref cwd, since: Some(since), ref mut limited, ref mut lines } = self.content else {
+            return;
+        };
+        *limited = !*limited;
+        *lines = load_diff(cwd, limited.then_some(since))
+            .unwrap_or_else(|e| vec![format!("差分を取得できません: {:#}", e)]);
+        self.wrapped_width = 0;
+        self.scroll = 0;
+        self.current_match = None;
+    }
+
+    fn push_row(&mut self, line: Line<'static>) {
+        self.row_texts.push(line.to_string().to_lowercase());
+        self.rows.push(line);
+    }
+
+    fn max_scroll(&self) -> usize {
+        self.rows.len().saturating_sub(self.page_height)
+    }
+
+    fn scroll_by(&mut self, delta: isize) {
+        let current = self.scroll.min(self.max_scroll());
+        self.scroll = current.saturating_add_signed(delta).min(self.max_scroll());
+    }
+
+    fn is_match(&self, row: usize, query: &str) -> bool {
+        !query.is_empty() && self.row_texts[row].contains(query)
+    }
+
+    /// 次（前）の一致行までスクロールする
+    fn find(&mut self, forward: bool) {
+        let query = self.query.to_lowercase();
+        let start = self.current_match.unwrap_or(self.scroll.min(self.max_scroll()));
+        let found = if forward {
+            let from = if self.current_match.is_some() { start + 1 } else { start };
+            (from..self.rows.len()).find(|&i| self.is_match(i, &query))
+        } else {
+            (0..start).rev().find(|&i| self.is_match(i, &query))
+        };
+        if let Some(row) = found {
+            self.current_match = Some(row);
+            self.scroll = row;
+        }
+    }
+
+    /// キー入力を処理する（閉じるときはfalseを返す）
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        if self.searching {
+            match key.code {
+                KeyCode::Enter => {
+                    self.searching = false;
+                    self.current_match = None;
+                    self.find(true);
+                }
+                KeyCode::Esc => {
+                    self.searching = false;
+                    self.query.clear();
+                }
+                KeyCode::Backspace => {
+                    self.query.pop();
+                }
+                KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.query.push(c);
+                }
+                _ => {}
+            }
+            return true;
+        }
+
+        let page = self.page_height.max(1) as isize;
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => return false,
+            KeyCode::Down | KeyCode::Char('j') => self.scroll_by(1),
+            KeyCode::Up | KeyCode::Char('k') => self.scroll_by(-1),
+            KeyCode::PageDown | KeyCode::Char(' ') => self.scroll_by(page),
+            KeyCode::PageUp | KeyCode::Char('b') => self.scroll_by(-page),
+            KeyCode::Home | KeyCode::Char('g') => self.scroll = 0,
+            KeyCode::End | KeyCode::Char('G') => self.scroll = usize::MAX,
+            KeyCode::Char('/') => {
+                self.searching = true;
+                self.query.clear();
+            }
+            KeyCode::Char('n') => self.find(true),
+            KeyCode::Char('N') => self.find(false),
+            KeyCode::Char('s') => self.toggle_diff_limit(),
+            _ => {}
+        }
+        true
+    }
+}
+
+/// メモ・タグの入力中の内容
+struct NoteInput {
+    session_id: String,
+    buffer: String,
+}
+
+/// ペインに送るテキストの入力中の内容（`i`）
+struct SendInput {
+    pane_id: String,
+    cwd: String,
+    // 承認待ちの理由（Claudeの質問）
+    prompt: Option<String>,
+    buffer: String,
+}
+
+/// 会話ログの全文検索（`/`）
+struct SearchView {
+    query: String,
+    // 入力中ならtrue（Enterで検索して結果の選択に移る）
+    editing: bool,
+    results: Vec<SearchResult>,
+    selected: usize,
+}
+
+/// 新しいセッションを起動するディレクトリの選択（`N`）
+struct ProjectPicker {
+    // 絞り込みの語（一致する候補がなければそのままパスとして使う）
+    input: String,
+    // 実行中のセッションと履歴のディレクトリ（最近使った順）
+    candidates: Vec<String>,
+    selected: usize,
+}
+
+impl ProjectPicker {
+    /// 入力を含む候補（大文字小文字は区別しない）
+    fn matches(&self) -> Vec<&str> {
+        let input = self.input.trim().to_lowercase();
+        self.candidates
+            .iter()
+            .filter(|c| format_cwd(c).to_lowercase().contains(&input) || c.to_lowercase().contains(&input))
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+/// 選択中のセッションのペインに表示されている内容（`w`）
+struct Monitor {
+    // 最後に取得したペインと、その内容または取得できなかった理由
+    pane_id: Option<String>,
+    content: Result<String, String>,
+    fetched: Option<Instant>,
+}
+
+/// 同じペインを指すセッションファイルの一覧（`!`）
+struct ConflictView {
+    selected: usize,
+}
 
-fn load_and_filter_sessions() -> Result<Vec<Session>> {
-    let all_sessions = load_sessions()?;
-    let mut sessions = filter_active_sessions(all_sessions)?;
-    enrich_sessions_with_index(&mut sessions)?;
-    Ok(sessions)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tab {
+    Sessions,
+    History,
+    // ~/.claude/projects のプロジェクト（起動・再開のランチャー）
+    Projects,
 }
 
 pub struct App {
+    // 取得した全セッションと、タグで絞り込んだ表示用の一覧
+    all_sessions: Vec<Session>,
     sessions: Vec<Session>,
-    state: ListState,
+    state: TableState,
+    tab: Tab,
+    history: Vec<HistoryEntry>,
+    history_state: TableState,
+    // 履歴タブをこのプロジェクトに絞る（プロジェクトタブから開いたとき）
+    history_project: Option<String>,
+    projects: Vec<ProjectSummary>,
+    projects_state: TableState,
+    // フッターに一時的に表示するメッセージ
+    status_message: Option<(String, Instant)>,
     should_quit: bool,
     last_update: Instant,
+    // マウス操作用: 直近に描画したリストの領域と最後のクリック
+    list_area: Rect,
+    last_click: Option<(Instant, usize)>,
+    show_help: bool,
+    pending_action: Option<Confirm<PendingAction>>,
+    action_menu: Option<ActionMenu>,
+    // マーク中のセッションID
+    marked: HashSet<String>,
+    // 読み込めなかったセッションファイル
+    load_errors: Vec<LoadError>,
+    // 経過時間の代わりに時刻を表示する
+    absolute_time: bool,
+    // セッション一覧の各行の下に要約・承認待ちの理由を表示する
+    detailed_list: bool,
+    // 更新のたびに最も新しく更新されたセッションを選択する
+    follow_newest: bool,
+    // ペインがなくなったセッションを一覧の末尾に出す（最後に読み込んだものとその時刻）
+    show_orphans: bool,
+    orphans: Vec<Session>,
+    orphans_loaded: Option<Instant>,
+    // デーモンから一覧を受け取っている
+    daemon_attached: bool,
+    // メモ・タグ（状態ファイル）
+    local_state: State,
+    note_input: Option<NoteInput>,
+    send_input: Option<SendInput>,
+    search: Option<SearchView>,
+    conflict_view: Option<ConflictView>,
+    project_picker: Option<ProjectPicker>,
+    // 詳細の代わりに選択中のペインの表示を映す
+    monitor: Option<Monitor>,
+    tag_filter: Option<String>,
+    // 承認待ちになったセッションへ自動でジャンプするまでの待ち時間（Noneなら無効）
+    auto_jump: Option<Duration>,
+    pending_jump: Option<PendingJump>,
+    pager: Option<PagerView>,
+    // 今日の承認までの平均待ち時間（秒）
+    approval_avg: Option<u64>,
+    keys: KeyBindings,
+    // セッション一覧の列と幅
+    columns: Vec<(Column, Constraint)>,
+    config: Config,
 }
 
 impl App {
-    pub fn new(sessions: Vec<Session>) -> Self {
-        let mut state = ListState::default();
-        if !sessions.is_empty() {
-            state.select(Some(0));
-        }
-
-        Self {
-            sessions,
-            state,
+    pub fn new(
+        mut sessions: Vec<Session>,
+        local_state: State,
+        keys: KeyBindings,
+        columns: Vec<(Column, Constraint)>,
+        config: Config,
+        auto_jump: Option<Duration>,
+    ) -> Self {
+        local_state.apply_snoozes(&mut sessions);
+        let mut app = Self {
+            all_sessions: sessions,
+            sessions: Vec::new(),
+            state: TableState::default(),
+            tab: Tab::Sessions,
+            history: Vec::new(),
+            history_state: TableState::default(),
+            history_project: None,
+            projects: Vec::new(),
+            projects_state: TableState::default(),
+            status_message: None,
             should_quit: false,
             last_update: Instant::now(),
-        }
+            list_area: Rect::default(),
+            last_click: None,
+            show_help: false,
+            pending_action: None,
+            action_menu: None,
+            marked: HashSet::new(),
+            load_errors: last_load_errors(),
+            absolute_time: false,
+            detailed_list: false,
+            follow_newest: false,
+            show_orphans: false,
+            orphans: Vec::new(),
+            orphans_loaded: None,
+            daemon_attached: false,
+            local_state,
+            note_input: None,
+            send_input: None,
+            search: None,
+            conflict_view: None,
+            project_picker: None,
+            monitor: None,
+            tag_filter: None,
+            auto_jump,
+            pending_jump: None,
+            pager: None,
+            approval_avg: None,
+            keys,
+            columns,
+            config,
+        };
+        app.apply_filter();
+        app
     }
 
     pub fn update_sessions(&mut self, sessions: Vec<Session>) {
+        self.all_sessions = sessions;
+        self.load_errors = last_load_errors();
+        // 終了したセッションのマークは外す
+        let ids: HashSet<&str> = self.all_sessions.iter().map(|s| s.session_id.as_str()).collect();
+        self.marked.retain(|id| ids.contains(id.as_str()));
+        // 古いファイルを削除したら選択を残りの範囲に収める
+        let conflicts = self.pane_conflicts().len();
+        if let Some(ref mut view) = self.conflict_view {
+            view.selected = view.selected.min(conflicts.saturating_sub(1));
+        }
+        // 待っている間に承認待ちでなくなったら自動ジャンプを取りやめる
+        if let Some(ref jump) = self.pending_jump
+            && !self
+                .all_sessions
+                .iter()
+                .any(|s| s.session_id == jump.session_id && s.status == SessionStatus::Waiting)
+        {
+            self.pending_jump = None;
+        }
+
+        self.apply_filter();
+        self.last_update = Instant::now();
+    }
+
+    /// タグで絞り込んだ表示用の一覧を作り直す
+    fn apply_filter(&mut self) {
         let selected = self.state.selected();
-        self.sessions = sessions;
+        let selected_id = self.selected_session().map(|s| s.session_id.clone());
+        self.sessions = self
+            .all_sessions
+            .iter()
+            .filter(|s| {
+                self.tag_filter
+                    .as_ref()
+                    .is_none_or(|tag| self.local_state.has_tag(&s.session_id, tag))
+            })
+            .filter(|s| !is_hidden_by_project(s))
+            .cloned()
+            .collect();
+        self.local_state.sort_pinned_first(&mut self.sessions);
+        // 長く承認待ちのままのセッションはピン留めより前に出す（スヌーズ中のものは除く）
+        self.sessions
+            .sort_by_key(|s| s.snoozed || escalation(s, &self.config) != Escalation::Alert);
+        group_by_host(&mut self.sessions);
+        if self.show_orphans {
+            // 再びペインが見つかったものは通常の一覧に任せる
+            let active: HashSet<&str> = self.all_sessions.iter().map(|s| s.session_id.as_str()).collect();
+            let orphans: Vec<Session> = self
+                .orphans
+                .iter()
+                .filter(|s| !active.contains(s.session_id.as_str()))
+                .filter(|s| {
+                    self.tag_filter
+                        .as_ref()
+                        .is_none_or(|tag| self.local_state.has_tag(&s.session_id, tag))
+                })
+                .filter(|s| !is_hidden_by_project(s))
+                .cloned()
+                .collect();
+            self.sessions.extend(orphans);
+        }
 
-        // 選択位置を維持
-        if !self.sessions.is_empty() {
+        // 並び順が変わっても同じセッションを選択し続ける（追従中は最新のセッション）
+        let follow = if self.follow_newest {
+            self.sessions
+                .iter()
+                .enumerate()
+                .max_by_key(|(_, s)| s.updated)
+                .map(|(idx, _)| idx)
+        } else {
+            selected_id.and_then(|id| self.sessions.iter().position(|s| s.session_id == id))
+        };
+        if let Some(idx) = follow {
+            self.state.select(Some(idx));
+        } else if !self.sessions.is_empty() {
             if let Some(idx) = selected {
                 if idx >= self.sessions.len() {
                     self.state.select(Some(self.sessions.len() - 1));
@@ -68,204 +797,2797 @@ impl App {
         } else {
             self.state.select(None);
         }
+    }
 
-        self.last_update = Instant::now();
+    fn toggle_orphans(&mut self) {
+        self.show_orphans = !self.show_orphans;
+        if self.show_orphans {
+            self.orphans_loaded = None;
+            self.refresh_orphans();
+            if self.orphans.is_empty() {
+                self.set_status_message("ペインがなくなったセッションはありません");
+            }
+        }
+        self.apply_filter();
     }
 
-    pub fn next(&mut self) {
-        if self.sessions.is_empty() {
+    /// 表示中ならペインがなくなったセッションを読み直す（前回から ORPHAN_RELOAD たっていなければ何もしない）
+    fn refresh_orphans(&mut self) {
+        if !self.show_orphans || self.orphans_loaded.is_some_and(|at| at.elapsed() < ORPHAN_RELOAD) {
             return;
         }
+        self.orphans_loaded = Some(Instant::now());
+        match load_orphaned_sessions() {
+            Ok(orphans) => self.orphans = orphans,
+            Err(e) => tracing::warn!("{:#}", e),
+        }
+    }
 
-        let i = match self.state.selected() {
-            Some(i) => {
-                if i >= self.sessions.len() - 1 {
-                    0
-                } else {
-                    i + 1
-                }
+    /// ペインがなくなったセッションなら理由を表示してfalseを返す
+    fn ensure_pane_exists(&mut self, idx: usize) -> bool {
+        if !self.sessions.get(idx).is_some_and(|s| s.orphaned) {
+            return true;
+        }
+        let message = format!(
+            "{}ペインがなくなっています（{}: 再開）",
+            glyph("⚠ ", "! "),
+            self.keys.label(Action::Resume)
+        );
+        self.set_status_message(message);
+        false
+    }
+
+    /// タグの絞り込みを 全件 → タグ1 → タグ2 → … → 全件 の順に切り替える
+    fn cycle_tag_filter(&mut self) {
+        let tags = self.local_state.all_tags();
+        if tags.is_empty() {
+            self.set_status_message("タグが付いたセッションがありません");
+            return;
+        }
+        self.tag_filter = match self.tag_filter {
+            None => tags.first().cloned(),
+            Some(ref current) => tags.iter().skip_while(|t| *t != current).nth(1).cloned(),
+        };
+        self.state.select(Some(0));
+        self.apply_filter();
+    }
+
+    fn start_note_input(&mut self) {
+        let Some(session_id) = self.selected_session_id().map(str::to_string) else {
+            return;
+        };
+        let buffer = self
+            .local_state
+            .note(&session_id)
+            .map(|n| n.to_input())
+            .unwrap_or_default();
+        self.note_input = Some(NoteInput { session_id, buffer });
+    }
+
+    /// 入力中のメモ・タグへのキー入力（Enterで保存、Escで破棄）
+    fn handle_note_key(&mut self, key: KeyEvent) {
+        let Some(ref mut input) = self.note_input else {
+            return;
+        };
+        match key.code {
+            KeyCode::Enter => self.save_note_input(),
+            KeyCode::Esc => self.note_input = None,
+            KeyCode::Backspace => {
+                input.buffer.pop();
             }
-            None => 0,
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                input.buffer.push(c);
+            }
+            _ => {}
+        }
+    }
+
+    fn save_note_input(&mut self) {
+        let Some(input) = self.note_input.take() else {
+            return;
         };
-        self.state.select(Some(i));
+        let note = SessionNote::from_input(&input.buffer);
+        match update_state(|state| state.set_note(&input.session_id, note)) {
+            Ok((state, ())) => {
+                self.local_state = state;
+                self.set_status_message("📝 メモを保存しました");
+            }
+            Err(e) => self.set_status_message(format!("⚠ メモの保存に失敗: {}", e)),
+        }
+        self.apply_filter();
     }
 
-    pub fn previous(&mut self) {
-        if self.sessions.is_empty() {
+    fn start_send_input(&mut self) {
+        let Some(session) = self.selected_session() else {
             return;
+        };
+        if session.host.is_some() {
+            return self.set_status_message("⚠ リモートのセッションには送信できません");
+        }
+        if session.orphaned {
+            return self.set_status_message("⚠ ペインがなくなっています");
         }
+        let prompt = session
+            .notification_message
+            .clone()
+            .filter(|_| session.status == SessionStatus::Waiting);
+        self.send_input = Some(SendInput {
+            pane_id: session.pane_id.clone(),
+            cwd: session.cwd.clone(),
+            prompt,
+            buffer: String::new(),
+        });
+    }
 
-        let i = match self.state.selected() {
-            Some(i) => {
-                if i == 0 {
-                    self.sessions.len() - 1
-                } else {
-                    i - 1
+    /// 送信するテキストへのキー入力（Enterで送信、Escで破棄）
+    fn handle_send_key(&mut self, key: KeyEvent) {
+        let Some(ref mut input) = self.send_input else {
+            return;
+        };
+        match key.code {
+            KeyCode::Enter if !input.buffer.is_empty() => self.send_input_text(),
+            KeyCode::Esc => self.send_input = None,
+            KeyCode::Backspace => {
+                input.buffer.pop();
+            }
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                input.buffer.push(c);
+            }
+            _ => {}
+        }
+    }
+
+    fn send_input_text(&mut self) {
+        let Some(input) = self.send_input.take() else {
+            return;
+        };
+        match send_prompt(&input.pane_id, &input.buffer, true) {
+            Ok(()) => self.set_status_message(format!("➡ Pane {} に送信しました", input.pane_id)),
+            Err(e) => self.set_status_message(format!("⚠ 送信に失敗: {}", e)),
+        }
+    }
+
+    fn start_search(&mut self) {
+        self.search = Some(SearchView {
+            query: String::new(),
+            editing: true,
+            results: Vec::new(),
+            selected: 0,
+        });
+    }
+
+    /// 検索画面へのキー入力（ジャンプするセッションのIDを返す）
+    fn handle_search_key(&mut self, key: KeyEvent) -> Option<String> {
+        let view = self.search.as_mut()?;
+        if view.editing {
+            match key.code {
+                KeyCode::Enter if !view.query.trim().is_empty() => self.run_search(),
+                KeyCode::Esc => self.search = None,
+                KeyCode::Backspace => {
+                    view.query.pop();
+                }
+                KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    view.query.push(c);
                 }
+                _ => {}
             }
-            None => 0,
+            return None;
+        }
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') if !view.results.is_empty() => {
+                view.selected = view.selected.checked_sub(1).unwrap_or(view.results.len() - 1);
+            }
+            KeyCode::Down | KeyCode::Char('j') if !view.results.is_empty() => {
+                view.selected = (view.selected + 1) % view.results.len();
+            }
+            KeyCode::Char('/') => view.editing = true,
+            KeyCode::Esc | KeyCode::Char('q') => self.search = None,
+            KeyCode::Enter => return self.open_search_result(),
+            KeyCode::Char('r') => {
+                if let Some(result) = view.results.get(view.selected).cloned() {
+                    self.resume(&result.cwd, &result.session_id);
+                }
+            }
+            KeyCode::Char('v') => self.view_search_result(),
+            _ => {}
+        }
+        None
+    }
+
+    fn run_search(&mut self) {
+        let Some(ref mut view) = self.search else {
+            return;
         };
-        self.state.select(Some(i));
+        match search_transcripts(&view.query, SEARCH_LIMIT) {
+            Ok(results) => {
+                view.results = results;
+                view.selected = 0;
+                view.editing = false;
+            }
+            Err(e) => self.set_status_message(format!("⚠ 検索に失敗: {}", e)),
+        }
     }
 
-    pub fn selected_session(&self) -> Option<&Session> {
-        self.state.selected().and_then(|i| self.sessions.get(i))
+    /// 選んだ結果が実行中のセッションならジャンプ先のIDを返し、そうでなければ新しいペインで再開する
+    fn open_search_result(&mut self) -> Option<String> {
+        let result = self.search.as_ref().and_then(|v| v.results.get(v.selected)).cloned()?;
+        let running = self
+            .all_sessions
+            .iter()
+            .find(|s| s.session_id == result.session_id && s.status != SessionStatus::Stopped)
+            .cloned();
+        match running {
+            Some(session) if session.host.is_some() => {
+                self.set_status_message("⚠ リモートのセッションにはセッション一覧からジャンプしてください");
+                None
+            }
+            Some(session) => self.ensure_can_jump().then_some(session.session_id),
+            None => {
+                self.resume(&result.cwd, &result.session_id);
+                self.search = None;
+                None
+            }
+        }
     }
 
-    pub fn quit(&mut self) {
-        self.should_quit = true;
+    fn view_search_result(&mut self) {
+        let Some(result) = self.search.as_ref().and_then(|v| v.results.get(v.selected)).cloned() else {
+            return;
+        };
+        match load_conversation(&result.path) {
+            Ok(messages) => {
+                let title = format!("{} ({})", format_cwd(&result.cwd), truncate_text(&result.session_id, 8));
+                self.pager = Some(PagerView::new(title, PagerContent::Conversation(messages)));
+            }
+            Err(e) => self.set_status_message(format!("⚠ 会話ログを読み込めません: {}", e)),
+        }
+    }
+
+    /// ローカルのセッションごとの、一覧から外した古いセッションファイル
+    fn pane_conflicts(&self) -> Vec<(&Session, &PaneConflict)> {
+        self.all_sessions
+            .iter()
+            .filter(|s| s.host.is_none())
+            .flat_map(|s| s.pane_conflicts.iter().map(move |c| (s, c)))
+            .collect()
+    }
+
+    fn open_conflict_view(&mut self) {
+        let conflicts = self.pane_conflicts();
+        if conflicts.is_empty() {
+            self.set_status_message("同じペインを指すセッションファイルはありません");
+            return;
+        }
+        // 選択中のセッションに重複があればそこから始める
+        let selected_id = self.selected_session().map(|s| s.session_id.as_str());
+        let selected = conflicts
+            .iter()
+            .position(|(s, _)| Some(s.session_id.as_str()) == selected_id)
+            .unwrap_or(0);
+        self.conflict_view = Some(ConflictView { selected });
+    }
+
+    /// 重複の一覧へのキー入力（d で古いセッションファイルを削除）
+    fn handle_conflict_key(&mut self, code: KeyCode) {
+        let paths: Vec<PathBuf> = self.pane_conflicts().iter().map(|(_, c)| c.path.clone()).collect();
+        let count = paths.len();
+        let Some(ref mut view) = self.conflict_view else {
+            return;
+        };
+        match code {
+            KeyCode::Up | KeyCode::Char('k') if count > 0 => {
+                view.selected = view.selected.checked_sub(1).unwrap_or(count - 1);
+            }
+            KeyCode::Down | KeyCode::Char('j') if count > 0 => {
+                view.selected = (view.selected + 1) % count;
+            }
+            KeyCode::Char('d') | KeyCode::Delete => {
+                if let Some(path) = paths.get(view.selected).cloned() {
+                    self.confirm(PendingAction::Clean(vec![path]));
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('q') => self.conflict_view = None,
+            _ => {}
+        }
+    }
+
+    /// 対象のセッションのピン留めを切り替える（1件でも未ピンなら全件ピン留め）
+    fn toggle_pin(&mut self) {
+        let ids: Vec<String> = self
+            .target_sessions()
+            .iter()
+            .map(|s| s.session_id.clone())
+            .collect();
+        if ids.is_empty() {
+            return;
+        }
+        let pin = ids.iter().any(|id| !self.local_state.is_pinned(id));
+        let saved = update_state(|state| {
+            for id in &ids {
+                if state.is_pinned(id) != pin {
+                    state.toggle_pin(id);
+                }
+            }
+        });
+        match saved {
+            Ok((state, ())) => {
+                self.local_state = state;
+                if pin {
+                    self.set_status_message(format!("📌 {}件をピン留めしました", ids.len()));
+                } else {
+                    self.set_status_message(format!("📌 {}件のピン留めを外しました", ids.len()));
+                }
+            }
+            Err(e) => self.set_status_message(format!("⚠ ピン留めの保存に失敗: {}", e)),
+        }
+
+        // 並び替え後も同じセッションを選択したままにする
+        let selected = self.selected_session().map(|s| s.session_id.clone());
+        self.apply_filter();
+        if let Some(idx) = selected.and_then(|id| self.sessions.iter().position(|s| s.session_id == id)) {
+            self.state.select(Some(idx));
+        }
     }
+
+    /// 選択中のセッション（履歴タブでは過去のセッション）のディレクトリをエディタで開く
+    fn open_editor(&mut self) {
+        let cwd = match self.tab {
+            Tab::Sessions => match self.selected_session() {
+                Some(session) if session.host.is_some() => {
+                    return self.set_status_message("⚠ リモートのセッションはエディタで開けません");
+                }
+                session => session.map(|s| s.cwd.clone()),
+            },
+            Tab::History => self.selected_history().map(|e| e.project_path.clone()),
+            Tab::Projects => self.selected_project().map(|p| p.path.clone()),
+        };
+        let Some(cwd) = cwd else {
+            return;
+        };
+        match open_in_editor(&self.config.editor, &cwd) {
+            Ok(()) => self.set_status_message(format!("📂 {} を開きました", format_cwd(&cwd))),
+            Err(e) => self.set_status_message(format!("⚠ エディタを開けません: {}", e)),
+        }
+    }
+
+    /// 選択中のセッションの会話ログを開く
+    fn open_transcript(&mut self) {
+        let target = match self.tab {
+            Tab::Sessions => self.selected_session().map(|s| (s.cwd.clone(), s.session_id.clone())),
+            Tab::History => self
+                .selected_history()
+                .map(|e| (e.project_path.clone(), e.session_id.clone())),
+            // プロジェクトの最新のセッション
+            Tab::Projects => self.selected_project().and_then(|p| p.latest()).map(|e| (e.project_path.clone(), e.session_id.clone())),
+        };
+        let Some((cwd, session_id)) = target else {
+            return;
+        };
+        let path = match transcript_path(&cwd, &session_id) {
+            Ok(path) => path,
+            Err(e) => return self.set_status_message(format!("⚠ 会話ログを読み込めません: {}", e)),
+        };
+        if !path.exists() {
+            self.set_status_message("会話ログがまだありません");
+            return;
+        }
+        match load_conversation(&path) {
+            Ok(messages) if messages.is_empty() => self.set_status_message("会話ログがまだありません"),
+            Ok(messages) => {
+                let title = format!("{} ({})", format_cwd(&cwd), truncate_text(&session_id, 8));
+                self.pager = Some(PagerView::new(title, PagerContent::Conversation(messages)));
+            }
+            Err(e) => self.set_status_message(format!("⚠ 会話ログを読み込めません: {}", e)),
+        }
+    }
+
+    /// 選択中のセッションのディレクトリの差分を表示する
+    fn open_diff(&mut self) {
+        let Some(session) = self.selected_session() else {
+            return;
+        };
+        if session.host.is_some() {
+            return self.set_status_message("⚠ リモートのセッションの差分は表示できません");
+        }
+        let cwd = session.cwd.clone();
+        // セッション開始以降の変更に絞れるよう会話の開始時刻を調べておく
+        let since = transcript_path(&cwd, &session.session_id)
+            .ok()
+            .and_then(|path| session_started_at(&path));
+        match load_diff(&cwd, None) {
+            Ok(lines) => {
+                let title = format!("{} ({})", format_cwd(&cwd), truncate_text(&session.session_id, 8));
+                let content = PagerContent::Diff { cwd, since, limited: false, lines };
+                self.pager = Some(PagerView::new(title, content));
+            }
+            Err(e) => self.set_status_message(format!("⚠ 差分を取得できません: {}", e)),
+        }
+    }
+
+    fn handle_pager_key(&mut self, key: KeyEvent) {
+        if let Some(ref mut view) = self.pager
+            && !view.handle_key(key)
+        {
+            self.pager = None;
+        }
+    }
+
+    /// 表示中のタブのリスト件数
+    fn list_len(&self) -> usize {
+        match self.tab {
+            Tab::Sessions => self.sessions.len(),
+            Tab::History => self.history.len(),
+            Tab::Projects => self.projects.len(),
+        }
+    }
+
+    fn list_state(&self) -> &TableState {
+        match self.tab {
+            Tab::Sessions => &self.state,
+            Tab::History => &self.history_state,
+            Tab::Projects => &self.projects_state,
+        }
+    }
+
+    fn list_state_mut(&mut self) -> &mut TableState {
+        match self.tab {
+            Tab::Sessions => &mut self.state,
+            Tab::History => &mut self.history_state,
+            Tab::Projects => &mut self.projects_state,
+        }
+    }
+
+    pub fn next(&mut self) {
+        let len = self.list_len();
+        if len == 0 {
+            return;
+        }
+
+        let i = match self.list_state().selected() {
+            Some(i) => {
+                if i >= len - 1 {
+                    0
+                } else {
+                    i + 1
+                }
+            }
+            None => 0,
+        };
+        self.list_state_mut().select(Some(i));
+    }
+
+    pub fn previous(&mut self) {
+        let len = self.list_len();
+        if len == 0 {
+            return;
+        }
+
+        let i = match self.list_state().selected() {
+            Some(i) => {
+                if i == 0 {
+                    len - 1
+                } else {
+                    i - 1
+                }
+            }
+            None => 0,
+        };
+        self.list_state_mut().select(Some(i));
+    }
+
+    /// リストの1項目の行数
+    fn item_height(&self) -> usize {
+        if self.tab == Tab::Sessions && self.detailed_list {
+            2
+        } else {
+            1
+        }
+    }
+
+    /// リストの1項目の行数（セッション一覧でサブエージェントの行を出すものは1行多い）
+    fn row_height(&self, idx: usize) -> usize {
+        let child = self.tab == Tab::Sessions && self.sessions.get(idx).is_some_and(|s| !s.subagents.is_empty());
+        self.item_height() + usize::from(child)
+    }
+
+    /// リストの見出しの行数
+    fn header_height(&self) -> u16 {
+        match self.tab {
+            Tab::Sessions => 1,
+            Tab::History | Tab::Projects => 0,
+        }
+    }
+
+    /// リストに一度に表示できる項目数
+    fn page_size(&self) -> usize {
+        let rows = self.list_area.height.saturating_sub(2 + self.header_height());
+        (rows as usize / self.item_height()).max(1)
+    }
+
+    /// 1ページ分移動する（端で止まる、先頭・末尾で折り返さない）
+    fn move_page(&mut self, down: bool) {
+        let len = self.list_len();
+        if len == 0 {
+            return;
+        }
+        let page = self.page_size();
+        let current = self.list_state().selected().unwrap_or(0);
+        let i = if down {
+            (current + page).min(len - 1)
+        } else {
+            current.saturating_sub(page)
+        };
+        self.list_state_mut().select(Some(i));
+    }
+
+    fn select_first(&mut self) {
+        if self.list_len() > 0 {
+            self.list_state_mut().select(Some(0));
+        }
+    }
+
+    fn select_last(&mut self) {
+        let len = self.list_len();
+        if len > 0 {
+            self.list_state_mut().select(Some(len - 1));
+        }
+    }
+
+    pub fn selected_session(&self) -> Option<&Session> {
+        self.state.selected().and_then(|i| self.sessions.get(i))
+    }
+
+    fn selected_history(&self) -> Option<&HistoryEntry> {
+        self.history_state.selected().and_then(|i| self.history.get(i))
+    }
+
+    fn selected_project(&self) -> Option<&ProjectSummary> {
+        self.projects_state.selected().and_then(|i| self.projects.get(i))
+    }
+
+    /// 表示中のタブで選択されているセッションID
+    fn selected_session_id(&self) -> Option<&str> {
+        match self.tab {
+            Tab::Sessions => self.selected_session().map(|s| s.session_id.as_str()),
+            Tab::History => self.selected_history().map(|e| e.session_id.as_str()),
+            Tab::Projects => None,
+        }
+    }
+
+    fn toggle_tab(&mut self) {
+        self.tab = match self.tab {
+            Tab::Sessions => Tab::History,
+            Tab::History => Tab::Projects,
+            Tab::Projects => Tab::Sessions,
+        };
+
+        // 履歴・プロジェクトのタブに入るたびに読み直す
+        match self.tab {
+            Tab::History => {
+                self.history_project = None;
+                self.reload_history();
+            }
+            Tab::Projects => {
+                self.projects = load_projects().unwrap_or_default();
+                let selected = (!self.projects.is_empty()).then_some(0);
+                self.projects_state.select(selected);
+            }
+            Tab::Sessions => {}
+        }
+    }
+
+    /// 履歴を読み直す（アクティブなセッションは除外）
+    fn reload_history(&mut self) {
+        let active_ids = self.all_sessions.iter().map(|s| s.session_id.clone()).collect();
+        self.history = load_history(&active_ids).unwrap_or_default();
+        if let Some(ref project) = self.history_project {
+            self.history.retain(|e| e.project_path == *project);
+        }
+        let selected = (!self.history.is_empty()).then_some(0);
+        self.history_state.select(selected);
+    }
+
+    /// 選択中のプロジェクトの履歴だけを履歴タブに出す
+    fn open_project_history(&mut self) {
+        let Some(path) = self.selected_project().map(|p| p.path.clone()) else {
+            return;
+        };
+        self.tab = Tab::History;
+        self.history_project = Some(path);
+        self.reload_history();
+    }
+
+    /// 選択中のプロジェクトで新しいセッションを起動する（ほかのタブではディレクトリを選ぶ）
+    fn new_session_here(&mut self) {
+        match self.tab {
+            Tab::Projects => {
+                if let Some(path) = self.selected_project().map(|p| p.path.clone()) {
+                    self.start_new_session(&path);
+                }
+            }
+            _ => self.open_project_picker(),
+        }
+    }
+
+    /// 選択中のプロジェクトの最新のセッションを新しいペインで再開する
+    fn resume_selected_project(&mut self) {
+        let Some(entry) = self.selected_project().and_then(|p| p.latest()).cloned() else {
+            return;
+        };
+        self.resume(&entry.project_path, &entry.session_id);
+    }
+
+    fn set_status_message(&mut self, message: impl Into<String>) {
+        let mut message = message.into();
+        if ascii_mode()
+            && let Some((emoji, ascii)) = MESSAGE_ICONS.iter().find(|(e, _)| message.starts_with(e))
+        {
+            message = format!("{}{}", ascii, &message[emoji.len()..]);
+        }
+        self.status_message = Some((message, Instant::now()));
+    }
+
+    fn resume_selected_history(&mut self) {
+        let Some(entry) = self.selected_history().cloned() else {
+            return;
+        };
+        self.resume(&entry.project_path, &entry.session_id);
+    }
+
+    /// 選択中のセッションを新しいペインで再開する（終了したセッションの続きから）
+    fn resume_selected_session(&mut self) {
+        let Some(session) = self.selected_session().cloned() else {
+            return;
+        };
+        if session.host.is_some() {
+            self.set_status_message("⚠ リモートのセッションは再開できません");
+            return;
+        }
+        self.resume(&session.cwd, &session.session_id);
+    }
+
+    fn open_project_picker(&mut self) {
+        // 実行中のセッションのディレクトリを先に、履歴は更新の新しい順に続ける
+        let active = self.all_sessions.iter().filter(|s| s.host.is_none()).map(|s| s.cwd.clone());
+        let history = load_history(&HashSet::new()).unwrap_or_default();
+        let mut seen = HashSet::new();
+        let candidates = active
+            .chain(history.into_iter().map(|e| e.project_path))
+            .filter(|cwd| seen.insert(cwd.clone()) && std::path::Path::new(cwd).is_dir())
+            .collect();
+        self.project_picker = Some(ProjectPicker {
+            input: String::new(),
+            candidates,
+            selected: 0,
+        });
+    }
+
+    /// ディレクトリの選択へのキー入力（Enterで選んだディレクトリで起動する）
+    fn handle_picker_key(&mut self, key: KeyEvent) {
+        let Some(ref mut picker) = self.project_picker else {
+            return;
+        };
+        let count = picker.matches().len();
+        match key.code {
+            KeyCode::Up if count > 0 => {
+                picker.selected = picker.selected.checked_sub(1).unwrap_or(count - 1);
+            }
+            KeyCode::Down if count > 0 => {
+                picker.selected = (picker.selected + 1) % count;
+            }
+            KeyCode::Enter => {
+                let path = match picker.matches().get(picker.selected) {
+                    Some(dir) => dir.to_string(),
+                    None if !picker.input.trim().is_empty() => picker.input.trim().to_string(),
+                    None => return,
+                };
+                self.project_picker = None;
+                self.start_new_session(&path);
+            }
+            KeyCode::Esc => self.project_picker = None,
+            KeyCode::Backspace => {
+                picker.input.pop();
+                picker.selected = 0;
+            }
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                picker.input.push(c);
+                picker.selected = 0;
+            }
+            _ => {}
+        }
+    }
+
+    fn start_new_session(&mut self, path: &str) {
+        match resolve_project_dir(path).and_then(|cwd| new_session(&cwd)) {
+            Ok(pane_id) => self.set_status_message(format!("▶ Pane {} で claude を起動しました", pane_id)),
+            Err(e) => self.set_status_message(format!("⚠ 起動に失敗: {}", e)),
+        }
+    }
+
+    fn resume(&mut self, cwd: &str, session_id: &str) {
+        match resume_session(cwd, session_id) {
+            Ok(pane_id) => self.set_status_message(format!("▶ Pane {} で再開しました", pane_id)),
+            Err(e) => self.set_status_message(format!("⚠ 再開に失敗: {}", e)),
+        }
+    }
+
+    fn open_action_menu(&mut self) {
+        let selected = match self.tab {
+            Tab::Projects => self.selected_project().is_some(),
+            _ => self.selected_session_id().is_some(),
+        };
+        if selected {
+            self.action_menu = Some(ActionMenu::new(self.tab));
+        }
+    }
+
+    /// 操作メニューへのキー入力（Enterで選んだ操作を返す）
+    fn handle_menu_key(&mut self, key: KeyEvent) -> Option<Action> {
+        let menu = self.action_menu.as_mut()?;
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                menu.selected = menu.selected.checked_sub(1).unwrap_or(menu.items.len() - 1);
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                menu.selected = (menu.selected + 1) % menu.items.len();
+            }
+            KeyCode::Enter => {
+                let action = menu.items[menu.selected];
+                self.action_menu = None;
+                return Some(action);
+            }
+            KeyCode::Esc | KeyCode::Char('q') => self.action_menu = None,
+            // 各操作のキーでもそのまま選べる
+            _ => {
+                let action = self.keys.action_for(&key).filter(|a| menu.items.contains(a))?;
+                self.action_menu = None;
+                return Some(action);
+            }
+        }
+        None
+    }
+
+    /// 操作対象のセッション（マークがあればマーク中のもの、なければ選択中のもの）
+    fn target_sessions(&self) -> Vec<&Session> {
+        if self.marked.is_empty() {
+            self.selected_session().into_iter().collect()
+        } else {
+            self.marked_sessions()
+        }
+    }
+
+    /// マーク中のセッション（一覧の表示順）
+    fn marked_sessions(&self) -> Vec<&Session> {
+        self.sessions
+            .iter()
+            .filter(|s| self.marked.contains(&s.session_id))
+            .collect()
+    }
+
+    fn toggle_mark(&mut self) {
+        let Some(session_id) = self.selected_session().map(|s| s.session_id.clone()) else {
+            return;
+        };
+        if !self.marked.remove(&session_id) {
+            self.marked.insert(session_id);
+        }
+        self.next();
+    }
+
+    /// マーク中のペインへ、選択位置の次から順番にジャンプする（TUIは閉じない）
+    fn cycle_marked(&mut self) {
+        let current = self.state.selected().unwrap_or(0);
+        let len = self.sessions.len();
+        let next = (1..=len)
+            .map(|offset| (current + offset) % len)
+            .find(|&i| self.marked.contains(&self.sessions[i].session_id));
+        let Some(idx) = next else {
+            self.set_status_message("マーク中のセッションがありません");
+            return;
+        };
+
+        self.state.select(Some(idx));
+        if self.jump_remote(idx) || !self.ensure_can_jump() {
+            return;
+        }
+        let pane_id = self.sessions[idx].pane_id.clone();
+        self.activate_pane(&pane_id);
+    }
+
+    /// TUIを閉じずにペインをアクティブにする
+    fn activate_pane(&mut self, pane_id: &str) {
+        match current_backend().activate_pane(pane_id) {
+            Ok(()) => self.set_status_message(format!("➡ Pane {} にジャンプしました", pane_id)),
+            Err(e) => self.set_status_message(format!("⚠ ジャンプに失敗: {}", e)),
+        }
+    }
+
+    /// ちょうど1つのセッションが承認待ちになったら、そのペインへのジャンプを予約する
+    fn schedule_auto_jump(&mut self, transitions: &[Transition]) {
+        let Some(delay) = self.auto_jump else {
+            return;
+        };
+        let waiting: Vec<&Session> = transitions
+            .iter()
+            .filter(|t| t.to == Some(SessionStatus::Waiting) && t.session.host.is_none())
+            .map(|t| &t.session)
+            .collect();
+        let [session] = waiting.as_slice() else {
+            return;
+        };
+        if !can_control_panes() {
+            return;
+        }
+        self.pending_jump = Some(PendingJump {
+            session_id: session.session_id.clone(),
+            pane_id: session.pane_id.clone(),
+            cwd: session.cwd.clone(),
+            deadline: Instant::now() + delay,
+        });
+        self.tick_auto_jump();
+    }
+
+    fn toggle_monitor(&mut self) {
+        if self.monitor.take().is_none() {
+            self.monitor = Some(Monitor {
+                pane_id: None,
+                content: Ok(String::new()),
+                fetched: None,
+            });
+            self.tick_monitor();
+        }
+    }
+
+    /// モニター中なら、選択が変わったときと MONITOR_REFRESH ごとにペインの表示を取り直す
+    fn tick_monitor(&mut self) {
+        if self.tab != Tab::Sessions {
+            return;
+        }
+        let target = self.selected_session().map(|s| (s.pane_id.clone(), s.host.is_some(), s.orphaned));
+        let Some(ref mut monitor) = self.monitor else {
+            return;
+        };
+        let pane_id = target.as_ref().map(|(pane_id, _, _)| pane_id.clone());
+        if monitor.pane_id == pane_id && monitor.fetched.is_some_and(|at| at.elapsed() < MONITOR_REFRESH) {
+            return;
+        }
+        monitor.content = match target {
+            None => Ok(String::new()),
+            Some((_, true, _)) => Err("リモートのセッションのペインは映せません".to_string()),
+            Some((_, _, true)) => Err("ペインがなくなっています".to_string()),
+            Some((ref pane_id, _, _)) => current_backend().get_text(pane_id).map_err(|e| e.to_string()),
+        };
+        monitor.pane_id = pane_id;
+        monitor.fetched = Some(Instant::now());
+    }
+
+    /// 予約した自動ジャンプの時刻になっていればジャンプする
+    fn tick_auto_jump(&mut self) {
+        if self.pending_jump.as_ref().is_some_and(|j| Instant::now() >= j.deadline) {
+            self.jump_now();
+        }
+    }
+
+    fn jump_now(&mut self) {
+        let Some(jump) = self.pending_jump.take() else {
+            return;
+        };
+        if let Some(idx) = self.sessions.iter().position(|s| s.session_id == jump.session_id) {
+            self.state.select(Some(idx));
+        }
+        self.activate_pane(&jump.pane_id);
+    }
+
+    fn request_interrupt(&mut self) {
+        let sessions = self.target_sessions();
+        let has_remote = sessions.iter().any(|s| s.host.is_some());
+        let targets: Vec<(String, String)> = sessions
+            .iter()
+            .filter(|s| s.host.is_none())
+            .map(|s| (s.pane_id.clone(), s.cwd.clone()))
+            .collect();
+        if !targets.is_empty() {
+            self.confirm(PendingAction::Interrupt(targets));
+        } else if has_remote {
+            self.set_status_message(format!("{}{}", glyph("⚠ ", "! "), REMOTE_UNSUPPORTED));
+        }
+    }
+
+    /// 承認待ちのセッションの通知を snooze_minutes 分止める（スヌーズ中なら解除する）
+    fn toggle_snooze(&mut self) {
+        let targets: Vec<Session> = self
+            .target_sessions()
+            .into_iter()
+            .filter(|s| s.status == SessionStatus::Waiting)
+            .cloned()
+            .collect();
+        if targets.is_empty() {
+            self.set_status_message("承認待ちのセッションがありません");
+            return;
+        }
+        let snooze = targets.iter().any(|s| !s.snoozed);
+        let secs = self.config.snooze_minutes * 60;
+        let saved = update_state(|state| {
+            for session in &targets {
+                if state.is_snoozed(session) != snooze {
+                    state.toggle_snooze(session, secs);
+                }
+            }
+        });
+        match saved {
+            Ok((state, ())) => {
+                self.local_state = state;
+                if snooze {
+                    self.set_status_message(format!(
+                        "🔕 {}件を{}分スヌーズしました",
+                        targets.len(),
+                        self.config.snooze_minutes
+                    ));
+                } else {
+                    self.set_status_message(format!("🔔 {}件のスヌーズを解除しました", targets.len()));
+                }
+            }
+            Err(e) => self.set_status_message(format!("⚠ スヌーズの保存に失敗: {}", e)),
+        }
+        self.local_state.apply_snoozes(&mut self.all_sessions);
+        self.apply_filter();
+    }
+
+    /// Stopフックを取りこぼしたセッションをセッションファイルごと完了扱いにする
+    fn mark_stopped(&mut self) {
+        let paths: Vec<PathBuf> = self
+            .target_sessions()
+            .iter()
+            .filter(|s| s.host.is_none() && s.status != SessionStatus::Stopped)
+            .map(|s| s.path.clone())
+            .collect();
+        if paths.is_empty() {
+            self.set_status_message("完了扱いにできるセッションがありません");
+            return;
+        }
+        let failed = paths
+            .iter()
+            .filter(|path| set_session_status(path, &SessionStatus::Stopped).is_err())
+            .count();
+        if failed == 0 {
+            self.set_status_message(format!("⏹ {}個のセッションを完了扱いにしました", paths.len()));
+        } else {
+            self.set_status_message(format!("⚠ {}個のセッションファイルを書き換えられませんでした", failed));
+        }
+    }
+
+    /// 承認待ちのセッションのツール実行を許可 / 拒否する
+    fn request_answer(&mut self, approve: bool) {
+        let sessions: Vec<Session> = self
+            .target_sessions()
+            .into_iter()
+            .filter(|s| s.host.is_none() && s.status == SessionStatus::Waiting)
+            .cloned()
+            .collect();
+        if sessions.is_empty() {
+            self.set_status_message("承認待ちのセッションがありません");
+            return;
+        }
+        let targets: Vec<AnswerTarget> = sessions.iter().map(AnswerTarget::new).collect();
+        if !approve {
+            return self.confirm(PendingAction::Answer(approve, targets));
+        }
+        // 何を許可するのか見ないまま許可しないよう、confirm = false でも内容を見せて確認する
+        let detail = match (sessions.as_slice(), targets.as_slice()) {
+            ([session], [target]) => pending_call_lines(session, target.call.as_ref()),
+            _ => targets
+                .iter()
+                .map(|t| {
+                    let call = t
+                        .call
+                        .as_ref()
+                        .map(|call| call.describe())
+                        .unwrap_or_else(|| "（ツール呼び出しを読めません）".to_string());
+                    Line::from(vec![
+                        Span::styled(format!("pane {} ", t.pane_id), Style::default().fg(theme().muted)),
+                        Span::styled(truncate_text(&call, 80), Style::default().fg(theme().text)),
+                    ])
+                })
+                .collect(),
+        };
+        let action = PendingAction::Answer(approve, targets);
+        self.pending_action = Some(Confirm::new(action.prompt(), action).with_detail(detail));
+    }
+
+    fn request_clean(&mut self) {
+        let paths: Vec<PathBuf> = self
+            .target_sessions()
+            .iter()
+            .filter(|s| s.host.is_none())
+            .map(|s| s.path.clone())
+            .collect();
+        if paths.is_empty() {
+            self.set_status_message("削除できるセッションがありません");
+            return;
+        }
+        self.confirm(PendingAction::Clean(paths));
+    }
+
+    /// マーク中のセッションをカレントディレクトリにJSONで書き出す
+    fn export_marked(&mut self) {
+        let marked: Vec<serde_json::Value> =
+            self.marked_sessions().iter().map(|s| s.to_json()).collect();
+        if marked.is_empty() {
+            self.set_status_message("マーク中のセッションがありません");
+            return;
+        }
+
+        let file_name = format!(
+            "claude-watch-export-{}.json",
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        );
+        let result = serde_json::to_string_pretty(&marked)
+            .map_err(anyhow::Error::from)
+            .and_then(|json| fs::write(&file_name, json).map_err(anyhow::Error::from));
+        match result {
+            Ok(()) => self.set_status_message(format!("💾 {}件を {} に書き出しました", marked.len(), file_name)),
+            Err(e) => self.set_status_message(format!("⚠ 書き出しに失敗: {}", e)),
+        }
+    }
+
+    /// 確認ダイアログを出す（confirm = false なら確認せずに実行する）
+    fn confirm(&mut self, action: PendingAction) {
+        if self.config.confirm {
+            self.pending_action = Some(Confirm::new(action.prompt(), action));
+        } else {
+            self.run_action(action);
+        }
+    }
+
+    fn handle_confirm_key(&mut self, code: KeyCode) {
+        let Some(confirmed) = self.pending_action.as_mut().and_then(|c| c.handle_key(code)) else {
+            return;
+        };
+        if let Some(confirm) = self.pending_action.take()
+            && confirmed
+        {
+            self.run_action(confirm.value);
+        }
+    }
+
+    fn run_action(&mut self, action: PendingAction) {
+        match action {
+            PendingAction::Interrupt(targets) => {
+                let failed = targets
+                    .iter()
+                    .filter(|(pane_id, _)| interrupt_pane(pane_id).is_err())
+                    .count();
+                if failed == 0 {
+                    self.set_status_message(format!("⏹ {}個のペインにCtrl-Cを送信しました", targets.len()));
+                } else {
+                    self.set_status_message(format!("⚠ {}個のペインへの送信に失敗しました", failed));
+                }
+            }
+            PendingAction::Clean(paths) => {
+                let removed = paths.iter().filter(|p| fs::remove_file(p).is_ok()).count();
+                self.marked.clear();
+                self.set_status_message(format!("🧹 {}個のセッションファイルを削除しました", removed));
+            }
+            PendingAction::Answer(approve, targets) => {
+                // 確認している間に次の確認へ進んだセッションには送らない
+                let (targets, changed): (Vec<AnswerTarget>, Vec<AnswerTarget>) =
+                    targets.into_iter().partition(AnswerTarget::is_unchanged);
+                let failed = targets
+                    .iter()
+                    .filter(|target| answer_permission(&target.pane_id, approve).is_err())
+                    .count();
+                if !changed.is_empty() {
+                    self.set_status_message(format!(
+                        "⚠ {}個のセッションは確認の内容が変わったため送信しませんでした",
+                        changed.len()
+                    ));
+                } else if failed > 0 {
+                    self.set_status_message(format!("⚠ {}個のペインへの送信に失敗しました", failed));
+                } else if approve {
+                    self.set_status_message(format!("✅ {}個のセッションで許可しました", targets.len()));
+                } else {
+                    self.set_status_message(format!("🚫 {}個のセッションで拒否しました", targets.len()));
+                }
+            }
+        }
+    }
+
+    /// ジャンプできない状態なら理由を表示してfalseを返す
+    fn ensure_can_jump(&mut self) -> bool {
+        if can_control_panes() {
+            return true;
+        }
+        self.set_status_message("⚠ バックエンドが使えないためジャンプできません");
+        false
+    }
+
+    /// リモートのセッションならTUIを閉じずに [[remote]] の jump でジャンプしてtrueを返す
+    fn jump_remote(&mut self, idx: usize) -> bool {
+        let Some(session) = self.sessions.get(idx).filter(|s| s.host.is_some()) else {
+            return false;
+        };
+        let message = format!(
+            "➡ {} の Pane {} にジャンプしました",
+            session.host.as_deref().unwrap_or_default(),
+            session.pane_id
+        );
+        match jump_to_remote_pane(&self.config.remote, session) {
+            Ok(()) => self.set_status_message(message),
+            Err(e) => self.set_status_message(format!("⚠ ジャンプに失敗: {}", e)),
+        }
+        true
+    }
+
+    /// 選択中のセッション（履歴タブでは過去のセッション）の値をコピーする
+    fn copy_selected(&mut self, field: CopyField) {
+        let value = match self.tab {
+            Tab::Sessions => self
+                .selected_session()
+                .map(|s| field.value(&s.session_id, &s.cwd, Some(&s.pane_id))),
+            Tab::History => self
+                .selected_history()
+                .map(|e| field.value(&e.session_id, &e.project_path, None)),
+            Tab::Projects => self
+                .selected_project()
+                .and_then(|p| p.latest())
+                .map(|e| field.value(&e.session_id, &e.project_path, None)),
+        };
+        let Some(value) = value else {
+            return;
+        };
+        let Some(value) = value else {
+            return self.set_status_message("過去のセッションにはペインがありません");
+        };
+        match copy_to_clipboard(&value) {
+            Ok(()) => self.set_status_message(format!("📋 コピーしました: {}", value)),
+            Err(e) => self.set_status_message(format!("⚠ コピーに失敗: {}", e)),
+        }
+    }
+
+    /// 画面座標からリストのインデックスを求める
+    fn index_at(&self, column: u16, row: u16) -> Option<usize> {
+        let area = self.list_area;
+        // 枠線の内側だけを対象にする
+        let top = area.y + 1 + self.header_height();
+        if column <= area.x
+            || column >= area.x + area.width.saturating_sub(1)
+            || row < top
+            || row >= area.y + area.height.saturating_sub(1)
+        {
+            return None;
+        }
+
+        // サブエージェントの行があるセッションは1行高い
+        let mut y = (row - top) as usize;
+        let mut idx = self.list_state().offset();
+        while idx < self.list_len() {
+            let height = self.row_height(idx);
+            if y < height {
+                return Some(idx);
+            }
+            y -= height;
+            idx += 1;
+        }
+        None
+    }
+
+    /// マウスイベントを処理し、ジャンプ対象が決まればそのインデックスを返す
+    fn handle_mouse(&mut self, mouse: MouseEvent) -> Option<usize> {
+        if let Some(ref mut view) = self.pager {
+            match mouse.kind {
+                MouseEventKind::ScrollDown => view.scroll_by(3),
+                MouseEventKind::ScrollUp => view.scroll_by(-3),
+                _ => {}
+            }
+            return None;
+        }
+        match mouse.kind {
+            MouseEventKind::ScrollDown => self.next(),
+            MouseEventKind::ScrollUp => self.previous(),
+            MouseEventKind::Down(MouseButton::Left) => {
+                let idx = self.index_at(mouse.column, mouse.row)?;
+                self.list_state_mut().select(Some(idx));
+
+                // 同じ行を素早く2回クリックしたらジャンプ
+                let now = Instant::now();
+                let is_double = matches!(
+                    self.last_click,
+                    Some((at, prev)) if prev == idx && now.duration_since(at) < Duration::from_millis(400)
+                );
+                if is_double {
+                    self.last_click = None;
+                    return Some(idx);
+                }
+                self.last_click = Some((now, idx));
+            }
+            MouseEventKind::Down(MouseButton::Middle) => {
+                let idx = self.index_at(mouse.column, mouse.row)?;
+                self.list_state_mut().select(Some(idx));
+                return Some(idx);
+            }
+            _ => {}
+        }
+        None
+    }
+
+    pub fn quit(&mut self) {
+        self.should_quit = true;
+    }
+}
+
+/// ヘルプに表示するマウス操作の一覧
+const MOUSE_HELP: &[(&str, &str)] = &[
+    ("クリック", "セッションを選択"),
+    ("ダブルクリック / 中クリック", "ペインにジャンプ"),
+    ("ホイール", "選択を移動"),
+];
+
+/// 画面中央に指定サイズの領域を確保する
+fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+fn render_action_menu(f: &mut Frame, menu: &ActionMenu, keys: &KeyBindings) {
+    let lines: Vec<Line> = menu
+        .items
+        .iter()
+        .enumerate()
+        .map(|(i, &action)| {
+            let label = menu_label(action);
+            let padding = 26usize.saturating_sub(Span::raw(label).width());
+            let line = Line::from(vec![
+                Span::raw(if i == menu.selected { glyph("▶ ", "> ") } else { "  " }),
+                Span::raw(label),
+                Span::raw(" ".repeat(padding)),
+                Span::styled(keys.label(action), Style::default().fg(theme().subtle)),
+            ]);
+            if i == menu.selected {
+                line.style(theme().highlight)
+            } else {
+                line
+            }
+        })
+        .collect();
+
+    let area = centered_rect(42, lines.len() as u16 + 2, f.area());
+    let popup = Paragraph::new(lines).block(
+        block()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme().accent))
+            .title("操作 (Enter: 実行 / Esc: 閉じる)"),
+    );
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
+
+/// 差分の行の色（追加・削除・ハンク・ファイル見出し）
+fn diff_line_style(line: &str) -> Style {
+    if line.starts_with("+++") || line.starts_with("---") || line.starts_with("diff ") || line.starts_with("index ") {
+        Style::default().fg(theme().text).add_modifier(Modifier::BOLD)
+    } else if line.starts_with('+') {
+        Style::default().fg(theme().ok)
+    } else if line.starts_with('-') {
+        Style::default().fg(theme().danger)
+    } else if line.starts_with("@@") {
+        Style::default().fg(theme().accent)
+    } else if line.starts_with("??") {
+        Style::default().fg(theme().warning)
+    } else {
+        Style::default().fg(theme().muted)
+    }
+}
+
+/// 発言者ごとのアイコン・表示名・色
+fn role_style(role: Role) -> (&'static str, &'static str, Color) {
+    let (icon, color) = match role {
+        Role::User => (glyph("👤 ", "> "), theme().accent),
+        Role::Assistant => (glyph("🤖 ", "< "), theme().ok),
+        Role::ToolUse => (glyph("🔧 ", "$ "), theme().warning),
+        Role::ToolResult => (glyph("📄 ", "  "), theme().muted),
+    };
+    (icon, role.label(), color)
+}
+
+/// 表示幅で折り返す（全角文字は2桁として数える）
+fn wrap_text(text: &str, width: usize) -> Vec<String> {
+    let mut rows = Vec::new();
+    let mut row = String::new();
+    let mut row_width = 0;
+    for ch in text.chars() {
+        let ch_width = Span::raw(ch.to_string()).width();
+        if row_width + ch_width > width && !row.is_empty() {
+            rows.push(std::mem::take(&mut row));
+            row_width = 0;
+        }
+        row.push(ch);
+        row_width += ch_width;
+    }
+    rows.push(row);
+    rows
+}
+
+/// 全画面のトランスクリプトビューア
+fn render_pager(f: &mut Frame, view: &mut PagerView) {
+    let area = f.area();
+    let chunks = Layout::default()
+        .constraints([Constraint::Min(0), Constraint::Length(1)])
+        .split(area);
+    let transcript_block = block()
+        .borders(Borders::ALL)
+        .title(format!("{} · {}", view.kind(), view.title));
+    let inner = transcript_block.inner(chunks[0]);
+    view.wrap(inner.width);
+    view.page_height = inner.height as usize;
+    view.scroll = view.scroll.min(view.max_scroll());
+
+    let query = view.query.to_lowercase();
+    let visible: Vec<Line> = (view.scroll..view.rows.len())
+        .take(view.page_height)
+        .map(|i| {
+            let line = view.rows[i].clone();
+            if view.is_match(i, &query) {
+                line.patch_style(theme().highlight)
+            } else {
+                line
+            }
+        })
+        .collect();
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(visible).block(transcript_block), chunks[0]);
+
+    let footer = if view.searching {
+        format!("検索: {}_  (Enter: 検索 / Esc: キャンセル)", view.query)
+    } else {
+        let position = format!(
+            "{}/{}行",
+            (view.scroll + view.page_height).min(view.rows.len()),
+            view.rows.len()
+        );
+        let matches = if query.is_empty() {
+            String::new()
+        } else {
+            let count = (0..view.rows.len()).filter(|&i| view.is_match(i, &query)).count();
+            format!(" | \"{}\" {}件", view.query, count)
+        };
+        let limit = match view.content {
+            PagerContent::Diff { since: Some(_), limited: false, .. } => " | s: セッション開始以降のみ",
+            PagerContent::Diff { since: Some(_), limited: true, .. } => " | s: すべての変更",
+            _ => "",
+        };
+        format!(
+            "{}{} | j/k: スクロール | Space/b: ページ | g/G: 先頭/末尾 | /: 検索 | n/N: 次/前{} | q/Esc: 閉じる",
+            position, matches, limit
+        )
+    };
+    f.render_widget(
+        Paragraph::new(footer).style(Style::default().fg(theme().subtle)),
+        chunks[1],
+    );
+}
+
+fn render_auto_jump(f: &mut Frame, jump: &PendingJump) {
+    let remaining = jump.deadline.saturating_duration_since(Instant::now());
+    let lines = vec![
+        Line::from(format!(
+            "{} が承認待ちです。{}秒後にジャンプします",
+            format_cwd(&jump.cwd),
+            remaining.as_secs() + 1
+        )),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("Enter", Style::default().fg(theme().ok).add_modifier(Modifier::BOLD)),
+            Span::raw(": 今すぐジャンプ  "),
+            Span::styled("Esc", Style::default().fg(theme().danger).add_modifier(Modifier::BOLD)),
+            Span::raw(": キャンセル"),
+        ]),
+    ];
+
+    let area = centered_rect(60, 5, f.area());
+    let popup = Paragraph::new(lines).block(
+        block()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme().waiting))
+            .title("自動ジャンプ"),
+    );
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
+
+fn render_note_input(f: &mut Frame, input: &NoteInput) {
+    let lines = vec![
+        Line::from(Span::styled(
+            "メモを入力（#で始まる語はタグ）",
+            Style::default().fg(theme().muted),
+        )),
+        Line::from(vec![
+            Span::styled(input.buffer.clone(), Style::default().fg(theme().text)),
+            Span::styled("_", Style::default().fg(theme().accent).add_modifier(Modifier::SLOW_BLINK)),
+        ]),
+        Line::from(vec![
+            Span::styled("Enter", Style::default().fg(theme().ok).add_modifier(Modifier::BOLD)),
+            Span::raw(": 保存  "),
+            Span::styled("Esc", Style::default().fg(theme().danger).add_modifier(Modifier::BOLD)),
+            Span::raw(": キャンセル"),
+        ]),
+    ];
+
+    let area = centered_rect(60, 5, f.area());
+    let popup = Paragraph::new(lines).block(
+        block()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme().accent))
+            .title(format!("メモ · {}", truncate_text(&input.session_id, 12))),
+    );
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
+
+/// 許可の確認に出すツール呼び出しの行数の上限
+const MAX_CALL_LINES: usize = 14;
+
+/// 許可の確認に出す、結果を待っているツール呼び出しの内容
+///
+/// Bashはコマンド全体、Edit / MultiEdit / Writeはファイルと書き換える内容を出す。
+fn pending_call_lines(session: &Session, call: Option<&ToolCall>) -> Vec<Line<'static>> {
+    let Some(call) = call else {
+        // 会話ログに書かれる前なら通知のメッセージだけでも出す
+        let message = session
+            .notification_message
+            .clone()
+            .unwrap_or_else(|| "ツール呼び出しの内容を会話ログから読めませんでした".to_string());
+        return vec![Line::styled(format!("{}{}", glyph("⚠ ", "! "), message), Style::default().fg(theme().warning))];
+    };
+
+    let input = &call.input;
+    let str_of = |key: &str| input[key].as_str().unwrap_or_default().to_string();
+    let mut lines = vec![Line::styled(call.name.clone(), Style::default().fg(theme().accent).add_modifier(Modifier::BOLD))];
+    let mut body: Vec<Line<'static>> = Vec::new();
+    let push_text = |body: &mut Vec<Line<'static>>, prefix: &str, text: &str, color: Color| {
+        for line in text.lines() {
+            body.push(Line::styled(format!("{}{}", prefix, line), Style::default().fg(color)));
+        }
+    };
+    match call.name.as_str() {
+        "Bash" => {
+            push_text(&mut body, "$ ", &str_of("command"), theme().text);
+            if let Some(description) = input["description"].as_str() {
+                lines.push(Line::styled(description.to_string(), Style::default().fg(theme().muted)));
+            }
+        }
+        "Edit" | "MultiEdit" | "Write" => {
+            lines.push(Line::styled(
+                format!("{}{}", glyph("📝 ", ""), format_cwd(&str_of("file_path"))),
+                Style::default().fg(theme().text),
+            ));
+            let edits = match call.name.as_str() {
+                "MultiEdit" => input["edits"].as_array().cloned().unwrap_or_default(),
+                _ => vec![input.clone()],
+            };
+            for edit in &edits {
+                push_text(&mut body, "- ", edit["old_string"].as_str().unwrap_or_default(), theme().danger);
+                let new = edit["new_string"].as_str().or(edit["content"].as_str()).unwrap_or_default();
+                push_text(&mut body, "+ ", new, theme().ok);
+            }
+        }
+        _ => push_text(&mut body, "", &describe_tool_input(input), theme().text),
+    }
+    let hidden = body.len().saturating_sub(MAX_CALL_LINES);
+    lines.extend(body.into_iter().take(MAX_CALL_LINES));
+    if hidden > 0 {
+        lines.push(Line::styled(format!("… ほか{}行", hidden), Style::default().fg(theme().subtle)));
+    }
+    lines
+}
+
+fn render_send_input(f: &mut Frame, input: &SendInput) {
+    let mut lines = Vec::new();
+    if let Some(ref prompt) = input.prompt {
+        lines.push(Line::styled(truncate_text(prompt, 100), Style::default().fg(theme().warning)));
+    }
+    lines.push(Line::from(vec![
+        Span::styled(input.buffer.clone(), Style::default().fg(theme().text)),
+        Span::styled("_", Style::default().fg(theme().accent).add_modifier(Modifier::SLOW_BLINK)),
+    ]));
+    lines.push(Line::from(vec![
+        Span::styled("Enter", Style::default().fg(theme().ok).add_modifier(Modifier::BOLD)),
+        Span::raw(": 送信  "),
+        Span::styled("Esc", Style::default().fg(theme().danger).add_modifier(Modifier::BOLD)),
+        Span::raw(": キャンセル"),
+    ]));
+
+    let area = centered_rect(80, lines.len() as u16 + 2, f.area());
+    let popup = Paragraph::new(lines).block(
+        block()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme().accent))
+            .title(format!("pane {} · {} に送信", input.pane_id, format_cwd(&input.cwd))),
+    );
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
+
+fn render_search(f: &mut Frame, view: &SearchView, sessions: &[Session]) {
+    let mut lines = vec![Line::from(vec![
+        Span::styled(glyph("🔍 ", "/ "), Style::default().fg(theme().accent)),
+        Span::styled(view.query.clone(), Style::default().fg(theme().text)),
+        if view.editing {
+            Span::styled("_", Style::default().fg(theme().accent).add_modifier(Modifier::SLOW_BLINK))
+        } else {
+            Span::raw("")
+        },
+    ])];
+    lines.push(Line::from(""));
+
+    if !view.editing && view.results.is_empty() {
+        lines.push(Line::styled("一致する会話はありません", Style::default().fg(theme().muted)));
+    }
+    for (i, result) in view.results.iter().enumerate() {
+        let modified = chrono::DateTime::<chrono::Utc>::from(result.modified).to_rfc3339();
+        let status = sessions
+            .iter()
+            .find(|s| s.session_id == result.session_id)
+            .map(|s| {
+                let label = format!("  {} {}", s.status.icon(), s.status.label());
+                Span::styled(label, Style::default().fg(s.status.color()))
+            })
+            .unwrap_or_else(|| Span::raw(""));
+        let header = Line::from(vec![
+            Span::raw(if i == view.selected { glyph("▶ ", "> ") } else { "  " }),
+            Span::styled(format_cwd(&result.cwd), Style::default().fg(theme().text).add_modifier(Modifier::BOLD)),
+            Span::styled(
+                format!("  {}  {}", truncate_text(&result.session_id, 8), format_relative_time(&modified)),
+                Style::default().fg(theme().subtle),
+            ),
+            status,
+        ]);
+        lines.push(if i == view.selected { header.style(theme().highlight) } else { header });
+        for m in &result.matches {
+            let (icon, _, color) = role_style(m.role);
+            lines.push(Line::from(vec![
+                Span::raw("    "),
+                Span::styled(icon, Style::default().fg(color)),
+                Span::styled(m.snippet.clone(), Style::default().fg(theme().muted)),
+            ]));
+        }
+        if result.match_count > result.matches.len() {
+            lines.push(Line::styled(
+                format!("    ほか{}件", result.match_count - result.matches.len()),
+                Style::default().fg(theme().subtle),
+            ));
+        }
+    }
+
+    let hint = if view.editing {
+        "Enter: 検索  Esc: 閉じる"
+    } else {
+        "Enter: ジャンプ / 再開  r: 再開  v: 会話ログ  /: 検索し直す  Esc: 閉じる"
+    };
+    let area = centered_rect(100, (lines.len() as u16 + 2).max(6), f.area());
+    // 選択中の結果の最後の行が見えるようにスクロールする
+    let result_lines = |r: &SearchResult| 1 + r.matches.len() + usize::from(r.match_count > r.matches.len());
+    let selected_end = 2 + view.results.iter().take(view.selected + 1).map(result_lines).sum::<usize>();
+    let scroll = selected_end.saturating_sub(area.height.saturating_sub(2) as usize);
+    let popup = Paragraph::new(lines)
+        .scroll((scroll as u16, 0))
+        .block(
+            block()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme().accent))
+                .title(format!("会話ログを検索 ({})", hint)),
+        );
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
+
+fn render_project_picker(f: &mut Frame, picker: &ProjectPicker) {
+    let mut lines = vec![Line::from(vec![
+        Span::styled(glyph("📂 ", "> "), Style::default().fg(theme().accent)),
+        Span::styled(picker.input.clone(), Style::default().fg(theme().text)),
+        Span::styled("_", Style::default().fg(theme().accent).add_modifier(Modifier::SLOW_BLINK)),
+    ])];
+    lines.push(Line::from(""));
+
+    let matches = picker.matches();
+    if matches.is_empty() {
+        let message = if picker.input.trim().is_empty() {
+            "候補はありません（パスを入力してください）"
+        } else {
+            "一致する候補はありません（Enterで入力したパスを使う）"
+        };
+        lines.push(Line::styled(message, Style::default().fg(theme().muted)));
+    }
+    for (i, dir) in matches.iter().enumerate() {
+        let line = Line::from(vec![
+            Span::raw(if i == picker.selected { glyph("▶ ", "> ") } else { "  " }),
+            Span::styled(format_cwd(dir), Style::default().fg(theme().text)),
+        ]);
+        lines.push(if i == picker.selected { line.style(theme().highlight) } else { line });
+    }
+
+    let area = centered_rect(80, (lines.len() as u16 + 2).clamp(6, 20), f.area());
+    // 選択中の候補が見えるようにスクロールする
+    let scroll = (picker.selected + 3).saturating_sub(area.height.saturating_sub(2) as usize);
+    let popup = Paragraph::new(lines)
+        .scroll((scroll as u16, 0))
+        .block(
+            block()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme().accent))
+                .title("新しいセッションを起動 (Enter: 起動  ↑↓: 選択  Esc: 閉じる)"),
+        );
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
+
+fn render_conflicts(f: &mut Frame, view: &ConflictView, conflicts: &[(&Session, &PaneConflict)]) {
+    let mut lines = vec![Line::styled(
+        "同じペインに複数のセッションファイルがあるため、新しい方だけを一覧に出しています",
+        Style::default().fg(theme().muted),
+    )];
+    if conflicts.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::styled("重複はなくなりました", Style::default().fg(theme().ok)));
+    }
+    let mut previous: Option<&str> = None;
+    for (i, (session, conflict)) in conflicts.iter().enumerate() {
+        // 一覧に出しているセッションごとに見出しを置く
+        if previous != Some(session.session_id.as_str()) {
+            lines.push(Line::from(""));
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("pane {} · {} ", session.pane_id, format_cwd(&session.cwd)),
+                    Style::default().fg(theme().text).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    format!("表示中: {} {}", truncate_text(&session.session_id, 8), session.status_label()),
+                    Style::default().fg(theme().muted),
+                ),
+            ]));
+            previous = Some(session.session_id.as_str());
+        }
+        let line = Line::from(vec![
+            Span::raw(if i == view.selected { glyph("▶ ", "> ") } else { "  " }),
+            Span::styled(truncate_text(&conflict.session_id, 8), Style::default().fg(theme().warning)),
+            Span::styled(
+                format!(
+                    "  {} · {} · {}前に更新 · {}",
+                    format_cwd(&conflict.cwd),
+                    conflict.status.label(),
+                    format_duration(elapsed_since(conflict.updated)),
+                    conflict.path.file_name().unwrap_or_default().to_string_lossy()
+                ),
+                Style::default().fg(theme().muted),
+            ),
+        ]);
+        lines.push(if i == view.selected { line.style(theme().highlight) } else { line });
+    }
+
+    let area = centered_rect(100, lines.len() as u16 + 2, f.area());
+    let popup = Paragraph::new(lines).block(
+        block()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme().warning))
+            .title("ペインの重複 (d: 古いファイルを削除 / Esc: 閉じる)"),
+    );
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
+
+fn render_help(f: &mut Frame, keys: &KeyBindings) {
+    let mut lines = vec![section_header("キー操作")];
+    let key_help = Action::ALL
+        .iter()
+        .map(|&action| (keys.label(action), action.description()));
+    let mouse_help = MOUSE_HELP.iter().map(|&(key, desc)| (key.to_string(), desc));
+    for (key, desc) in key_help.chain(mouse_help) {
+        // 全角文字を含むため表示幅で揃える
+        let padding = 28usize.saturating_sub(Span::raw(key.as_str()).width());
+        lines.push(Line::from(vec![
+            Span::styled(key, Style::default().fg(theme().accent)),
+            Span::raw(" ".repeat(padding)),
+            Span::raw(desc),
+        ]));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(section_header("ステータス"));
+    let builtin = [
+        SessionStatus::Active,
+        SessionStatus::Waiting,
+        SessionStatus::Stopped,
+        SessionStatus::Other(String::new()),
+    ];
+    for status in builtin.into_iter().chain(custom_statuses()) {
+        lines.push(Line::from(vec![
+            Span::raw(format!("{} ", status.icon())),
+            Span::styled(status.label().to_string(), Style::default().fg(status.color())),
+        ]));
+    }
+
+    let area = centered_rect(72, lines.len() as u16 + 2, f.area());
+    let popup = Paragraph::new(lines).block(
+        block()
+            .borders(Borders::ALL)
+            .title("Help (?/Esc で閉じる)"),
+    );
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
+
+/// ステータスメッセージ先頭の絵文字とASCIIでの代替
+const MESSAGE_ICONS: &[(&str, &str)] = &[
+    ("⚠ ", "! "),
+    ("▶ ", "> "),
+    ("➡ ", "> "),
+    ("⏹ ", ""),
+    ("💾 ", ""),
+    ("🧹 ", ""),
+    ("🔕 ", ""),
+    ("✅ ", ""),
+    ("🚫 ", ""),
+    ("📋 ", ""),
+    ("📝 ", ""),
+    ("📌 ", ""),
+    ("📂 ", ""),
+    ("🔔 ", "* "),
+    ("💸 ", "$ "),
+];
+
+/// ASCIIモードでは罫線を +-| で描く
+const ASCII_BORDER: border::Set = border::Set {
+    top_left: "+",
+    top_right: "+",
+    bottom_left: "+",
+    bottom_right: "+",
+    vertical_left: "|",
+    vertical_right: "|",
+    horizontal_top: "-",
+    horizontal_bottom: "-",
+};
+
+fn block() -> Block<'static> {
+    let block = Block::default().border_style(Style::default().fg(theme().border));
+    if ascii_mode() {
+        block.border_set(ASCII_BORDER)
+    } else {
+        block
+    }
+}
+
+/// "── Task ─────" 形式の見出し
+fn section_header(title: &str) -> Line<'static> {
+    let rule = glyph("─", "-");
+    let width = 30usize.saturating_sub(Span::raw(title).width() + 4);
+    Line::from(Span::styled(
+        format!("{}{} {} {}", rule, rule, title, rule.repeat(width)),
+        Style::default().fg(theme().muted),
+    ))
+}
+
+
+/// cwdごとに固定の色（同じプロジェクトは時系列で混ざっても同じ色になる）
+fn project_color(cwd: &str) -> Color {
+    // 実行ごとに変わらないようFNV-1aで自前でハッシュする
+    let hash = cwd
+        .trim_end_matches('/')
+        .bytes()
+        .fold(0xcbf29ce484222325u64, |h, b| (h ^ b as u64).wrapping_mul(0x100000001b3));
+    let colors = theme().project_colors;
+    if colors.is_empty() {
+        return theme().text;
+    }
+    colors[(hash % colors.len() as u64) as usize]
+}
+
+/// ファイルを監視しているときに、変化がなくても読み直す間隔
+const WATCHED_RELOAD: Duration = Duration::from_secs(5);
+
+/// 警告パネルに表示するファイル数の上限
+const MAX_WARNING_LINES: usize = 3;
+
+fn ui(f: &mut Frame, app: &mut App) {
+    if let Some(ref mut view) = app.pager {
+        render_pager(f, view);
+        if let Some(ref jump) = app.pending_jump {
+            render_auto_jump(f, jump);
+        }
+        return;
+    }
+
+    // 読み込めなかったセッションファイルがあるときだけ警告パネルを出す
+    let warning_height = match app.load_errors.len() {
+        0 => 0,
+        n => n.min(MAX_WARNING_LINES) as u16 + 2,
+    };
+    let chunks = Layout::default()
+        .constraints([
+            Constraint::Length(3),  // ヘッダー
+            Constraint::Min(0),     // ボディ
+            Constraint::Length(warning_height),  // 警告
+            Constraint::Length(1),  // フッター
+        ])
+        .split(f.area());
+
+    // ボディを左右に分割
+    let body = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(40),  // 左ペイン
+            Constraint::Percentage(60),  // 右ペイン
+        ])
+        .split(chunks[1]);
+
+    // ヘッダー（タブ）
+    let tab_index = match app.tab {
+        Tab::Sessions => 0,
+        Tab::History => 1,
+        Tab::Projects => 2,
+    };
+    // バックエンドが使えないときはタイトルに表示する
+    let mut title = vec![Span::raw(format!("{}Claude Code セッション監視", glyph("📋 ", "")))];
+    if app.daemon_attached {
+        title.push(Span::styled(" · daemon", Style::default().fg(theme().muted)));
+    }
+    if !can_control_panes() {
+        let reason = backend_unavailable().unwrap_or_else(|| "バックエンドなし".to_string());
+        title.push(Span::styled(
+            format!(" {}{}（ジャンプ無効）", glyph("⚠ ", "! "), reason),
+            Style::default().fg(theme().warning),
+        ));
+    }
+    let header = Tabs::new(vec!["Sessions", "History", "Projects"])
+        .select(tab_index)
+        .style(Style::default().fg(theme().subtle))
+        .highlight_style(Style::default().fg(theme().accent).add_modifier(Modifier::BOLD))
+        .divider(glyph("│", "|"))
+        .block(
+            block()
+                .borders(Borders::ALL)
+                .title(Line::from(title))
+                .title(status_bar(&app.all_sessions, app.last_update.elapsed()).right_aligned()),
+        );
+    f.render_widget(header, chunks[0]);
+
+    app.list_area = body[0];
+    match app.tab {
+        Tab::Sessions => {
+            render_session_list(f, body[0], app);
+            // 右ペイン: 選択セッションの詳細
+            let session = app.selected_session();
+            if let Some(ref monitor) = app.monitor {
+                render_monitor(f, body[1], monitor, session);
+            } else {
+                let note = session.and_then(|s| app.local_state.note(&s.session_id));
+                render_detail(f, body[1], session, &app.config.timer, app.absolute_time, note, app.approval_avg);
+            }
+        }
+        Tab::History => {
+            render_history_list(f, body[0], app);
+            render_history_detail(f, body[1], app.selected_history());
+        }
+        Tab::Projects => {
+            render_project_list(f, body[0], app);
+            render_project_detail(f, body[1], app.selected_project(), &app.all_sessions);
+        }
+    }
+
+    // フッター（一時メッセージがあれば優先して表示）
+    let status_message = app
+        .status_message
+        .as_ref()
+        .filter(|(_, at)| at.elapsed() < Duration::from_secs(3))
+        .map(|(msg, _)| msg.as_str());
+
+    let footer_text = match (status_message, app.tab) {
+        (Some(msg), _) => msg.to_string(),
+        (None, Tab::Sessions) if app.sessions.is_empty() && app.auto_jump.is_some() => format!(
+            "セッションを待っています（承認待ちになったら自動でジャンプ） | {}",
+            footer_hints(&app.keys, &[(Action::NewSession, "起動"), (Action::Quit, "終了")])
+        ),
+        (None, Tab::Sessions) if app.sessions.is_empty() => format!(
+            "アクティブなセッションがありません | {}",
+            footer_hints(
+                &app.keys,
+                &[(Action::NewSession, "起動"), (Action::SwitchTab, "履歴"), (Action::Quit, "終了")]
+            )
+        ),
+        (None, Tab::Sessions) if !app.marked.is_empty() => format!(
+            "{}件マーク中 | {}",
+            app.marked.len(),
+            footer_hints(
+                &app.keys,
+                &[
+                    (Action::CycleMarked, "順にジャンプ"),
+                    (Action::Interrupt, "中断"),
+                    (Action::CleanMarked, "削除"),
+                    (Action::ExportMarked, "書き出し"),
+                    (Action::ClearMarks, "マーク解除"),
+                ],
+            )
+        ),
+        (None, Tab::Sessions) => footer_hints(
+            &app.keys,
+            &[
+                (Action::Jump, "ジャンプ"),
+                (Action::Menu, "操作"),
+                (Action::Interrupt, "中断"),
+                (Action::ToggleMark, "マーク"),
+                (Action::CopyId, "IDコピー"),
+                (Action::SwitchTab, "履歴"),
+                (Action::Help, "ヘルプ"),
+                (Action::Quit, "終了"),
+            ],
+        ),
+        (None, Tab::History) => footer_hints(
+            &app.keys,
+            &[
+                (Action::Resume, "再開"),
+                (Action::ViewTranscript, "会話"),
+                (Action::CopyId, "IDコピー"),
+                (Action::SwitchTab, "プロジェクト"),
+                (Action::Help, "ヘルプ"),
+                (Action::Quit, "終了"),
+            ],
+        ),
+        (None, Tab::Projects) => footer_hints(
+            &app.keys,
+            &[
+                (Action::Jump, "履歴"),
+                (Action::NewSession, "新規"),
+                (Action::Resume, "最新を再開"),
+                (Action::OpenEditor, "エディタ"),
+                (Action::SwitchTab, "セッション"),
+                (Action::Help, "ヘルプ"),
+                (Action::Quit, "終了"),
+            ],
+        ),
+    };
+
+    if warning_height > 0 {
+        render_load_errors(f, chunks[2], &app.load_errors);
+    }
+
+    let footer = Paragraph::new(footer_text).style(Style::default().fg(theme().subtle));
+    f.render_widget(footer, chunks[3]);
+
+    if app.show_help {
+        render_help(f, &app.keys);
+    }
+
+    if let Some(ref menu) = app.action_menu {
+        render_action_menu(f, menu, &app.keys);
+    }
+    if let Some(ref view) = app.conflict_view {
+        render_conflicts(f, view, &app.pane_conflicts());
+    }
+    if let Some(ref picker) = app.project_picker {
+        render_project_picker(f, picker);
+    }
+    if let Some(ref confirm) = app.pending_action {
+        confirm.render(f);
+    }
+    if let Some(ref view) = app.search {
+        render_search(f, view, &app.all_sessions);
+    }
+    if let Some(ref input) = app.note_input {
+        render_note_input(f, input);
+    }
+    if let Some(ref input) = app.send_input {
+        render_send_input(f, input);
+    }
+    if let Some(ref jump) = app.pending_jump {
+        render_auto_jump(f, jump);
+    }
+}
+
+/// ヘッダー右の集計（"🟢 3 実行中 · 🟡 2 承認待ち · ⚪ 5 完了 · 2秒前に更新"）
+///
+/// 実行中・承認待ち・完了は0件でも表示し、独自ステータスとスヌーズ中はあるときだけ続ける。
+fn status_bar(sessions: &[Session], since_refresh: Duration) -> Line<'static> {
+    let mut statuses = vec![SessionStatus::Active, SessionStatus::Waiting, SessionStatus::Stopped];
+    for session in sessions {
+        if !statuses.contains(&session.status) {
+            statuses.push(session.status.clone());
+        }
+    }
+
+    let separator = || Span::styled(" · ", Style::default().fg(theme().muted));
+    let mut spans = Vec::new();
+    for status in &statuses {
+        // スヌーズ中の承認待ちは数えない（別に表示する）
+        let count = sessions.iter().filter(|s| s.status == *status && !s.snoozed).count();
+        let style = if count > 0 {
+            Style::default().fg(status.color())
+        } else {
+            Style::default().fg(theme().muted)
+        };
+        spans.push(Span::styled(format!("{} {} {}", status.icon(), count, status.label()), style));
+        spans.push(separator());
+    }
+    let snoozed = sessions.iter().filter(|s| s.snoozed).count();
+    if snoozed > 0 {
+        spans.push(Span::styled(
+            format!("{} {} スヌーズ中", glyph("🔕", "[Z]"), snoozed),
+            Style::default().fg(theme().muted),
+        ));
+        spans.push(separator());
+    }
+    if let Some(daily) = daily_budget() {
+        spans.push(Span::styled(
+            format!("今日 {}", daily.amounts()),
+            Style::default().fg(budget_color(daily.level)),
+        ));
+        spans.push(separator());
+    }
+    spans.push(Span::styled(
+        format!("{}秒前に更新 ", since_refresh.as_secs()),
+        Style::default().fg(theme().muted),
+    ));
+    Line::from(spans)
+}
+
+/// コンテキストの使用率の色（自動圧縮が近づくと黄色→赤）
+fn context_color(percent: u64) -> Color {
+    if percent >= 90 {
+        theme().danger
+    } else if percent >= 75 {
+        theme().warning
+    } else {
+        theme().muted
+    }
+}
+
+/// 予算の段階の色（注意は黄色、超過は赤）
+fn budget_color(level: Option<BudgetLevel>) -> Color {
+    match level {
+        Some(BudgetLevel::Over) => theme().danger,
+        Some(BudgetLevel::Warn) => theme().warning,
+        None => theme().muted,
+    }
+}
+
+/// 読み込めなかったセッションファイルの一覧
+fn render_load_errors(f: &mut Frame, area: Rect, errors: &[LoadError]) {
+    let lines: Vec<Line> = errors
+        .iter()
+        .take(MAX_WARNING_LINES)
+        .map(|error| {
+            let file_name = error
+                .path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            Line::from(vec![
+                Span::styled(file_name, Style::default().fg(theme().warning)),
+                Span::styled(format!("  {}", error.message), Style::default().fg(theme().muted)),
+            ])
+        })
+        .collect();
+
+    let panel = Paragraph::new(lines).block(
+        block()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme().warning))
+            .title(format!(
+                "{}読み込めないセッションファイル ({}) · claude-watch doctor で確認",
+                glyph("⚠ ", "! "),
+                errors.len()
+            )),
+    );
+    f.render_widget(panel, area);
+}
+
+/// 現在のキー割り当てでフッターの操作ヒントを組み立てる
+fn footer_hints(keys: &KeyBindings, hints: &[(Action, &str)]) -> String {
+    hints
+        .iter()
+        .map(|&(action, label)| format!("{}: {}", keys.label(action), label))
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+/// 承認待ちのまま平均待ち時間を超えているか
+fn waiting_longer_than(session: &Session, avg: u64) -> bool {
+    session.status == SessionStatus::Waiting && elapsed_since(session.updated) > avg
+}
+
+/// 経過時間に応じたセッション一覧での強調（承認待ちは [timer]、完了は [escalation]）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Escalation {
+    None,
+    /// 承認待ちが長い（オレンジ）
+    Warn,
+    /// 承認待ちがさらに長い（赤、一覧の先頭へ）
+    Alert,
+    /// 完了してから時間がたった・スヌーズ中（薄く表示）
+    Dim,
+}
+
+fn escalation(session: &Session, config: &Config) -> Escalation {
+    if session.snoozed {
+        return Escalation::Dim;
+    }
+    let elapsed = elapsed_since(session.updated);
+    let over = |threshold: u64| threshold > 0 && elapsed >= threshold;
+    match session.status {
+        SessionStatus::Waiting if over(config.timer.alert_after) => Escalation::Alert,
+        SessionStatus::Waiting if over(config.timer.warn_after) => Escalation::Warn,
+        SessionStatus::Stopped if over(config.escalation.stopped_dim_after) => Escalation::Dim,
+        _ => Escalation::None,
+    }
+}
+
+/// 一覧のステータスの文字の色（承認待ちが長いとオレンジ→赤）
+fn escalated_status_style(session: &Session, config: &Config) -> Style {
+    match escalation(session, config) {
+        Escalation::Warn => Style::default().fg(theme().escalated),
+        Escalation::Alert => Style::default().fg(theme().danger).add_modifier(Modifier::BOLD),
+        Escalation::None | Escalation::Dim => Style::default().fg(session.status_color()),
+    }
+}
+
+/// 経過時間に応じた色（閾値を超えると黄色→赤）
+fn elapsed_color(elapsed: u64, timer: &TimerConfig) -> Color {
+    if elapsed >= timer.alert_after {
+        theme().danger
+    } else if elapsed >= timer.warn_after {
+        theme().warning
+    } else {
+        theme().muted
+    }
+}
+
+/// 1行目と、2行表示のときだけ出す2行目からなるセルの内容
+fn two_line_text(first: Line<'static>, second: Option<Line<'static>>, detailed: bool) -> Text<'static> {
+    if detailed {
+        Text::from(vec![first, second.unwrap_or_default()])
+    } else {
+        Text::from(first)
+    }
+}
+
+/// セッション一覧の1セルの内容
+fn session_text(app: &App, session: &Session, column: Column) -> Text<'static> {
+    let detailed = app.detailed_list;
+    let muted = Style::default().fg(theme().muted);
+    match column {
+        Column::Status => {
+            let mark = if app.marked.contains(&session.session_id) {
+                Span::styled(glyph("● ", "* "), Style::default().fg(theme().mark))
+            } else {
+                Span::raw("  ")
+            };
+            let first = Line::from(vec![
+                mark,
+                Span::raw(format!("{} ", session.status_icon())),
+                Span::styled(session.status_label().to_string(), escalated_status_style(session, &app.config)),
+            ]);
+            // 要約の列がなければ承認待ちの理由か実行中のツールをここに出す
+            let reason = session
+                .waiting_reason
+                .as_ref()
+                .filter(|_| session.status == SessionStatus::Waiting)
+                .map(|reason| reason.label())
+                .or_else(|| session.current_tool.clone())
+                .filter(|_| !app.columns.iter().any(|(c, _)| *c == Column::Summary))
+                .map(|text| Line::from(Span::styled(format!("  {}", text), muted)));
+            two_line_text(first, reason, detailed)
+        }
+        Column::Project => {
+            let mut first = vec![Span::styled(
+                format_dir_name(&session.cwd).to_string(),
+                Style::default().fg(project_color(&session.cwd)),
+            )];
+            if app.local_state.is_pinned(&session.session_id) {
+                first.push(Span::raw(glyph(" 📌", " ^")));
+            }
+            let mut extra = Vec::new();
+            if let Some(ref host) = session.host {
+                extra.push(Span::styled(format!("@{} ", host), muted));
+            }
+            if let Some(model) = session.model_family() {
+                extra.push(Span::styled(format!("[{}] ", model), muted));
+            }
+            if let Some(level) = session_budget(session).and_then(|b| b.level) {
+                extra.push(Span::styled(format!("{} ", level.icon()), Style::default().fg(budget_color(Some(level)))));
+            }
+            if !session.pane_conflicts.is_empty() {
+                extra.push(Span::styled(
+                    format!("{}{} ", glyph("⚠×", "dup:"), session.pane_conflicts.len()),
+                    Style::default().fg(theme().warning),
+                ));
+            }
+            if let Some(note) = app.local_state.note(&session.session_id) {
+                for tag in &note.tags {
+                    extra.push(Span::styled(format!("#{} ", tag), Style::default().fg(theme().accent)));
+                }
+            }
+            if detailed {
+                two_line_text(Line::from(first), Some(Line::from(extra)), true)
+            } else {
+                first.push(Span::raw(" "));
+                first.extend(extra);
+                Text::from(Line::from(first))
+            }
+        }
+        Column::Summary => {
+            // 実行中ならアシスタントの最後の発言を要約より優先する
+            let summary = session
+                .last_message
+                .as_deref()
+                .or(session.summary.as_deref())
+                .or(session.first_prompt.as_deref());
+            let (first, second) = match session.waiting_reason {
+                // 承認待ちなら理由を先に出し、2行目に要約
+                Some(ref reason) if session.status == SessionStatus::Waiting => {
+                    (Line::from(Span::styled(reason.label(), Style::default().fg(theme().warning))), summary)
+                }
+                // 実行中ならツールを先に出す
+                _ if let Some(ref tool) = session.current_tool => (
+                    Line::from(Span::styled(format!("{}{}", glyph("⚙ ", ""), tool), Style::default().fg(theme().accent))),
+                    summary,
+                ),
+                _ if session.last_message.is_some() => (
+                    Line::from(summary.unwrap_or("-").to_string()),
+                    session.summary.as_deref().or(session.first_prompt.as_deref()),
+                ),
+                _ => (
+                    Line::from(summary.unwrap_or("-").to_string()),
+                    session.summary.as_ref().and(session.first_prompt.as_deref()),
+                ),
+            };
+            let second = second.map(|text| Line::from(Span::styled(text.to_string(), muted)));
+            two_line_text(first, second, detailed)
+        }
+        Column::Branch => {
+            let branch = Span::raw(session.git_branch.clone().unwrap_or_default());
+            let git = session
+                .git_status
+                .as_ref()
+                .and_then(format_git_status)
+                .map(|git| Span::styled(git, Style::default().fg(theme().warning)));
+            match git {
+                Some(git) if detailed => two_line_text(Line::from(branch), Some(Line::from(git)), true),
+                Some(git) => Text::from(Line::from(vec![branch, Span::raw(" "), git])),
+                None => two_line_text(Line::from(branch), None, detailed),
+            }
+        }
+        Column::Messages => Text::from(
+            Line::from(session.message_count.map(|n| n.to_string()).unwrap_or_default()).right_aligned(),
+        ),
+        Column::Context => match session.context_percent() {
+            Some(percent) => Text::from(Span::styled(
+                format_context_gauge(percent),
+                Style::default().fg(context_color(percent)),
+            )),
+            None => Text::default(),
+        },
+        Column::Age => {
+            let Some(elapsed) = status_elapsed(session) else {
+                return Text::default();
+            };
+            let (text, other) = if app.absolute_time {
+                (format_clock_time(session.updated as i64), format_duration(elapsed))
+            } else {
+                (format_duration(elapsed), format_clock_time(session.updated as i64))
+            };
+            let mut first = vec![Span::styled(text, Style::default().fg(elapsed_color(elapsed, &app.config.timer)))];
+            if app.approval_avg.is_some_and(|avg| waiting_longer_than(session, avg)) {
+                first.push(Span::styled(glyph(" ⏳", " !"), Style::default().fg(theme().danger)));
+            }
+            two_line_text(Line::from(first), Some(Line::from(Span::styled(other, muted))), detailed)
+        }
+    }
+}
+
+/// 実行中のサブエージェントを親のセッションの下に出す行のセル
+///
+/// ステータスの列に "└─ 🤖 2件"、要約（なければプロジェクト）の列にそれぞれの説明を出す。
+fn subagent_line(session: &Session, column: Column, columns: &[(Column, Constraint)]) -> Option<Line<'static>> {
+    let has = |c: Column| columns.iter().any(|(column, _)| *column == c);
+    let detail_column = if has(Column::Summary) { Column::Summary } else { Column::Project };
+    let count = format!("{} {}{}件", tree(), glyph("🤖 ", "sub:"), session.subagents.len());
+    let descriptions: Vec<&str> = session.subagents.iter().map(|s| s.description.as_str()).collect();
+    let text = match column {
+        Column::Status => format!("  {}", count),
+        c if c == detail_column && has(Column::Status) => descriptions.join(" · "),
+        c if c == detail_column => format!("{} {}", count, descriptions.join(" · ")),
+        _ => return None,
+    };
+    Some(Line::styled(text, Style::default().fg(theme().accent)))
+}
+
+/// 左ペイン: セッション一覧
+fn render_session_list(f: &mut Frame, area: Rect, app: &mut App) {
+    let rows: Vec<Row> = app
+        .sessions
+        .iter()
+        .map(|session| {
+            let has_subagents = !session.subagents.is_empty();
+            let cells: Vec<Cell> = app
+                .columns
+                .iter()
+                .map(|&(column, _)| {
+                    let mut text = session_text(app, session, column);
+                    // サブエージェントの行は親の行の下に出す
+                    if has_subagents && let Some(child) = subagent_line(session, column, &app.columns) {
+                        text.lines.resize(app.item_height(), Line::default());
+                        text.lines.push(child);
+                    }
+                    Cell::from(text)
+                })
+                .collect();
+            let height = app.item_height() + usize::from(has_subagents);
+            let row = Row::new(cells).height(height as u16);
+            if escalation(session, &app.config) == Escalation::Dim {
+                row.style(Style::default().add_modifier(Modifier::DIM))
+            } else {
+                row
+            }
+        })
+        .collect();
+    let header = Row::new(app.columns.iter().map(|(column, _)| column.header()))
+        .style(Style::default().fg(theme().subtle).add_modifier(Modifier::BOLD));
+
+    let mut title = match app.tag_filter {
+        Some(ref tag) => format!("Sessions ({}/{}) #{}", app.sessions.len(), app.all_sessions.len(), tag),
+        None if app.config.remote.is_empty() => format!("Sessions ({})", app.sessions.len()),
+        None => {
+            let hosts: Vec<String> = count_by_host(&app.sessions)
+                .iter()
+                .map(|(host, count)| format!("{} {}", host, count))
+                .collect();
+            format!("Sessions ({}) · {}", app.sessions.len(), hosts.join(" · "))
+        }
+    };
+    if app.follow_newest {
+        title.push_str(" · 追従");
+    }
+    if app.show_orphans {
+        let orphans = app.sessions.iter().filter(|s| s.orphaned).count();
+        title.push_str(&format!(" · {}{}", glyph("👻", "orphaned:"), orphans));
+    }
+
+    let table = Table::new(rows, app.columns.iter().map(|&(_, width)| width))
+        .header(header)
+        .block(
+            block()
+                .borders(Borders::ALL)
+                .title(title),
+        )
+        .row_highlight_style(theme().highlight)
+        .highlight_symbol(glyph("▶ ", "> "));
+
+    f.render_stateful_widget(table, area, &mut app.state);
+    render_list_scrollbar(f, area, app.sessions.len(), app.page_size(), &app.state);
+}
+
+/// 枠内に収まらないときだけ右の枠線上にスクロールバーを表示する
+fn render_list_scrollbar(f: &mut Frame, area: Rect, len: usize, visible: usize, state: &TableState) {
+    if len <= visible {
+        return;
+    }
+    // 最後のページを表示しているときにつまみが下端に来るよう、スクロールできる位置の数を渡す
+    let mut scrollbar_state = ScrollbarState::new(len - visible + 1)
+        .viewport_content_length(visible)
+        .position(state.offset());
+    let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+        .begin_symbol(None)
+        .end_symbol(None)
+        .track_symbol(Some(glyph("│", "|")))
+        .thumb_symbol(glyph("█", "#"))
+        .style(Style::default().fg(theme().muted));
+    f.render_stateful_widget(
+        scrollbar,
+        area.inner(Margin { vertical: 1, horizontal: 0 }),
+        &mut scrollbar_state,
+    );
+}
+
+/// 左ペイン: 過去のセッション一覧
+fn render_history_list(f: &mut Frame, area: Rect, app: &mut App) {
+    let rows: Vec<Row> = app
+        .history
+        .iter()
+        .map(|entry| {
+            let date = entry
+                .modified
+                .as_deref()
+                .map(format_datetime)
+                .unwrap_or_else(|| "不明".to_string());
+
+            Row::new(vec![
+                Cell::from(Span::styled(date, Style::default().fg(theme().muted))),
+                Cell::from(Span::styled(
+                    format_dir_name(&entry.project_path).to_string(),
+                    Style::default().fg(project_color(&entry.project_path)),
+                )),
+            ])
+        })
+        .collect();
+
+    let table = Table::new(rows, [Constraint::Length(16), Constraint::Fill(1)])
+        .block(
+            block()
+                .borders(Borders::ALL)
+                .title(match app.history_project {
+                    Some(ref project) => format!("History · {} ({})", format_dir_name(project), app.history.len()),
+                    None => format!("History ({})", app.history.len()),
+                }),
+        )
+        .row_highlight_style(theme().highlight)
+        .highlight_symbol(glyph("▶ ", "> "));
+
+    f.render_stateful_widget(table, area, &mut app.history_state);
+    render_list_scrollbar(f, area, app.history.len(), app.page_size(), &app.history_state);
+}
+
+fn render_monitor(f: &mut Frame, area: Rect, monitor: &Monitor, session: Option<&Session>) {
+    let title = match session {
+        Some(session) => format!("Monitor · pane {} · {}", session.pane_id, format_dir_name(&session.cwd)),
+        None => "Monitor".to_string(),
+    };
+    let lines: Vec<Line> = match monitor.content {
+        _ if session.is_none() => vec![Line::styled("セッションを選択してください", Style::default().fg(theme().muted))],
+        Ok(ref text) => {
+            // 画面の下の空行は除き、入りきらなければ最後の行を見せる
+            let mut rows: Vec<&str> = text.lines().collect();
+            while rows.last().is_some_and(|row| row.trim().is_empty()) {
+                rows.pop();
+            }
+            let height = area.height.saturating_sub(2) as usize;
+            rows[rows.len().saturating_sub(height)..]
+                .iter()
+                .map(|row| Line::styled(row.to_string(), Style::default().fg(theme().text)))
+                .collect()
+        }
+        Err(ref e) => vec![Line::styled(format!("{}{}", glyph("⚠ ", "! "), e), Style::default().fg(theme().warning))],
+    };
+    let monitor = Paragraph::new(lines).block(
+        block()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme().accent))
+            .title(title),
+    );
+    f.render_widget(monitor, area);
 }
 
-fn format_dir_name(cwd: &str) -> &str {
-    cwd.rsplit('/').next().unwrap_or(cwd)
-}
+fn render_project_list(f: &mut Frame, area: Rect, app: &mut App) {
+    let rows: Vec<Row> = app
+        .projects
+        .iter()
+        .map(|project| {
+            let date = project
+                .latest()
+                .and_then(|e| e.modified.as_deref())
+                .map(format_datetime)
+                .unwrap_or_else(|| "不明".to_string());
+            let running = app
+                .all_sessions
+                .iter()
+                .filter(|s| s.host.is_none() && s.cwd == project.path)
+                .count();
+            Row::new(vec![
+                Cell::from(Span::styled(date, Style::default().fg(theme().muted))),
+                Cell::from(Span::styled(
+                    format_dir_name(&project.path).to_string(),
+                    Style::default().fg(project_color(&project.path)),
+                )),
+                Cell::from(Span::styled(
+                    format!("{:>4}", project.session_count),
+                    Style::default().fg(theme().subtle),
+                )),
+                Cell::from(if running > 0 {
+                    Span::styled(format!("{}{}", glyph("🟢", "*"), running), Style::default().fg(theme().ok))
+                } else {
+                    Span::raw("")
+                }),
+            ])
+        })
+        .collect();
 
-fn ui(f: &mut Frame, app: &mut App) {
-    let chunks = Layout::default()
-        .constraints([
-            Constraint::Length(3),  // ヘッダー
-            Constraint::Min(0),     // ボディ
-            Constraint::Length(1),  // フッター
-        ])
-        .split(f.area());
+    let table = Table::new(
+        rows,
+        [Constraint::Length(16), Constraint::Fill(1), Constraint::Length(4), Constraint::Length(4)],
+    )
+    .block(
+        block()
+            .borders(Borders::ALL)
+            .title(format!("Projects ({})", app.projects.len())),
+    )
+    .row_highlight_style(theme().highlight)
+    .highlight_symbol(glyph("▶ ", "> "));
 
-    // ボディを左右に分割
-    let body = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage(30),  // 左ペイン
-            Constraint::Percentage(70),  // 右ペイン
-        ])
-        .split(chunks[1]);
+    f.render_stateful_widget(table, area, &mut app.projects_state);
+    render_list_scrollbar(f, area, app.projects.len(), app.page_size(), &app.projects_state);
+}
 
-    // ヘッダー
-    let header = Paragraph::new("📋 Claude Code セッション監視")
-        .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
-        .block(Block::default().borders(Borders::ALL));
-    f.render_widget(header, chunks[0]);
+fn render_project_detail(f: &mut Frame, area: Rect, project: Option<&ProjectSummary>, sessions: &[Session]) {
+    let Some(project) = project else {
+        let text = Paragraph::new("プロジェクトがありません")
+            .style(Style::default().fg(theme().muted))
+            .block(block().borders(Borders::ALL).title("Detail"));
+        f.render_widget(text, area);
+        return;
+    };
 
-    // 左ペイン: セッション一覧（コンパクト）
-    let items: Vec<ListItem> = app
-        .sessions
-        .iter()
-        .map(|session| {
-            let icon = get_status_icon(&session.status);
-            let status_label = get_status_label(&session.status);
-            let dir_name = format_dir_name(&session.cwd);
-            let color = get_status_color(&session.status);
+    let mut lines = vec![
+        Line::from(vec![
+            Span::raw(glyph("📁 ", "")),
+            Span::styled(format_cwd(&project.path), Style::default().fg(theme().text)),
+        ]),
+        Line::from(Span::styled(
+            format!("{}セッション", project.session_count),
+            Style::default().fg(theme().muted),
+        )),
+    ];
 
-            // 1行: "{icon} {status_label} {dir_name}"
-            let line = Line::from(vec![
-                Span::raw(format!("{} ", icon)),
+    let running: Vec<&Session> = sessions
+        .iter()
+        .filter(|s| s.host.is_none() && s.cwd == project.path)
+        .collect();
+    if !running.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(section_header(&format!("Running ({})", running.len())));
+        for session in running {
+            lines.push(Line::from(vec![
                 Span::styled(
-                    format!("{:<8}", status_label),
-                    Style::default().fg(color),
+                    format!("{} {} ", session.status_icon(), session.status_label()),
+                    Style::default().fg(session.status_color()),
                 ),
-                Span::raw(format!(" {}", dir_name)),
-            ]);
+                Span::styled(
+                    format!("pane {} · {}", session.pane_id, truncate_text(&session.session_id, 8)),
+                    Style::default().fg(theme().muted),
+                ),
+            ]));
+        }
+    }
 
-            ListItem::new(line)
-        })
-        .collect();
+    lines.push(Line::from(""));
+    lines.push(section_header(&format!("Recent ({})", project.recent.len())));
+    for entry in &project.recent {
+        let date = entry
+            .modified
+            .as_deref()
+            .map(format_datetime)
+            .unwrap_or_else(|| "不明".to_string());
+        let title = entry.summary.as_ref().or(entry.first_prompt.as_ref());
+        lines.push(Line::from(vec![
+            Span::styled(format!("{}  ", date), Style::default().fg(theme().muted)),
+            Span::styled(
+                title.map(|t| truncate_text(t, 60)).unwrap_or_else(|| truncate_text(&entry.session_id, 8)),
+                Style::default().fg(theme().text),
+            ),
+        ]));
+    }
 
-    let list = List::new(items)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title(format!("Sessions ({})", app.sessions.len())),
-        )
-        .highlight_style(
-            Style::default()
-                .bg(Color::DarkGray)
-                .add_modifier(Modifier::BOLD),
-        )
-        .highlight_symbol("▶ ");
+    let detail = Paragraph::new(lines).block(block().borders(Borders::ALL).title("Detail"));
+    f.render_widget(detail, area);
+}
 
-    f.render_stateful_widget(list, body[0], &mut app.state);
+fn render_history_detail(f: &mut Frame, area: Rect, entry: Option<&HistoryEntry>) {
+    let Some(entry) = entry else {
+        let text = Paragraph::new("過去のセッションがありません")
+            .style(Style::default().fg(theme().muted))
+            .block(block().borders(Borders::ALL).title("Detail"));
+        f.render_widget(text, area);
+        return;
+    };
 
-    // 右ペイン: 選択セッションの詳細
-    render_detail(f, body[1], app.selected_session());
+    let mut lines = vec![
+        Line::from(vec![
+            Span::raw(glyph("📁 ", "")),
+            Span::styled(format_cwd(&entry.project_path), Style::default().fg(theme().text)),
+        ]),
+    ];
 
-    // フッター
-    let footer_text = if app.sessions.is_empty() {
-        "アクティブなセッションがありません | q: 終了"
-    } else {
-        "↑↓: 選択 | Enter: ジャンプ | q: 終了"
-    };
+    let mut meta_parts = vec![];
+    if let Some(ref branch) = entry.git_branch {
+        meta_parts.push(format!("{}{}", glyph("🔀 ", "@"), branch));
+    }
+    if let Some(count) = entry.message_count {
+        meta_parts.push(format!("{}{}msg", glyph("📨 ", ""), count));
+    }
+    if let Some(ref modified) = entry.modified {
+        meta_parts.push(format!("{}{}", glyph("🕐 ", ""), format_datetime(modified)));
+    }
+    if !meta_parts.is_empty() {
+        lines.push(Line::from(Span::styled(
+            meta_parts.join(" · "),
+            Style::default().fg(theme().muted),
+        )));
+    }
 
-    let footer = Paragraph::new(footer_text).style(Style::default().fg(Color::Gray));
-    f.render_widget(footer, chunks[2]);
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled("ID: ", Style::default().fg(theme().muted)),
+        Span::styled(entry.session_id.clone(), Style::default().fg(theme().warning)),
+    ]));
+    lines.push(Line::from(Span::styled(
+        format!("claude --resume {}", entry.session_id),
+        Style::default().fg(theme().muted),
+    )));
+
+    if let Some(ref first_prompt) = entry.first_prompt {
+        lines.push(Line::from(""));
+        lines.push(section_header("Task"));
+        lines.push(Line::from(Span::styled(
+            truncate_text(first_prompt, 100),
+            Style::default().fg(theme().accent),
+        )));
+    }
+
+    if let Some(ref summary) = entry.summary {
+        lines.push(Line::from(""));
+        lines.push(section_header("Summary"));
+        lines.push(Line::from(Span::styled(
+            truncate_text(summary, 150),
+            Style::default().fg(theme().text),
+        )));
+    }
+
+    let detail = Paragraph::new(lines).block(block().borders(Borders::ALL).title("Detail"));
+    f.render_widget(detail, area);
 }
 
-fn render_detail(f: &mut Frame, area: ratatui::layout::Rect, session: Option<&Session>) {
+fn render_detail(
+    f: &mut Frame,
+    area: ratatui::layout::Rect,
+    session: Option<&Session>,
+    timer: &TimerConfig,
+    absolute_time: bool,
+    note: Option<&SessionNote>,
+    approval_avg: Option<u64>,
+) {
     let Some(session) = session else {
         let text = Paragraph::new("セッションを選択してください")
-            .style(Style::default().fg(Color::DarkGray))
-            .block(Block::default().borders(Borders::ALL).title("Detail"));
+            .style(Style::default().fg(theme().muted))
+            .block(block().borders(Borders::ALL).title("Detail"));
         f.render_widget(text, area);
         return;
     };
 
-    let icon = get_status_icon(&session.status);
-    let status_label = get_status_label(&session.status);
-    let color = get_status_color(&session.status);
-    let cwd = format_cwd(&session.cwd);
+    let icon = session.status_icon();
+    let status_label = session.status_label();
+    let color = session.status_color();
+    let cwd = match session.host {
+        Some(ref host) => format!("{}:{}", host, format_cwd(&session.cwd)),
+        None => format_cwd(&session.cwd),
+    };
 
     let mut lines = vec![];
 
     // ステータス行
-    lines.push(Line::from(vec![
+    let mut status_spans = vec![
         Span::raw(format!("{} ", icon)),
         Span::styled(
             status_label,
             Style::default().fg(color).add_modifier(Modifier::BOLD),
         ),
-    ]));
+    ];
+    if let Some(elapsed) = status_elapsed(session) {
+        let text = if absolute_time {
+            format!(" · {}から", format_clock_time(session.updated as i64))
+        } else {
+            format!(" · {}経過", format_duration(elapsed))
+        };
+        status_spans.push(Span::styled(
+            text,
+            Style::default().fg(elapsed_color(elapsed, timer)),
+        ));
+    }
+    if let Some(source) = session.status_source.label() {
+        status_spans.push(Span::styled(format!(" · {}", source), Style::default().fg(theme().muted)));
+    }
+    lines.push(Line::from(status_spans));
+    if let Some(ref tool) = session.current_tool {
+        lines.push(Line::from(Span::styled(
+            format!("{}{}", glyph("⚙ ", "> "), tool),
+            Style::default().fg(theme().accent),
+        )));
+    }
+    if session.snoozed {
+        lines.push(Line::from(Span::styled(
+            format!("{}スヌーズ中（通知を止めています）", glyph("🔕 ", "")),
+            Style::default().fg(theme().muted),
+        )));
+    }
+    if let Some(percent) = session.context_percent() {
+        lines.push(Line::from(vec![
+            Span::raw(glyph("📚 ", "")),
+            Span::styled(format!("ctx {}", format_context_gauge(percent)), Style::default().fg(context_color(percent))),
+            Span::styled(
+                format!("（{}）", format_tokens(session.context_tokens.unwrap_or_default())),
+                Style::default().fg(theme().muted),
+            ),
+        ]));
+    }
+    if let Some(budget) = session_budget(session)
+        && let Some(level) = budget.level
+    {
+        lines.push(Line::from(Span::styled(
+            format!("{} {}（{}）", level.icon(), level.label(), budget.amounts()),
+            Style::default().fg(budget_color(Some(level))),
+        )));
+    }
+    if let Some(avg) = approval_avg.filter(|&avg| waiting_longer_than(session, avg)) {
+        lines.push(Line::from(Span::styled(
+            format!("{}今日の平均（{}）より長く待っています", glyph("⏳ ", "! "), format_duration(avg)),
+            Style::default().fg(theme().danger),
+        )));
+    }
     lines.push(Line::from(""));
 
     // パス行
     lines.push(Line::from(vec![
-        Span::raw("📁 "),
-        Span::styled(cwd, Style::default().fg(Color::White)),
+        Span::raw(glyph("📁 ", "")),
+        Span::styled(cwd, Style::default().fg(theme().text)),
     ]));
 
     // メタ行
     let mut meta_parts = vec![];
     if let Some(ref branch) = session.git_branch {
-        meta_parts.push(format!("🔀 {}", branch));
+        meta_parts.push(format!("{}{}", glyph("🔀 ", "@"), branch));
+    }
+    if let Some(git) = session.git_status.as_ref().and_then(format_git_status) {
+        meta_parts.push(git);
     }
     if let Some(count) = session.message_count {
-        meta_parts.push(format!("📨 {}msg", count));
+        meta_parts.push(format!("{}{}msg", glyph("📨 ", ""), count));
     }
     if let Some(mem_kb) = session.memory_usage_kb {
-        let mem_mb = mem_kb / 1024;
-        if mem_mb >= 1024 {
-            meta_parts.push(format!("💾 {:.1}GB", mem_mb as f64 / 1024.0));
-        } else {
-            meta_parts.push(format!("💾 {}MB", mem_mb));
-        }
+        meta_parts.push(format!("{}{}", glyph("💾 ", ""), format_memory(mem_kb)));
+    }
+    if let Some(ref usage) = session.token_usage {
+        meta_parts.push(format!("{}{}", glyph("🪙 ", ""), format_token_usage(usage)));
+    }
+    if let Some(ref model) = session.model {
+        meta_parts.push(format!("{}{}", glyph("🧠 ", ""), model));
     }
     if let Some(ref modified) = session.modified {
-        meta_parts.push(format!("🕐 {}", format_relative_time(modified)));
+        let time = if absolute_time {
+            format_absolute_time(modified)
+        } else {
+            format_relative_time(modified)
+        };
+        meta_parts.push(format!("{}{}", glyph("🕐 ", ""), time));
     }
 
     if !meta_parts.is_empty() {
         lines.push(Line::from(Span::styled(
             meta_parts.join(" · "),
-            Style::default().fg(Color::DarkGray),
+            Style::default().fg(theme().muted),
         )));
     }
 
-    // 通知行
-    if let Some(ref msg) = session.notification_message {
+    // メモ・タグ
+    if let Some(note) = note {
+        let mut spans = vec![Span::raw(glyph("📝 ", "note: "))];
+        if let Some(ref text) = note.note {
+            spans.push(Span::styled(text.clone(), Style::default().fg(theme().text)));
+        }
+        for tag in &note.tags {
+            spans.push(Span::styled(format!(" #{}", tag), Style::default().fg(theme().accent)));
+        }
+        lines.push(Line::from(spans));
+    }
+
+    // 承認待ちの理由
+    if let Some(ref reason) = session.waiting_reason {
         lines.push(Line::from(""));
         lines.push(Line::from(vec![
-            Span::raw("⚠ "),
+            Span::raw(glyph("⚠ ", "! ")),
             Span::styled(
-                simplify_notification_message(msg),
+                reason.label(),
                 Style::default()
-                    .fg(Color::Yellow)
+                    .fg(theme().warning)
                     .add_modifier(Modifier::BOLD),
             ),
         ]));
@@ -274,42 +3596,111 @@ fn render_detail(f: &mut Frame, area: ratatui::layout::Rect, session: Option<&Se
     // Task
     if let Some(ref first_prompt) = session.first_prompt {
         lines.push(Line::from(""));
-        lines.push(Line::from(Span::styled(
-            "── Task ──────────────────────",
-            Style::default().fg(Color::DarkGray),
-        )));
+        lines.push(section_header("Task"));
         lines.push(Line::from(Span::styled(
             truncate_text(first_prompt, 100),
-            Style::default().fg(Color::Cyan),
+            Style::default().fg(theme().accent),
         )));
     }
 
+    // アシスタントの最後の発言
+    if let Some(ref message) = session.last_message {
+        lines.push(Line::from(""));
+        lines.push(section_header("Latest"));
+        lines.push(Line::from(Span::styled(truncate_text(message, 200), Style::default().fg(theme().text))));
+    }
+
+    // 実行中のサブエージェント
+    if !session.subagents.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(section_header(&format!("Subagents ({})", session.subagents.len())));
+        for subagent in &session.subagents {
+            let mut spans = vec![Span::raw(format!("{} ", glyph("└─", "`-")))];
+            if let Some(ref kind) = subagent.subagent_type {
+                spans.push(Span::styled(format!("[{}] ", kind), Style::default().fg(theme().muted)));
+            }
+            spans.push(Span::raw(truncate_text(&subagent.description, 60)));
+            let started = subagent.started.as_deref().and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok());
+            if let Some(started) = started {
+                spans.push(Span::styled(
+                    format!(" · {}", format_duration(elapsed_since(started.timestamp().max(0) as u64))),
+                    Style::default().fg(theme().muted),
+                ));
+            }
+            lines.push(Line::from(spans));
+        }
+    }
+
+    // 同じペインを指していた古いセッションファイル
+    if !session.pane_conflicts.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(section_header(&format!("Pane conflicts ({})", session.pane_conflicts.len())));
+        for conflict in &session.pane_conflicts {
+            lines.push(Line::from(vec![
+                Span::raw(format!("{} ", glyph("└─", "`-"))),
+                Span::styled(truncate_text(&conflict.session_id, 8), Style::default().fg(theme().warning)),
+                Span::styled(
+                    format!(
+                        " {} · {} · {}",
+                        format_cwd(&conflict.cwd),
+                        conflict.status.label(),
+                        format_duration(elapsed_since(conflict.updated))
+                    ),
+                    Style::default().fg(theme().muted),
+                ),
+            ]));
+        }
+    }
+
     // Summary
     if let Some(ref summary) = session.summary {
         lines.push(Line::from(""));
-        lines.push(Line::from(Span::styled(
-            "── Summary ───────────────────",
-            Style::default().fg(Color::DarkGray),
-        )));
+        lines.push(section_header("Summary"));
         lines.push(Line::from(Span::styled(
             truncate_text(summary, 150),
-            Style::default().fg(Color::White),
+            Style::default().fg(theme().text),
         )));
     }
 
-    let detail = Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title("Detail"));
+    let detail = Paragraph::new(lines).block(block().borders(Borders::ALL).title("Detail"));
     f.render_widget(detail, area);
 }
 
-pub fn run_tui(sessions: Vec<Session>) -> Result<Option<String>> {
+/// TUIを起動し、Enterで選ばれたセッションIDを返す
+///
+/// `auto_jump` を指定すると、承認待ちになったセッションへその待ち時間の後に自動でジャンプする。
+pub fn run_tui(sessions: Vec<Session>, config: &Config, auto_jump: Option<Duration>) -> Result<Option<String>> {
+    // キー設定の誤りは画面を切り替える前に報告する
+    let keys = KeyBindings::from_config(config)?;
+    let columns = table_columns(&config.table)?;
+    let local_state = load_state()?;
+
     // ターミナルのセットアップ
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let mut app = App::new(sessions);
+    // デーモンが起動していればその一覧を受け取る（リモートの取得・通知・記録もデーモンが行う）
+    let mut daemon = DaemonClient::connect().and_then(DaemonClient::subscribe).ok();
+    let mut remotes = RemotePoller::start(if daemon.is_some() { &[] } else { &config.remote });
+    let mut watcher = match daemon {
+        Some(_) => None,
+        None => SessionWatcher::start().inspect_err(|e| tracing::warn!("{:#}", e)).ok(),
+    };
+    let mut sessions = remotes.merge(sessions);
+    apply_cached_git_status(&mut sessions);
+    let mut app = App::new(sessions, local_state, keys, columns, config.clone(), auto_jump);
+    let mut alerts = AlertEngine::new(config.alerts.clone());
+    let mut tracker = StatusTracker::new();
+    tracker.update(&app.sessions);
+    // 記録できなくても監視は続ける
+    let mut store = EventStore::open()
+        .inspect_err(|e| tracing::warn!("{:#}", e))
+        .ok();
+    app.approval_avg = store.as_ref().and_then(average_latency_today).map(|(avg, _)| avg);
+    app.daemon_attached = daemon.is_some();
     let mut selected_session_id: Option<String> = None;
 
     loop {
@@ -317,47 +3708,411 @@ pub fn run_tui(sessions: Vec<Session>) -> Result<Option<String>> {
 
         // イベント処理（タイムアウト付き）
         if event::poll(Duration::from_millis(100))? {
-            if let Event::Key(KeyEvent { code, .. }) = event::read()? {
-                match code {
-                    KeyCode::Char('q') => {
-                        app.quit();
-                        break;
+            // 操作メニューで選んだ操作も通常のキー操作と同じように処理する
+            let action = match event::read()? {
+                // メモ入力中はキー入力をすべて入力欄に渡す
+                Event::Key(key) if app.note_input.is_some() => {
+                    app.handle_note_key(key);
+                    None
+                }
+                Event::Key(key) if app.send_input.is_some() => {
+                    app.handle_send_key(key);
+                    None
+                }
+                // 確認ダイアログ表示中はダイアログの操作だけを受け付ける
+                Event::Key(KeyEvent { code, .. }) if app.pending_action.is_some() => {
+                    app.handle_confirm_key(code);
+                    None
+                }
+                Event::Key(KeyEvent { code, .. }) if app.pending_jump.is_some() => {
+                    match code {
+                        KeyCode::Enter => app.jump_now(),
+                        KeyCode::Esc => app.pending_jump = None,
+                        _ => {}
                     }
-                    KeyCode::Down | KeyCode::Char('j') => {
-                        app.next();
+                    None
+                }
+                Event::Key(key) if app.pager.is_some() => {
+                    app.handle_pager_key(key);
+                    None
+                }
+                Event::Key(KeyEvent { code, .. }) if app.conflict_view.is_some() => {
+                    app.handle_conflict_key(code);
+                    None
+                }
+                Event::Key(key) if app.project_picker.is_some() => {
+                    app.handle_picker_key(key);
+                    None
+                }
+                Event::Key(key) if app.search.is_some() => {
+                    if let Some(session_id) = app.handle_search_key(key) {
+                        selected_session_id = Some(session_id);
+                        break;
                     }
-                    KeyCode::Up | KeyCode::Char('k') => {
-                        app.previous();
+                    None
+                }
+                Event::Key(key) if app.action_menu.is_some() => app.handle_menu_key(key),
+                // ヘルプ表示中はどのキーでも閉じる（終了キーは終了）
+                Event::Key(key)
+                    if app.show_help && app.keys.action_for(&key) != Some(Action::Quit) =>
+                {
+                    app.show_help = false;
+                    None
+                }
+                Event::Key(key) => app.keys.action_for(&key),
+                Event::Mouse(mouse) => {
+                    if let Some(idx) = app.handle_mouse(mouse)
+                        && app.tab == Tab::Sessions
+                        && app.ensure_pane_exists(idx)
+                        && !app.jump_remote(idx)
+                        && app.ensure_can_jump()
+                    {
+                        selected_session_id = app.sessions.get(idx).map(|s| s.session_id.clone());
+                        break;
                     }
-                    KeyCode::Enter => {
-                        if let Some(session) = app.selected_session() {
-                            selected_session_id = Some(session.session_id.clone());
-                            break;
-                        }
+                    None
+                }
+                _ => None,
+            };
+            match action {
+                Some(Action::Help) => {
+                    app.show_help = true;
+                }
+                Some(Action::ToggleTime) => {
+                    app.absolute_time = !app.absolute_time;
+                }
+                Some(Action::ToggleDetailed) if app.tab == Tab::Sessions => {
+                    app.detailed_list = !app.detailed_list;
+                }
+                Some(Action::ToggleFollow) if app.tab == Tab::Sessions => {
+                    app.follow_newest = !app.follow_newest;
+                    app.apply_filter();
+                }
+                Some(Action::ToggleOrphans) if app.tab == Tab::Sessions => {
+                    app.toggle_orphans();
+                }
+                Some(Action::EditNote) => {
+                    app.start_note_input();
+                }
+                Some(Action::FilterTag) if app.tab == Tab::Sessions => {
+                    app.cycle_tag_filter();
+                }
+                Some(Action::Search) => {
+                    app.start_search();
+                }
+                Some(Action::PaneConflicts) if app.tab == Tab::Sessions => {
+                    app.open_conflict_view();
+                }
+                Some(Action::ViewTranscript) => {
+                    app.open_transcript();
+                }
+                Some(Action::ViewDiff) if app.tab == Tab::Sessions => {
+                    app.open_diff();
+                }
+                Some(Action::Monitor) if app.tab == Tab::Sessions => {
+                    app.toggle_monitor();
+                }
+                Some(Action::OpenEditor) => {
+                    app.open_editor();
+                }
+                Some(Action::SwitchTab) => {
+                    app.toggle_tab();
+                }
+                Some(Action::CopyId) => {
+                    app.copy_selected(CopyField::Id);
+                }
+                Some(Action::CopyCwd) => {
+                    app.copy_selected(CopyField::Cwd);
+                }
+                Some(Action::CopyResumeCommand) => {
+                    app.copy_selected(CopyField::ResumeCommand);
+                }
+                Some(Action::CopyPaneId) => {
+                    app.copy_selected(CopyField::PaneId);
+                }
+                Some(Action::Menu) => {
+                    app.open_action_menu();
+                }
+                Some(Action::NewSession) => {
+                    app.new_session_here();
+                }
+                Some(Action::Resume) => match app.tab {
+                    Tab::Sessions => app.resume_selected_session(),
+                    Tab::History => app.resume_selected_history(),
+                    Tab::Projects => app.resume_selected_project(),
+                },
+                Some(Action::SendText) if app.tab == Tab::Sessions => {
+                    app.start_send_input();
+                }
+                Some(Action::Interrupt) if app.tab == Tab::Sessions => {
+                    app.request_interrupt();
+                }
+                Some(Action::Approve) if app.tab == Tab::Sessions => {
+                    app.request_answer(true);
+                }
+                Some(Action::Deny) if app.tab == Tab::Sessions => {
+                    app.request_answer(false);
+                }
+                Some(Action::MarkStopped) if app.tab == Tab::Sessions => {
+                    app.mark_stopped();
+                }
+                Some(Action::Snooze) if app.tab == Tab::Sessions => {
+                    app.toggle_snooze();
+                }
+                Some(Action::ToggleMark) if app.tab == Tab::Sessions => {
+                    app.toggle_mark();
+                }
+                Some(Action::ClearMarks) => {
+                    app.marked.clear();
+                }
+                Some(Action::CycleMarked) if app.tab == Tab::Sessions => {
+                    app.cycle_marked();
+                }
+                Some(Action::CleanMarked) if app.tab == Tab::Sessions => {
+                    app.request_clean();
+                }
+                Some(Action::ExportMarked) if app.tab == Tab::Sessions => {
+                    app.export_marked();
+                }
+                Some(Action::Pin) if app.tab == Tab::Sessions => {
+                    app.toggle_pin();
+                }
+                Some(Action::Quit) => {
+                    app.quit();
+                    break;
+                }
+                Some(Action::Down) => {
+                    app.next();
+                }
+                Some(Action::Up) => {
+                    app.previous();
+                }
+                Some(Action::PageDown) => {
+                    app.move_page(true);
+                }
+                Some(Action::PageUp) => {
+                    app.move_page(false);
+                }
+                Some(Action::First) => {
+                    app.select_first();
+                }
+                Some(Action::Last) => {
+                    app.select_last();
+                }
+                Some(Action::Jump) if app.tab == Tab::Projects => {
+                    app.open_project_history();
+                }
+                Some(Action::Jump) if app.tab == Tab::Sessions => {
+                    if app.state.selected().is_some_and(|idx| !app.ensure_pane_exists(idx)) {
+                        // ペインがなくなったセッションにはジャンプしない
+                    } else if app.state.selected().is_some_and(|idx| app.jump_remote(idx)) {
+                        // リモートはTUIを閉じずにジャンプ済み
+                    } else if app.ensure_can_jump()
+                        && let Some(session) = app.selected_session()
+                    {
+                        selected_session_id = Some(session.session_id.clone());
+                        break;
                     }
-                    _ => {}
                 }
+                _ => {}
             }
         }
 
-        // 1秒ごとに自動更新
-        if app.last_update.elapsed() >= Duration::from_secs(1) {
-            match load_and_filter_sessions() {
-                Ok(new_sessions) => {
-                    app.update_sessions(new_sessions);
+        app.tick_auto_jump();
+        app.tick_monitor();
+
+        if daemon.as_ref().is_some_and(|d| !d.is_connected()) {
+            daemon = None;
+            app.daemon_attached = false;
+            remotes = RemotePoller::start(&config.remote);
+            watcher = SessionWatcher::start().ok();
+            app.set_status_message("⚠ デーモンとの接続が切れました（セッションファイルの読み込みに切り替えます）");
+        }
+
+        // デーモンからの一覧が届くかセッションファイルが変わったら更新する
+        let refresh = match daemon {
+            Some(ref subscription) => subscription
+                .take()
+                .or_else(|| (app.last_update.elapsed() >= Duration::from_secs(1)).then(|| app.all_sessions.clone()))
+                .map(Ok),
+            None => {
+                // ファイルの変化を監視できていれば、変化のない間はペインの終了などを拾うために間隔をあけて読み直す
+                let reload_after = if watcher.is_some() {
+                    WATCHED_RELOAD
+                } else {
+                    Duration::from_secs(1)
+                };
+                let changed = watcher.as_ref().is_some_and(SessionWatcher::changed);
+                (changed || app.last_update.elapsed() >= reload_after)
+                    .then(|| load_active_sessions().map(|new_sessions| remotes.merge(new_sessions)))
+            }
+        };
+        match refresh {
+            Some(Ok(mut new_sessions)) => {
+                apply_cached_git_status(&mut new_sessions);
+                // CLIで付けたタグやスヌーズも反映する
+                if let Ok(local_state) = load_state() {
+                    app.local_state = local_state;
+                }
+                app.local_state.apply_snoozes(&mut new_sessions);
+                let attached = daemon.is_some();
+                if !attached && let Some(message) = alerts.check(&new_sessions).pop() {
+                    app.set_status_message(format!("🔔 {}", message));
                 }
-                Err(_) => {
-                    // エラー時は更新をスキップ（次回リトライ）
-                    app.last_update = Instant::now();
+                if !attached && let Some(message) = check_budgets(&new_sessions).pop() {
+                    app.set_status_message(format!("💸 {}", message));
                 }
+                // デーモンに接続中も今日の合計はヘッダーに出す
+                refresh_daily_cost();
+                let transitions = tracker.update(&new_sessions);
+                if !transitions.is_empty()
+                    && let Some(ref mut store) = store
+                {
+                    if !attached && let Err(e) = store.record_transitions(&transitions) {
+                        tracing::warn!("{:#}", e);
+                    }
+                    app.approval_avg = average_latency_today(store).map(|(avg, _)| avg);
+                }
+                ring_on_new_waiting(&config.bell, &transitions);
+                if !attached {
+                    dispatch_webhooks(config.webhook.items(), &transitions);
+                    dispatch_push(config.push.items(), &transitions);
+                    dispatch_email(config.email.as_ref(), &new_sessions, &transitions);
+                    run_triggers(&transitions);
+                }
+                app.refresh_orphans();
+                app.update_sessions(new_sessions);
+                app.schedule_auto_jump(&transitions);
+            }
+            Some(Err(_)) => {
+                // エラー時は更新をスキップ（次回リトライ）
+                app.last_update = Instant::now();
             }
+            None => {}
         }
     }
 
     // ターミナルのクリーンアップ
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen, DisableMouseCapture)?;
     terminal.show_cursor()?;
 
     Ok(selected_session_id)
 }
+
+/// 統計のグラフ表示（`stats --tui`）
+fn render_stats(f: &mut Frame, stats: &Stats) {
+    let chunks = Layout::default()
+        .constraints([
+            Constraint::Length(10), // 日ごとのセッション数
+            Constraint::Min(0),     // プロジェクト・ステータス
+            Constraint::Length(1),  // フッター
+        ])
+        .split(f.area());
+
+    let day_bars: Vec<Bar> = stats
+        .per_day
+        .iter()
+        .map(|&(date, count)| {
+            Bar::default()
+                .value(count as u64)
+                .label(Line::from(date.format("%m/%d").to_string()))
+        })
+        .collect();
+    let per_day = BarChart::default()
+        .block(block().borders(Borders::ALL).title(format!(
+            "日ごとのセッション数（直近{}日 · {}セッション）",
+            stats.per_day.len(),
+            stats.total_sessions
+        )))
+        .data(BarGroup::default().bars(&day_bars))
+        .bar_width(5)
+        .bar_gap(1)
+        .bar_style(Style::default().fg(theme().accent))
+        .value_style(Style::default().fg(theme().text).add_modifier(Modifier::REVERSED));
+    f.render_widget(per_day, chunks[0]);
+
+    let body = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+        .split(chunks[1]);
+
+    let project_bars: Vec<Bar> = stats
+        .projects
+        .iter()
+        .map(|p| {
+            Bar::default()
+                .value(p.sessions as u64)
+                .label(Line::from(truncate_text(format_dir_name(&p.project), 16)))
+                .text_value(format!("{}件 · {}", p.sessions, format_duration(p.total_secs)))
+                .style(Style::default().fg(project_color(&p.project)))
+        })
+        .collect();
+    let projects = BarChart::default()
+        .block(block().borders(Borders::ALL).title("プロジェクト"))
+        .direction(Direction::Horizontal)
+        .data(BarGroup::default().bars(&project_bars))
+        .bar_width(1)
+        .bar_gap(1)
+        .value_style(Style::default().fg(theme().text));
+    f.render_widget(projects, body[0]);
+
+    let mut lines = vec![section_header("現在のステータス")];
+    if stats.statuses.is_empty() {
+        lines.push(Line::styled("アクティブなセッションはありません", Style::default().fg(theme().muted)));
+    }
+    for (status, count) in &stats.statuses {
+        lines.push(Line::from(vec![
+            Span::raw(format!("{} ", status.icon())),
+            Span::styled(format!("{:<8}", status.label()), Style::default().fg(status.color())),
+            Span::raw(format!(" {}", count)),
+        ]));
+    }
+    if let Some(avg) = stats.waiting_avg {
+        lines.push(Line::from(vec![
+            Span::styled("承認待ちの平均 ", Style::default().fg(theme().muted)),
+            Span::styled(format_duration(avg), Style::default().fg(theme().waiting)),
+        ]));
+    }
+    lines.push(Line::from(""));
+    lines.push(section_header("長いセッション"));
+    for entry in &stats.longest {
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("{:>7} ", format_duration(entry.duration_secs().unwrap_or(0))),
+                Style::default().fg(theme().accent),
+            ),
+            Span::styled(
+                format_dir_name(&entry.project_path).to_string(),
+                Style::default().fg(project_color(&entry.project_path)),
+            ),
+        ]));
+    }
+    let side = Paragraph::new(lines).block(block().borders(Borders::ALL).title("概要"));
+    f.render_widget(side, body[1]);
+
+    let footer = Paragraph::new("q / Esc: 閉じる").style(Style::default().fg(theme().subtle));
+    f.render_widget(footer, chunks[2]);
+}
+
+/// 統計をグラフで表示し、q / Esc で閉じる
+pub fn run_stats_view(stats: &Stats) -> Result<()> {
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+
+    loop {
+        terminal.draw(|f| render_stats(f, stats))?;
+        if let Event::Key(key) = event::read()?
+            && matches!(key.code, KeyCode::Char('q') | KeyCode::Esc)
+        {
+            break;
+        }
+    }
+
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use anyhow::{Context, Result};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::Path;
+use std::sync::mpsc::{channel, Receiver};
+use std::time::Duration;
+
+use crate::session::{get_projects_dir, get_sessions_dir};
+
+/// セッションの表示に関わるファイルか（トランスクリプトなどの書き込みは無視する）
+fn is_relevant(event: &Event, sessions_dir: &Path) -> bool {
+    if matches!(event.kind, EventKind::Access(_)) {
+        return false;
+    }
+    event.paths.iter().any(|path| {
+        path.starts_with(sessions_dir) || path.file_name().is_some_and(|name| name == "sessions-index.json")
+    })
+}
+
+/// ~/.claude/sessions と各プロジェクトの sessions-index.json の変化を監視する
+pub struct SessionWatcher {
+    // dropすると監視が止まるので持っておく
+    _watcher: RecommendedWatcher,
+    rx: Receiver<()>,
+}
+
+impl SessionWatcher {
+    pub fn start() -> Result<Self> {
+        let sessions_dir = get_sessions_dir()?;
+        let projects_dir = get_projects_dir()?;
+        let (tx, rx) = channel();
+        let filter_dir = sessions_dir.clone();
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+            if event.is_ok_and(|event| is_relevant(&event, &filter_dir)) {
+                let _ = tx.send(());
+            }
+        })
+        .context("ファイルの監視を開始できません")?;
+        watcher
+            .watch(&sessions_dir, RecursiveMode::NonRecursive)
+            .with_context(|| format!("監視できません: {:?}", sessions_dir))?;
+        // プロジェクトのディレクトリはまだ無いこともある（セッション一覧の監視だけで続ける）
+        if let Err(e) = watcher.watch(&projects_dir, RecursiveMode::Recursive) {
+            tracing::debug!("{:?} を監視できません: {}", projects_dir, e);
+        }
+        Ok(Self { _watcher: watcher, rx })
+    }
+
+    /// 前回から変化があったか（溜まった通知はまとめて読み捨てる）
+    pub fn changed(&self) -> bool {
+        let mut changed = false;
+        while self.rx.try_recv().is_ok() {
+            changed = true;
+        }
+        changed
+    }
+
+    /// 変化があるか timeout が過ぎるまで待つ
+    pub fn wait(&self, timeout: Duration) {
+        if self.rx.recv_timeout(timeout).is_ok() {
+            // 一時ファイルへの書き込みとrenameなど続けて届く通知をまとめる
+            self.changed();
+        }
+    }
+}
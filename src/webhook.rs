@@ -0,0 +1,175 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::thread;
+
+use crate::config::{WebhookConfig, WebhookFormat};
+use crate::display::{format_cwd, format_dir_name};
+use crate::quiet::should_notify;
+use crate::reason::WaitingReason;
+use crate::session::Session;
+use crate::status::SessionStatus;
+use crate::transitions::Transition;
+
+fn build_payload(transition: &Transition) -> serde_json::Value {
+    let session = &transition.session;
+    serde_json::json!({
+        "event": "status_change",
+        "session_id": session.session_id,
+        "cwd": session.cwd,
+        "pane_id": session.pane_id,
+        "from": transition.old_label(),
+        "to": transition.new_label(),
+        "summary": session.summary,
+        "notification_message": session.notification_message,
+        "waiting_reason": session.waiting_reason.as_ref().map(|r| r.name()),
+        "waiting_tool": session.waiting_reason.as_ref().and_then(|r| r.tool()),
+        "timestamp": transition.timestamp,
+    })
+}
+
+/// ステータスの表示（消滅したセッションは「終了」）
+fn status_text(status: Option<&SessionStatus>) -> (String, &str) {
+    match status {
+        Some(status) => (status.icon().to_string(), status.label()),
+        None => (String::new(), "終了"),
+    }
+}
+
+/// 見出し（"🟡 承認待ち · proj"）
+pub fn title(transition: &Transition) -> String {
+    let (icon, label) = status_text(transition.to.as_ref());
+    format!("{} {} · {}", icon, label, format_dir_name(&transition.session.cwd)).trim_start().to_string()
+}
+
+/// 承認待ちなら理由、それ以外は要約
+pub fn detail(session: &Session) -> Option<String> {
+    session
+        .waiting_reason
+        .as_ref()
+        .map(WaitingReason::label)
+        .or_else(|| session.summary.clone())
+}
+
+pub fn expand_link(template: &str, session: &Session) -> String {
+    template
+        .replace("{session_id}", &session.session_id)
+        .replace("{pane_id}", &session.pane_id)
+        .replace("{host}", session.host.as_deref().unwrap_or(""))
+}
+
+/// SlackのBlock Kit形式（textは通知のプレビュー用）
+fn build_slack_payload(transition: &Transition, link: Option<&str>) -> serde_json::Value {
+    let session = &transition.session;
+    let mut fields = vec![
+        serde_json::json!({"type": "mrkdwn", "text": format!("*プロジェクト*\n`{}`", format_cwd(&session.cwd))}),
+        serde_json::json!({"type": "mrkdwn", "text": format!("*変化*\n{} → {}", transition.old_label(), transition.new_label())}),
+    ];
+    if let Some(detail) = detail(session) {
+        fields.push(serde_json::json!({"type": "mrkdwn", "text": format!("*詳細*\n{}", detail)}));
+    }
+    let mut context = format!("session `{}` · pane {}", session.session_id, session.pane_id);
+    if let Some(ref host) = session.host {
+        context.push_str(&format!(" · {}", host));
+    }
+
+    let mut blocks = vec![
+        serde_json::json!({"type": "header", "text": {"type": "plain_text", "text": title(transition)}}),
+        serde_json::json!({"type": "section", "fields": fields}),
+        serde_json::json!({"type": "context", "elements": [{"type": "mrkdwn", "text": context}]}),
+    ];
+    if let Some(url) = link {
+        blocks.push(serde_json::json!({
+            "type": "actions",
+            "elements": [{"type": "button", "text": {"type": "plain_text", "text": "開く"}, "url": url}],
+        }));
+    }
+    serde_json::json!({
+        "text": format!("{}: {}", title(transition), format_cwd(&session.cwd)),
+        "blocks": blocks,
+    })
+}
+
+/// embedの左端の色（ステータスごと）
+fn discord_color(status: Option<&SessionStatus>) -> u32 {
+    match status {
+        Some(SessionStatus::Active) => 0x2ecc71,
+        Some(SessionStatus::Waiting) => 0xf1c40f,
+        Some(SessionStatus::Stopped) => 0x95a5a6,
+        _ => 0x7f8c8d,
+    }
+}
+
+/// Discordのembed形式
+fn build_discord_payload(transition: &Transition, link: Option<&str>) -> serde_json::Value {
+    let session = &transition.session;
+    let mut fields = vec![
+        serde_json::json!({"name": "プロジェクト", "value": format!("`{}`", format_cwd(&session.cwd)), "inline": true}),
+        serde_json::json!({"name": "変化", "value": format!("{} → {}", transition.old_label(), transition.new_label()), "inline": true}),
+    ];
+    if let Some(ref host) = session.host {
+        fields.push(serde_json::json!({"name": "ホスト", "value": host, "inline": true}));
+    }
+    let mut embed = serde_json::json!({
+        "title": title(transition),
+        "color": discord_color(transition.to.as_ref()),
+        "fields": fields,
+        "footer": {"text": format!("session {} · pane {}", session.session_id, session.pane_id)},
+    });
+    if let Some(detail) = detail(session) {
+        embed["description"] = detail.into();
+    }
+    if let Some(url) = link {
+        embed["url"] = url.into();
+    }
+    if let Some(time) = chrono::DateTime::from_timestamp(transition.timestamp as i64, 0) {
+        embed["timestamp"] = time.to_rfc3339().into();
+    }
+    serde_json::json!({"embeds": [embed]})
+}
+
+/// Webhookの形式に合わせたJSON
+fn payload_for(webhook: &WebhookConfig, transition: &Transition) -> serde_json::Value {
+    let link = webhook.link.as_deref().map(|template| expand_link(template, &transition.session));
+    match webhook.format {
+        WebhookFormat::Json => build_payload(transition),
+        WebhookFormat::Slack => build_slack_payload(transition, link.as_deref()),
+        WebhookFormat::Discord => build_discord_payload(transition, link.as_deref()),
+    }
+}
+
+/// curlでJSONをPOSTする（完了はバックグラウンドで待つ）
+pub fn post_json(url: &str, headers: &[String], payload: &serde_json::Value) {
+    let url = url.to_string();
+    let headers: Vec<String> = headers.iter().flat_map(|h| ["-H".to_string(), h.clone()]).collect();
+    let body = payload.to_string();
+
+    thread::spawn(move || {
+        let Ok(mut child) = Command::new("curl")
+            .args(["-sS", "-m", "10", "-X", "POST"])
+            .args(["-H", "Content-Type: application/json"])
+            .args(&headers)
+            .args(["--data-binary", "@-", &url])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        else {
+            return;
+        };
+        if let Some(mut stdin) = child.stdin.take() {
+            let _ = stdin.write_all(body.as_bytes());
+        }
+        let _ = child.wait();
+    });
+}
+
+/// 設定された遷移に一致するステータス変化をWebhookに送信する
+pub fn dispatch_webhooks(webhooks: &[WebhookConfig], transitions: &[Transition]) {
+    for webhook in webhooks {
+        for transition in transitions.iter().filter(|t| should_notify(&t.session)) {
+            if webhook.on.iter().any(|pattern| transition.matches(pattern)) {
+                post_json(&webhook.url, &[], &payload_for(webhook, transition));
+            }
+        }
+    }
+}
@@ -1,10 +1,136 @@
 use anyhow::{anyhow, Context, Result};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::OnceLock;
 
-pub fn jump_to_pane(pane_id: &str) -> Result<()> {
-    let wezterm = "/mnt/c/Program Files/WezTerm/wezterm.exe";
+/// WSLから見たWindows版WezTermのインストール先
+const WSL_WEZTERM: &str = "/mnt/c/Program Files/WezTerm/wezterm.exe";
 
-    let status = Command::new(wezterm)
+/// PATHの中から実行ファイルを探す
+fn find_in_path(names: &[&str]) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path)
+        .flat_map(|dir| names.iter().map(move |name| dir.join(name)))
+        .find(|candidate| candidate.is_file())
+}
+
+/// レジストリのApp Pathsに登録されたwezterm.exe（Windowsのみ）
+fn find_in_registry() -> Option<PathBuf> {
+    let output = Command::new("reg")
+        .args([
+            "query",
+            r"HKLM\SOFTWARE\Microsoft\Windows\CurrentVersion\App Paths\wezterm.exe",
+            "/ve",
+        ])
+        .output()
+        .ok()?;
+    // "    (既定)    REG_SZ    C:\Program Files\WezTerm\wezterm.exe"
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| line.split_once("REG_SZ"))
+        .map(|(_, path)| PathBuf::from(path.trim()))
+        .filter(|path| path.is_file())
+}
+
+/// wezterm.exe の場所
+///
+/// Windows: PATH → レジストリ → %ProgramFiles%\WezTerm、
+/// それ以外: WSLから見たWindows版 → PATHのwezterm / wezterm.exe。見つからなければ "wezterm"。
+fn wezterm() -> &'static Path {
+    static WEZTERM: OnceLock<PathBuf> = OnceLock::new();
+    WEZTERM.get_or_init(|| {
+        let found = if cfg!(windows) {
+            find_in_path(&["wezterm.exe"])
+                .or_else(find_in_registry)
+                .or_else(|| {
+                    let dir = std::env::var_os("ProgramFiles")?;
+                    Some(PathBuf::from(dir).join("WezTerm").join("wezterm.exe")).filter(|path| path.is_file())
+                })
+        } else {
+            Some(PathBuf::from(WSL_WEZTERM))
+                .filter(|path| path.is_file())
+                .or_else(|| find_in_path(&["wezterm", "wezterm.exe"]))
+        };
+        let path = found.unwrap_or_else(|| PathBuf::from("wezterm"));
+        tracing::debug!(?path, "WezTermの実行ファイル");
+        path
+    })
+}
+
+/// `wezterm cli list` の結果（ペインごとのJSON）
+fn cli_list() -> Result<Vec<serde_json::Value>> {
+    tracing::debug!("wezterm cli list");
+    let output = Command::new(wezterm())
+        .args(["cli", "list", "--format", "json"])
+        .output()
+        .context("WezTermのペイン一覧取得に失敗")?;
+
+    if !output.status.success() {
+        return Err(anyhow!("WezTerm cliコマンドが失敗しました"));
+    }
+
+    let json_str = String::from_utf8(output.stdout)
+        .context("WezTerm出力のUTF-8変換に失敗")?;
+
+    serde_json::from_str(&json_str).context("WezTerm JSON解析に失敗")
+}
+
+/// `wezterm cli list` から pane_id → フォアグラウンドプロセスのPID を取得
+pub fn list_panes() -> Result<HashMap<String, Option<u32>>> {
+    let panes = cli_list()?;
+
+    let map = panes
+        .iter()
+        .filter_map(|pane| {
+            let pane_id = pane["pane_id"].as_u64()?;
+            let pid = pane["foreground_process_id"].as_u64().map(|pid| pid as u32);
+            Some((pane_id.to_string(), pid))
+        })
+        .collect::<HashMap<_, _>>();
+    tracing::debug!(count = map.len(), "WezTermのペインを取得");
+    Ok(map)
+}
+
+/// ペインを含むタブとウィンドウ
+struct PaneLocation {
+    tab_id: u64,
+    window_id: u64,
+    window_title: Option<String>,
+}
+
+fn find_pane(pane_id: &str) -> Result<Option<PaneLocation>> {
+    let location = cli_list()?.iter().find_map(|pane| {
+        if pane["pane_id"].as_u64()?.to_string() != pane_id {
+            return None;
+        }
+        Some(PaneLocation {
+            tab_id: pane["tab_id"].as_u64()?,
+            window_id: pane["window_id"].as_u64()?,
+            window_title: pane["window_title"].as_str().map(str::to_string),
+        })
+    });
+    Ok(location)
+}
+
+/// ペインを含むタブを選び、ペインをアクティブにしてウィンドウを前面に出す
+///
+/// 別のウィンドウやタブのペインは `activate-pane` だけでは前面に出ないことがある。
+pub fn activate_pane(pane_id: &str) -> Result<()> {
+    let location = find_pane(pane_id)?
+        .ok_or_else(|| anyhow!("WezTermにpane {}が見つかりません", pane_id))?;
+
+    tracing::debug!(pane_id, tab_id = location.tab_id, "wezterm cli activate-tab");
+    let status = Command::new(wezterm())
+        .args(["cli", "activate-tab", "--tab-id", &location.tab_id.to_string()])
+        .status()
+        .context("WezTermコマンドの実行に失敗")?;
+    if !status.success() {
+        return Err(anyhow!("WezTermのtab {}の選択に失敗しました", location.tab_id));
+    }
+
+    tracing::debug!(pane_id, "wezterm cli activate-pane");
+    let status = Command::new(wezterm())
         .args(["cli", "activate-pane", "--pane-id", pane_id])
         .status()
         .context("WezTermコマンドの実行に失敗")?;
@@ -13,6 +139,82 @@ pub fn jump_to_pane(pane_id: &str) -> Result<()> {
         return Err(anyhow!("WezTermのpane {}へのジャンプに失敗しました", pane_id));
     }
 
-    println!("✅ Pane {} にジャンプしました", pane_id);
+    // ウィンドウを前面に出せなくてもペインの切り替えはできている
+    if let Some(ref title) = location.window_title
+        && let Err(e) = raise_window(title)
+    {
+        tracing::debug!(window_id = location.window_id, "ウィンドウを前面に出せません: {:#}", e);
+    }
+    Ok(())
+}
+
+/// タイトルでWezTermのウィンドウを探して前面に出す（WSLからpowershell.exe経由）
+fn raise_window(title: &str) -> Result<()> {
+    tracing::debug!(title, "powershell.exe AppActivate");
+    let script = format!(
+        "if (-not (New-Object -ComObject WScript.Shell).AppActivate('{}')) {{ exit 1 }}",
+        title.replace('\'', "''")
+    );
+    let status = Command::new("powershell.exe")
+        .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+        .status()
+        .context("powershell.exeの実行に失敗")?;
+
+    if !status.success() {
+        return Err(anyhow!("ウィンドウ \"{}\" が見つかりません", title));
+    }
+    Ok(())
+}
+
+/// 指定ディレクトリで新しいペインを開き、コマンドを実行する（新しいpane_idを返す）
+pub fn spawn_in_cwd(cwd: &str, command: &[&str]) -> Result<String> {
+    tracing::debug!(cwd, ?command, "wezterm cli spawn");
+    let output = Command::new(wezterm())
+        .args(["cli", "spawn", "--cwd", cwd, "--"])
+        .args(command)
+        .output()
+        .context("WezTermコマンドの実行に失敗")?;
+
+    if !output.status.success() {
+        return Err(anyhow!("WezTermでのペイン作成に失敗しました"));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// ペインに表示されている内容（エスケープシーケンスなしのテキスト）
+pub fn get_text(pane_id: &str) -> Result<String> {
+    tracing::debug!(pane_id, "wezterm cli get-text");
+    let output = Command::new(wezterm())
+        .args(["cli", "get-text", "--pane-id", pane_id])
+        .output()
+        .context("WezTermコマンドの実行に失敗")?;
+
+    if !output.status.success() {
+        return Err(anyhow!("WezTermのpane {}の内容を取得できません", pane_id));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// ペインにテキストを送信する（no_pasteならブラケットペーストを使わずキー入力として送る）
+pub fn send_text(pane_id: &str, text: &str, no_paste: bool) -> Result<()> {
+    tracing::debug!(pane_id, ?text, no_paste, "wezterm cli send-text");
+    let mut cmd = Command::new(wezterm());
+    cmd.args(["cli", "send-text", "--pane-id", pane_id]);
+    if no_paste {
+        cmd.arg("--no-paste");
+    }
+
+    // "-y" や "--help" のように - で始まるテキストをオプションとして解釈させない
+    let status = cmd
+        .arg("--")
+        .arg(text)
+        .status()
+        .context("WezTermコマンドの実行に失敗")?;
+
+    if !status.success() {
+        return Err(anyhow!("WezTermのpane {}へのテキスト送信に失敗しました", pane_id));
+    }
     Ok(())
 }
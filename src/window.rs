@@ -0,0 +1,131 @@
+use anyhow::{anyhow, Context, Result};
+use std::process::Command;
+use std::sync::OnceLock;
+
+use crate::compositor::{hyprland_focus, sway_focus};
+use crate::config::WindowConfig;
+use crate::display::format_dir_name;
+
+// CLIを持たない端末（Alacritty、foot、GNOME Terminalなど）向けに、
+// ウィンドウマネージャのツール（Hyprland・swayはIPC）でタイトルが一致するウィンドウを前面に出す。
+// ペインを区別できないので、フックはペインIDとしてセッションのcwdを書き込む。
+
+static CONFIG: OnceLock<WindowConfig> = OnceLock::new();
+
+/// 起動時に [window] の設定を反映する（以降は変更しない）
+pub fn init_window_config(config: &WindowConfig) {
+    let _ = CONFIG.set(config.clone());
+}
+
+/// ウィンドウを操作するツール
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tool {
+    Wmctrl,
+    Xdotool,
+    Hyprland,
+    Sway,
+}
+
+impl Tool {
+    fn parse(name: &str) -> Result<Tool> {
+        match name {
+            "wmctrl" => Ok(Tool::Wmctrl),
+            "xdotool" => Ok(Tool::Xdotool),
+            "hyprland" | "hyprctl" => Ok(Tool::Hyprland),
+            "sway" | "swaymsg" => Ok(Tool::Sway),
+            _ => Err(anyhow!(
+                "不明なウィンドウ操作ツール: {} ([window] tool、wmctrl, xdotool, hyprland, sway のいずれか)",
+                name
+            )),
+        }
+    }
+
+    /// 環境変数から動いているコンポジタを判定する（X11ならwmctrl）
+    fn detect() -> Tool {
+        if std::env::var_os("HYPRLAND_INSTANCE_SIGNATURE").is_some() {
+            Tool::Hyprland
+        } else if std::env::var_os("SWAYSOCK").is_some() {
+            Tool::Sway
+        } else {
+            Tool::Wmctrl
+        }
+    }
+}
+
+fn run(program: &str, args: &[&str]) -> Result<String> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .with_context(|| format!("{}の実行に失敗", program))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!("{} {} が失敗しました: {}", program, args.join(" "), stderr.trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// セッションのウィンドウのタイトルに含まれるはずの文字列（[window] title の {cwd} / {dir} を置換）
+fn expected_title(config: &WindowConfig, cwd: &str) -> String {
+    config
+        .title
+        .replace("{cwd}", cwd)
+        .replace("{dir}", format_dir_name(cwd))
+}
+
+/// `wmctrl -l` の出力からタイトルが一致するウィンドウID
+fn find_wmctrl(title: &str) -> Result<Option<String>> {
+    let output = run("wmctrl", &["-l"])?;
+    // "0x02400003  0 host タイトル"
+    Ok(output
+        .lines()
+        .filter(|line| line.contains(title))
+        .find_map(|line| line.split_whitespace().next())
+        .map(str::to_string))
+}
+
+/// ペインID（セッションのcwd）に対応するウィンドウを前面に出す
+pub fn focus_window(pane_id: &str) -> Result<()> {
+    let config = CONFIG.get().cloned().unwrap_or_default();
+    let tool = match config.tool {
+        Some(ref name) => Tool::parse(name)?,
+        None => Tool::detect(),
+    };
+    let title = expected_title(&config, pane_id);
+    tracing::debug!(?tool, %title, "タイトルでウィンドウを探す");
+    let not_found = || anyhow!("タイトルに「{}」を含むウィンドウが見つかりません", title);
+
+    match tool {
+        Tool::Wmctrl => {
+            let id = find_wmctrl(&title)?.ok_or_else(not_found)?;
+            run("wmctrl", &["-i", "-a", &id])?;
+        }
+        Tool::Xdotool => {
+            let output = run("xdotool", &["search", "--name", &regex_escape(&title)]).map_err(|_| not_found())?;
+            let id = output.lines().next().ok_or_else(not_found)?;
+            run("xdotool", &["windowactivate", id])?;
+        }
+        Tool::Hyprland => {
+            if !hyprland_focus(&title, config.move_to_current)? {
+                return Err(not_found());
+            }
+        }
+        Tool::Sway => {
+            if !sway_focus(&title, config.move_to_current)? {
+                return Err(not_found());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// xdotool search は正規表現で探すので記号をエスケープする
+fn regex_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        if "\\.+*?()|[]{}^$".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
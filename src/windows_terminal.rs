@@ -0,0 +1,44 @@
+use anyhow::{anyhow, Context, Result};
+use std::process::Command;
+
+// Windows Terminalにはペイン一覧を返すCLIが無いため、ウィンドウ単位の操作だけを行う。
+// フック側では $WT_SESSION を pane_id として書き込む想定。
+
+/// Windows Terminalのウィンドウを前面に出す
+pub fn focus_window() -> Result<()> {
+    tracing::debug!("powershell.exe AppActivate WindowsTerminal");
+    let script = "$p = Get-Process WindowsTerminal -ErrorAction Stop | Select-Object -First 1; \
+                  (New-Object -ComObject WScript.Shell).AppActivate($p.Id) | Out-Null";
+
+    let status = Command::new("powershell.exe")
+        .args(["-NoProfile", "-NonInteractive", "-Command", script])
+        .status()
+        .context("powershell.exeの実行に失敗")?;
+
+    if !status.success() {
+        return Err(anyhow!("Windows Terminalのウィンドウを前面に出せませんでした"));
+    }
+    Ok(())
+}
+
+/// 直近のウィンドウに新しいタブを開き、cwdでコマンドを実行する（WSLからは wsl.exe 経由でWSLのcwdに入る）
+pub fn spawn_in_cwd(cwd: &str, command: &[&str]) -> Result<String> {
+    tracing::debug!(cwd, ?command, "wt.exe new-tab");
+    let mut cmd = Command::new("wt.exe");
+    if cfg!(windows) {
+        cmd.args(["-w", "0", "new-tab", "-d", cwd]);
+    } else {
+        cmd.args(["-w", "0", "new-tab", "wsl.exe", "--cd", cwd, "--"]);
+    }
+    let status = cmd
+        .args(command)
+        .status()
+        .context("wt.exeの実行に失敗")?;
+
+    if !status.success() {
+        return Err(anyhow!("Windows Terminalでのタブ作成に失敗しました"));
+    }
+
+    // wt.exeは新しいタブの識別子を返さない
+    Ok(String::new())
+}